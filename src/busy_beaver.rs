@@ -0,0 +1,505 @@
+//! # Busy Beaver Module
+//!
+//! This module enumerates the Turing machines with a given number of states and symbols in tree
+//! normal form, and classifies each of them as halting, diverging or undecided, in order to find
+//! the busy beaver champions: the halting machines running for the most steps and leaving the
+//! most non-blank symbols on their tape.
+//!
+//! ## Main Structures
+//!
+//! - **Machine**: A single-tape machine on the symbols `0` (the blank) to `k - 1`, whose states
+//!   are named `A`, `B`, ..., with some transitions left undefined.
+//! - **Outcome**: Whether a machine halts, diverges (with a proof) or runs until the step limit.
+//! - **Record**: The outcome of the run of a machine, which is a line of the result table.
+//! - **Summary**: The champions and the number of machines of each outcome.
+//!
+//! ## Key Features
+//!
+//! - **Tree Normal Form**: The enumeration starts from the machine without transitions. A machine
+//!   reaching an undefined transition halts there, and its children define that transition in
+//!   every possible way, with states and symbols used in order of first appearance and a first
+//!   transition moving right. Only the machines reaching an undefined transition are generated,
+//!   which makes the enumeration of BB(4) a matter of seconds.
+//! - **Deciders**: The machines are run on the compiled engine with loop detection, which proves
+//!   most non-halting machines diverging.
+//! - **Resumable Table**: Every machine enumerated is appended to a table as soon as it is run;
+//!   an interrupted enumeration given the same table reads the machines already run from it
+//!   instead of running them again. The table starts with the maximum number of steps of its
+//!   runs, and is only resumed with the same one, since the undecided machines depend on it.
+//!
+//! The machines are written in the usual notation of the busy beaver literature: the transitions
+//! of each state, separated by `_`, each made of the symbol written, the direction and the next
+//! state, with `---` for the undefined ones (`1RB1LB_1LA---` for the champion of BB(2)). The
+//! halting transition writes `1`, and counts as a step.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::compiled_machine;
+use crate::computer;
+use crate::turing_machine;
+use std::io::Write;
+
+/// A Turing machine in tree normal form.
+///
+/// # Fields
+/// * `states` - Number of states, named `A`, `B`, ...
+/// * `symbols` - Number of symbols, named `0`, `1`, ..., `0` being the blank
+/// * `transitions` - For each state and symbol read, the symbol written, the direction and the
+///   next state, if defined
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Machine {
+    pub states: usize,
+    pub symbols: usize,
+    pub transitions: Vec<Option<(usize, turing_machine::Direction, usize)>>,
+}
+
+/// The outcome of the run of a machine.
+///
+/// # Variants
+/// * `Halt` - The machine reached the undefined transition of a state and a symbol read
+/// * `Diverges` - The machine was proved to never halt, with the proof
+/// * `Undecided` - The machine reached the step limit
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Halt { state: usize, symbol: usize },
+    Diverges(String),
+    Undecided,
+}
+
+/// The run of a machine, written as a line of the result table.
+///
+/// # Fields
+/// * `machine` - The machine, in the usual notation
+/// * `outcome` - How the run ended
+/// * `steps` - The steps made, the halting transition included
+/// * `ones` - The non-blank symbols on the tape at the end of the run
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub machine: String,
+    pub outcome: Outcome,
+    pub steps: usize,
+    pub ones: usize,
+}
+
+/// The result of an enumeration.
+///
+/// # Fields
+/// * `halting` - Number of halting machines
+/// * `diverging` - Number of machines proved to diverge
+/// * `undecided` - Number of machines reaching the step limit
+/// * `max_steps` - The most steps made by a halting machine, with the machine
+/// * `max_ones` - The most non-blank symbols left by a halting machine, with the machine
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub halting: usize,
+    pub diverging: usize,
+    pub undecided: usize,
+    pub max_steps: (usize, String),
+    pub max_ones: (usize, String),
+}
+
+/// Returns the name of a state.
+fn state_name(state: usize) -> char {
+    (b'A' + state as u8) as char
+}
+
+impl Machine {
+    /// Creates a machine without transitions.
+    pub fn new(states: usize, symbols: usize) -> Machine {
+        Machine {
+            states,
+            symbols,
+            transitions: vec![None; states * symbols],
+        }
+    }
+
+    /// Converts the machine into a `TuringMachine`, which stops without transitions where the
+    /// machine halts.
+    pub fn to_turing_machine(&self) -> turing_machine::TuringMachine {
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = "0".to_string();
+        tm.initial_state = "A".to_string();
+        tm.states = (0..self.states)
+            .map(|q| state_name(q).to_string())
+            .collect();
+        tm.tape_alphabet = (0..self.symbols).map(|s| s.to_string()).collect();
+        tm.input_alphabet = tm.tape_alphabet[1..].to_vec();
+        for (index, transition) in self.transitions.iter().enumerate() {
+            if let Some((write, direction, next)) = transition {
+                tm.add_transition(
+                    state_name(index / self.symbols).to_string(),
+                    vec![(index % self.symbols).to_string()],
+                    state_name(*next).to_string(),
+                    vec![write.to_string()],
+                    vec![direction.clone()],
+                );
+            }
+        }
+        tm
+    }
+
+    /// Runs the machine on a blank tape, with loop detection.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_steps` - Maximum number of steps the run should make before stopping
+    ///
+    /// # Returns
+    ///
+    /// The `Record` of the run.
+    pub fn run(&self, max_steps: usize) -> Record {
+        let tape = vec!["0".to_string()];
+        let tm = self.to_turing_machine();
+        let machine = compiled_machine::CompiledMachine::new(&tm, &tape)
+            .expect("machines in tree normal form can always be compiled");
+        let result = machine.simulate(&tape, 0, max_steps, false, true);
+        let mut ones = result.tape.iter().filter(|symbol| *symbol != "0").count();
+        let outcome = match result.outcome {
+            computer::Outcome::Diverges(proof) => Outcome::Diverges(proof),
+            computer::Outcome::Stuck(state) => {
+                let state = (state.as_bytes()[0] - b'A') as usize;
+                let symbol: usize = result.tape[result.head].parse().unwrap_or(0);
+                // the halting transition writes a 1
+                if symbol == 0 {
+                    ones += 1;
+                }
+                Outcome::Halt { state, symbol }
+            }
+            _ => Outcome::Undecided,
+        };
+        let steps = result.steps;
+        Record {
+            machine: self.to_string(),
+            outcome,
+            steps,
+            ones,
+        }
+    }
+
+    /// Returns the machines defining the transition of a state and a symbol read in every way
+    /// allowed by the tree normal form.
+    ///
+    /// # Returns
+    ///
+    /// No machine if the transition is the last undefined one, since its children would never
+    /// halt, the children otherwise.
+    pub fn children(&self, state: usize, symbol: usize) -> Vec<Machine> {
+        let defined = self.transitions.iter().filter(|t| t.is_some()).count();
+        if defined + 1 == self.transitions.len() {
+            return Vec::new();
+        }
+        // the states and symbols used so far, the next one being allowed as well
+        let used_states = self
+            .transitions
+            .iter()
+            .flatten()
+            .map(|(_, _, next)| *next + 1)
+            .max()
+            .unwrap_or(1)
+            .max(state + 1);
+        let used_symbols = self
+            .transitions
+            .iter()
+            .flatten()
+            .map(|(write, _, _)| *write + 1)
+            .max()
+            .unwrap_or(1)
+            .max(symbol + 1);
+        let directions = if defined == 0 {
+            vec![turing_machine::Direction::Right]
+        } else {
+            vec![
+                turing_machine::Direction::Left,
+                turing_machine::Direction::Right,
+            ]
+        };
+        let mut children = Vec::new();
+        for write in 0..self.symbols.min(used_symbols + 1) {
+            for direction in directions.iter() {
+                for next in 0..self.states.min(used_states + 1) {
+                    let mut child = self.clone();
+                    child.transitions[state * self.symbols + symbol] =
+                        Some((write, direction.clone(), next));
+                    children.push(child);
+                }
+            }
+        }
+        children
+    }
+}
+
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (index, transition) in self.transitions.iter().enumerate() {
+            if index > 0 && index % self.symbols == 0 {
+                write!(f, "_")?;
+            }
+            match transition {
+                Some((write, direction, next)) => {
+                    let direction = match direction {
+                        turing_machine::Direction::Left => 'L',
+                        turing_machine::Direction::Right => 'R',
+                        turing_machine::Direction::Stay => 'S',
+                    };
+                    write!(f, "{}{}{}", write, direction, state_name(*next))?
+                }
+                None => write!(f, "---")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Record {
+    /// Formats the record as a line of the result table: the machine, the outcome, the steps,
+    /// the non-blank symbols and the halting transition or the proof, separated by tabs.
+    pub fn to_line(&self) -> String {
+        let (outcome, detail) = match &self.outcome {
+            Outcome::Halt { state, symbol } => {
+                ("halt", format!("{}{}", state_name(*state), symbol))
+            }
+            Outcome::Diverges(proof) => ("diverges", proof.clone()),
+            Outcome::Undecided => ("undecided", String::new()),
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.machine, outcome, self.steps, self.ones, detail
+        )
+    }
+
+    /// Parses a line of the result table.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Record)` - The record of the line
+    /// * `Err(String)` - An error message if the line is malformed
+    pub fn from_line(line: &str) -> Result<Record, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let error = || format!("malformed table line: '{}'", line);
+        if fields.len() != 5 {
+            return Err(error());
+        }
+        let outcome = match fields[1] {
+            "halt" => {
+                let mut detail = fields[4].chars();
+                let state = detail.next().ok_or_else(error)?;
+                Outcome::Halt {
+                    state: (state as u8).wrapping_sub(b'A') as usize,
+                    symbol: detail.as_str().parse().map_err(|_| error())?,
+                }
+            }
+            "diverges" => Outcome::Diverges(fields[4].to_string()),
+            "undecided" => Outcome::Undecided,
+            _ => return Err(error()),
+        };
+        Ok(Record {
+            machine: fields[0].to_string(),
+            outcome,
+            steps: fields[2].parse().map_err(|_| error())?,
+            ones: fields[3].parse().map_err(|_| error())?,
+        })
+    }
+}
+
+/// Enumerates the machines with a number of states and symbols in tree normal form, running
+/// each of them.
+///
+/// # Arguments
+///
+/// * `states` - Number of states, from 1 to 26
+/// * `symbols` - Number of symbols, from 2 to 10
+/// * `max_steps` - Maximum number of steps of each run
+/// * `table` - The file to which the records are appended, whose records are read first to
+///   resume an interrupted enumeration, if not empty. Its first line, `max_steps` and the
+///   maximum number of steps separated by a tab, is written when the table is created.
+///
+/// # Returns
+///
+/// * `Ok(Summary)` - The champions and the number of machines of each outcome
+/// * `Err(String)` - An error message if the arguments are out of range, the table cannot be
+///   read or written, or it was built with another maximum number of steps
+pub fn enumerate(
+    states: usize,
+    symbols: usize,
+    max_steps: usize,
+    table: &str,
+) -> Result<Summary, String> {
+    if !(1..=26).contains(&states) || !(2..=10).contains(&symbols) {
+        return Err("busy beavers need 1 to 26 states and 2 to 10 symbols".to_string());
+    }
+    let mut known = std::collections::HashMap::new();
+    let mut writer = None;
+    if !table.is_empty() {
+        let text = std::fs::read_to_string(table).unwrap_or_default();
+        let mut lines = text.lines().filter(|line| !line.is_empty());
+        let header = lines.next();
+        if let Some(header) = header {
+            let steps: usize = header
+                .strip_prefix("max_steps\t")
+                .and_then(|steps| steps.parse().ok())
+                .ok_or_else(|| format!("malformed table header: '{}'", header))?;
+            // the machines undecided within other steps could halt or be proved diverging
+            if steps != max_steps {
+                return Err(format!(
+                    "the table '{}' was built with at most {} steps, not {}",
+                    table, steps, max_steps
+                ));
+            }
+        }
+        for line in lines {
+            let record = Record::from_line(line)?;
+            known.insert(record.machine.clone(), record);
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(table)
+            .map_err(|error| format!("cannot open '{}': {}", table, error))?;
+        let mut file = std::io::BufWriter::new(file);
+        if header.is_none() {
+            writeln!(file, "max_steps\t{}", max_steps)
+                .map_err(|error| format!("cannot write '{}': {}", table, error))?;
+        }
+        writer = Some(file);
+    }
+
+    let mut summary = Summary::default();
+    let mut stack = vec![Machine::new(states, symbols)];
+    while let Some(machine) = stack.pop() {
+        let record = match known.remove(&machine.to_string()) {
+            Some(record) => record,
+            None => {
+                let record = machine.run(max_steps);
+                if let Some(writer) = writer.as_mut() {
+                    writeln!(writer, "{}", record.to_line())
+                        .map_err(|error| format!("cannot write '{}': {}", table, error))?;
+                }
+                record
+            }
+        };
+        match record.outcome {
+            Outcome::Halt { state, symbol } => {
+                summary.halting += 1;
+                if record.steps > summary.max_steps.0 {
+                    summary.max_steps = (record.steps, record.machine.clone());
+                }
+                if record.ones > summary.max_ones.0 {
+                    summary.max_ones = (record.ones, record.machine.clone());
+                }
+                stack.extend(machine.children(state, symbol).into_iter().rev());
+            }
+            Outcome::Diverges(_) => summary.diverging += 1,
+            Outcome::Undecided => summary.undecided += 1,
+        }
+    }
+    if let Some(mut writer) = writer {
+        writer
+            .flush()
+            .map_err(|error| format!("cannot write '{}': {}", table, error))?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine() {
+        let mut machine = Machine::new(2, 2);
+        assert_eq!(machine.to_string(), "------_------");
+        let record = machine.run(100);
+        assert_eq!(
+            record.outcome,
+            Outcome::Halt {
+                state: 0,
+                symbol: 0
+            }
+        );
+        assert_eq!((record.steps, record.ones), (1, 1));
+        let children = machine.children(0, 0);
+        assert_eq!(children.len(), 4);
+        assert_eq!(children[3].to_string(), "1RB---_------");
+
+        // the champion of BB(2)
+        machine.transitions = vec![
+            Some((1, turing_machine::Direction::Right, 1)),
+            Some((1, turing_machine::Direction::Left, 1)),
+            Some((1, turing_machine::Direction::Left, 0)),
+            None,
+        ];
+        assert_eq!(machine.to_string(), "1RB1LB_1LA---");
+        let record = machine.run(100);
+        assert_eq!(
+            record.outcome,
+            Outcome::Halt {
+                state: 1,
+                symbol: 1
+            }
+        );
+        assert_eq!((record.steps, record.ones), (6, 4));
+        assert!(machine.children(1, 1).is_empty());
+        assert_eq!(Record::from_line(&record.to_line()).unwrap(), record);
+        assert!(Record::from_line("1RB1LB_1LA---\thalt\t6").is_err());
+
+        machine.transitions[3] = Some((1, turing_machine::Direction::Right, 1));
+        let record = machine.run(100);
+        assert!(matches!(record.outcome, Outcome::Diverges(_)));
+        assert_eq!(Record::from_line(&record.to_line()).unwrap(), record);
+    }
+
+    #[test]
+    fn test_enumerate() {
+        let summary = enumerate(2, 2, 100, "").unwrap();
+        assert_eq!(summary.max_steps.0, 6);
+        assert_eq!(summary.max_ones.0, 4);
+        assert_eq!(summary.undecided, 0);
+
+        let summary = enumerate(3, 2, 100, "").unwrap();
+        assert_eq!(summary.max_steps.0, 21);
+        assert_eq!(summary.max_ones.0, 6);
+
+        assert!(enumerate(0, 2, 100, "").is_err());
+        assert!(enumerate(2, 1, 100, "").is_err());
+    }
+
+    #[test]
+    fn test_resume() {
+        let table = std::env::temp_dir().join(format!("bb_table_{}.tsv", std::process::id()));
+        let table = table.to_str().unwrap();
+        let _ = std::fs::remove_file(table);
+        let summary = enumerate(2, 2, 100, table).unwrap();
+        let lines = std::fs::read_to_string(table).unwrap().lines().count();
+        assert_eq!(
+            lines,
+            1 + summary.halting + summary.diverging + summary.undecided
+        );
+
+        // an interrupted enumeration only runs the machines missing from the table
+        let text = std::fs::read_to_string(table).unwrap();
+        let kept: Vec<&str> = text.lines().take(lines / 2).collect();
+        std::fs::write(table, kept.join("\n") + "\n").unwrap();
+        assert_eq!(enumerate(2, 2, 100, table).unwrap(), summary);
+        let text = std::fs::read_to_string(table).unwrap();
+        assert_eq!(text.lines().count(), lines);
+        assert_eq!(enumerate(2, 2, 100, table).unwrap(), summary);
+        assert_eq!(std::fs::read_to_string(table).unwrap(), text);
+        assert!(text.starts_with("max_steps\t100\n"));
+
+        // the undecided machines depend on the maximum number of steps
+        assert!(enumerate(2, 2, 1000, table).is_err());
+        assert_eq!(std::fs::read_to_string(table).unwrap(), text);
+        std::fs::write(table, "1RB---_------\thalt\t1\t1\tB0\n").unwrap();
+        assert!(enumerate(2, 2, 100, table).is_err());
+        std::fs::remove_file(table).unwrap();
+    }
+}
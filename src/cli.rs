@@ -2,7 +2,7 @@
 //!
 //! This module provides the command-line interface functionality for the Computing Simulator,
 //! allowing users to interact with various computing models including Turing Machines,
-//! RAM Machines, and Lambda expressions.
+//! RAM Machines, finite automata, and Lambda expressions.
 //!
 //! # Features
 //!
@@ -13,6 +13,27 @@
 //! - Handle file I/O for machine definitions
 //! - Convert multi-tape TMs to single-tape TMs
 //! - Generate and print machine encodings
+//! - Determinize and minimize finite automata
+//! - Check the equivalence of finite automata and regular expressions
+//! - Combine finite automata and regular expressions with boolean operations
+//! - Convert finite automata into regular expressions
+//! - Build deterministic automata directly from regular expressions
+//! - Search inputs and text files for the matches of a regular expression
+//! - Parse inputs with context-free grammars, with the CYK and Earley algorithms
+//! - Convert context-free grammars into Chomsky and Greibach normal form, step by step
+//! - Convert context-free grammars into pushdown automata and back
+//! - Simulate pushdown automata accepting by final state or by empty stack, tracing their stack
+//! - Explore non-deterministic Turing machines breadth-first, depth-first, with iterative
+//!   deepening or along a random branch
+//! - Accelerate long runs of deterministic Turing machines by simulating them over blocks of cells
+//! - Prove that deterministic Turing machines and RAM machines never halt, by detecting repeated
+//!   configurations, translated cycles and tape growth
+//! - Enumerate busy beaver candidates in tree normal form, with a resumable table of results
+//! - Debug Turing machines, RAM machines and lambda expressions step by step, with breakpoints,
+//!   watchpoints and reverse stepping
+//! - Show running Turing machines, RAM machines and lambda expressions full screen in the terminal
+//! - Print the computation history as text, JSON Lines or CSV, with the full configurations
+//!   and the nesting of subroutine calls
 //!
 //! # Main Components
 //!
@@ -48,13 +69,23 @@
 //!
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
+use crate::busy_beaver;
+use crate::debugger;
+use crate::finite_automaton;
+use crate::grammar;
 use crate::lambda;
+use crate::regex;
+use crate::trace;
 use crate::turing_machine;
+use crate::turing_machine::FromString;
 
 use crate::computer;
 use crate::file_handler;
 use crate::options;
+use crate::pushdown_automaton;
 use crate::ram_machine;
+use crate::utils;
+use crate::visualizer;
 use std::io::Write;
 
 /// Displays help information about the program's usage and available options
@@ -83,6 +114,27 @@ fn print_help() {
     println!("  --file: provide the file containing the description of the Turing Machine");
     println!("  --status: print informations about the Turing Machine");
     println!("  --print-encoding: print the encoding of the Turing Machine");
+    println!("  --determinize: print the deterministic automaton equivalent to a fsm or regex file");
+    println!("  --minimize: print the minimal automaton equivalent to a fsm or regex file, with its equivalence classes");
+    println!("  --equiv: check whether two fsm or regex files describe the same language (--equiv a.fsm b.reg)");
+    println!("  --union, --intersection, --difference: print the automaton combining the languages of two fsm or regex files (--union a.fsm b.reg)");
+    println!("  --complement: print the automaton recognizing the complement of a fsm or regex file");
+    println!("  --to-regex: print a regex file matching the language of a fsm file");
+    println!("  --regex-dfa: build regex files directly as deterministic automata, for linear time matching");
+    println!("  --search: print every match of a fsm or regex file inside the input (--search=longest or --search=first)");
+    println!("  --grep: print the lines of a text file containing a match of a fsm or regex file (--grep=file.txt)");
+    println!("  --parse: parse the input with a cfg file and print its parse tree (--parse=earley or --parse=cyk)");
+    println!("  --cnf: print a cfg file in Chomsky normal form, with every intermediate grammar if verbose >= 2");
+    println!("  --gnf: print a cfg file in Greibach normal form, with every intermediate grammar if verbose >= 2");
+    println!("  --to-pda: print a pda file accepting the language of a cfg file");
+    println!("  --to-cfg: print a cfg file generating the language of a pda file");
+    println!("  --strategy: search strategy for non-deterministic tm files (--strategy=bfs, dfs, iddfs, random or random:<seed>)");
+    println!("  --block-size: simulate deterministic single-tape tm files over blocks of cells, for long runs (--block-size=4)");
+    println!("  --busy-beaver: enumerate the tm with the given states and symbols in tree normal form and print the champions, resuming from and appending to the table file given, if any (--busy-beaver=4,2 table.tsv)");
+    println!("  --detect-loops: stop deterministic tm and ram files as soon as they are proved to never halt, printing the proof (not with --block-size, nor for tm files calling subroutines)");
+    println!("  --debug: debug a deterministic tm, ram or lambda file on the input step by step, reading commands such as step, back, continue, break and watch (type help for the list)");
+    println!("  --trace-format: format of the computation printed with --verbose=2 (--trace-format=text, jsonl or csv)");
+    println!("  --visualize: show the computation of a deterministic tm, ram or lambda file on the input full screen, with run/pause (space), step (n), back (b), speed (+ and -) and quit (q or Ctrl-C) keys");
     println!();
     println!("Acknowledgements:");
     println!("  This program is made by dp. Licensed under the MIT License.");
@@ -124,7 +176,7 @@ fn print_version() {
  */
 
 /// Prints the status of a Turing Machine, including whether it's deterministic,
-/// valid, and has total transitions
+/// valid, has total transitions and can be read back as a finite automaton
 ///
 /// # Arguments
 ///
@@ -133,6 +185,231 @@ pub fn print_status_tm(tm: &turing_machine::TuringMachine) {
     println!("Deterministic: {}", tm.is_deterministic());
     println!("Ok: {}", tm.is_ok());
     println!("Transition total: {}", tm.is_transition_total());
+    println!(
+        "Finite automaton: {}",
+        finite_automaton::FiniteAutomaton::from_tm(tm).is_ok()
+    );
+}
+
+/// Prints the status of a finite automaton, including whether it's deterministic
+/// and the size of its transition relation
+///
+/// # Arguments
+///
+/// * `fa` - Reference to a FiniteAutomaton instance
+pub fn print_status_fsm(fa: &finite_automaton::FiniteAutomaton) {
+    println!("Deterministic: {}", fa.is_deterministic());
+    println!("States: {}", fa.states.len());
+    println!("Transitions: {}", fa.transitions.len());
+}
+
+/// Prints the status of a pushdown automaton, including its acceptance mode and stack, the size of
+/// its transition relation and whether it's deterministic, listing the conflicting transitions
+///
+/// # Arguments
+///
+/// * `pda` - Reference to a PushdownAutomaton instance
+pub fn print_status_pda(pda: &pushdown_automaton::PushdownAutomaton) {
+    let acceptance = match pda.acceptance {
+        pushdown_automaton::Acceptance::FinalState => "final state",
+        pushdown_automaton::Acceptance::EmptyStack => "empty stack",
+    };
+    println!("Acceptance: {}", acceptance);
+    println!("Bottomless stack: {}", pda.bottomless);
+    println!("States: {}", pda.states.len());
+    println!("Transitions: {}", pda.transitions.len());
+    println!("Deterministic: {}", pda.is_deterministic());
+    for (i, j) in pda.determinism_conflicts() {
+        println!(
+            "Conflict: {} / {}",
+            pda.transition_line(&pda.transitions[i]),
+            pda.transition_line(&pda.transitions[j])
+        );
+    }
+}
+
+/// Prints the status of a context-free grammar, including its size and whether it is in
+/// Chomsky or Greibach normal form
+///
+/// # Arguments
+///
+/// * `g` - Reference to a Grammar instance
+pub fn print_status_cfg(g: &grammar::Grammar) {
+    println!("Nonterminals: {}", g.nonterminals.len());
+    println!("Terminals: {}", g.terminals.len());
+    println!("Productions: {}", g.productions.len());
+    println!("Chomsky normal form: {}", g.is_cnf());
+    println!("Greibach normal form: {}", g.is_gnf());
+}
+
+/// Prints the minimal automaton equivalent to a finite automaton, as a `fsm` file followed by
+/// comment lines mapping each state to its equivalence class
+///
+/// # Arguments
+///
+/// * `fa` - Reference to a FiniteAutomaton instance
+pub fn print_minimized_fsm(fa: &finite_automaton::FiniteAutomaton) {
+    let (minimal, mapping) = fa.minimize();
+    println!("{}", minimal);
+    println!("// equivalence classes:");
+    let mut entries: Vec<(&String, &String)> = mapping.iter().collect();
+    entries.sort_by_key(|(state, class)| {
+        (
+            minimal.states.iter().position(|s| s == *class),
+            (*state).clone(),
+        )
+    });
+    for (state, class) in entries {
+        println!("// {} -> {}", state, class);
+    }
+}
+
+/// Prints whether the language of a computer is the same as the one of the automaton in the
+/// file given with `--equiv`, with the shortest word telling them apart if it is not
+///
+/// # Arguments
+///
+/// * `c` - Reference to the Computer read from the main file
+/// * `options` - Reference to Options instance, holding the two file names
+/// * `s` - Mutable reference to the Server used to read the second file
+fn print_equivalence(c: &computer::Computer, options: &options::Options, s: &mut computer::Server) {
+    let other = match file_handler::handle_file_reads(options.equiv.clone(), s) {
+        Ok(comp) => comp,
+        Err(error) => {
+            println!("Error: {}", error);
+            return;
+        }
+    };
+    let (first, second) = match (c.to_fsm(), other.to_fsm()) {
+        (Ok(first), Ok(second)) => (first, second),
+        (Err(error), _) | (_, Err(error)) => {
+            println!("Error: {}", error);
+            return;
+        }
+    };
+    match first.distinguishing_word(&second) {
+        None => println!("equivalent"),
+        Some(word) => {
+            let accepted_by = if first.simulate(word.clone(), word.len()).map(|r| r.outcome)
+                == Ok(computer::Outcome::Accept)
+            {
+                &options.file
+            } else {
+                &options.equiv
+            };
+            println!("not equivalent");
+            println!(
+                "shortest distinguishing word: {} (accepted by {} only)",
+                if word.is_empty() { "ε".to_string() } else { word.join("") },
+                accepted_by
+            );
+        }
+    }
+}
+
+/// Prints the automaton recognizing the union, intersection or difference of the language of a
+/// computer and the one of the file given with the corresponding option, as a `fsm` file
+///
+/// # Arguments
+///
+/// * `c` - Reference to the Computer read from the main file
+/// * `other_file` - The name of the file holding the second automaton
+/// * `operation` - The boolean operation to apply
+/// * `s` - Mutable reference to the Server used to read the second file
+fn print_product(
+    c: &computer::Computer,
+    other_file: &str,
+    operation: finite_automaton::ProductOperation,
+    s: &mut computer::Server,
+) {
+    let other = match file_handler::handle_file_reads(other_file.to_string(), s) {
+        Ok(comp) => comp,
+        Err(error) => {
+            println!("Error: {}", error);
+            return;
+        }
+    };
+    match (c.to_fsm(), other.to_fsm()) {
+        (Ok(first), Ok(second)) => println!("{}", first.product(&second, operation)),
+        (Err(error), _) | (_, Err(error)) => println!("Error: {}", error),
+    }
+}
+
+/// Prints every match of an automaton inside the input given with `--input`, one per line in the
+/// format `start-end: text`, where the offsets count characters
+///
+/// # Arguments
+///
+/// * `fa` - Reference to the FiniteAutomaton to search with
+/// * `options` - Reference to Options instance, holding the input and the search semantics
+fn print_matches(fa: &finite_automaton::FiniteAutomaton, options: &options::Options) {
+    let input = utils::input_string_to_vec(fa.input_alphabet.clone(), options.input.clone());
+    let matches = fa.find_matches(&input, match_semantics(&options.search));
+    if matches.is_empty() {
+        println!("no match");
+    }
+    for (start, end) in matches {
+        let offset = |i: usize| input[..i].iter().map(|s| s.chars().count()).sum::<usize>();
+        println!("{}-{}: {}", offset(start), offset(end), input[start..end].join(""));
+    }
+}
+
+/// Prints the lines of the file given with `--grep` containing a match of an automaton, each
+/// preceded by its line number
+///
+/// # Arguments
+///
+/// * `fa` - Reference to the FiniteAutomaton to search with
+/// * `options` - Reference to Options instance, holding the file name and the search semantics
+fn print_grep(fa: &finite_automaton::FiniteAutomaton, options: &options::Options) {
+    let text = match std::fs::read_to_string(&options.grep) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("Error reading the file '{}': {}", options.grep, error);
+            return;
+        }
+    };
+    for (n, line) in text.lines().enumerate() {
+        let input = utils::input_string_to_vec(fa.input_alphabet.clone(), line.to_string());
+        if !fa.find_matches(&input, match_semantics(&options.search)).is_empty() {
+            println!("{}:{}", n + 1, line);
+        }
+    }
+}
+
+/// Collects the characters of the text searched with `--search` or `--grep`, so that the
+/// wildcards and the negated classes of a regex can match them, except the spaces, which are
+/// the blank symbol of the automata built from regexes
+///
+/// # Arguments
+///
+/// * `options` - Reference to Options instance, holding the input or the file name
+///
+/// # Returns
+///
+/// * `Vec<String>` - The characters of the text, without duplicates, empty if the file cannot be
+///   read
+fn searched_symbols(options: &options::Options) -> Vec<String> {
+    let text = if options.grep.is_empty() {
+        options.input.clone()
+    } else {
+        std::fs::read_to_string(&options.grep).unwrap_or_default()
+    };
+    let mut symbols: Vec<String> = Vec::new();
+    for ch in text.chars().filter(|ch| !matches!(ch, '\n' | '\r' | ' ')) {
+        if !symbols.contains(&ch.to_string()) {
+            symbols.push(ch.to_string());
+        }
+    }
+    symbols
+}
+
+/// Reads the semantics of a search from the value of `--search`, leftmost-longest by default
+fn match_semantics(search: &str) -> finite_automaton::MatchSemantics {
+    match search {
+        "first" => finite_automaton::MatchSemantics::LeftmostFirst,
+        _ => finite_automaton::MatchSemantics::LeftmostLongest,
+    }
 }
 
 /// Processes and displays computation results based on verbosity level
@@ -142,24 +419,42 @@ pub fn print_status_tm(tm: &turing_machine::TuringMachine) {
 /// * `server` - The computation server instance
 /// * `opt` - Options containing verbosity and other settings
 fn process_results(server: computer::Server, opt: options::Options) {
+    let format = if opt.trace_format.is_empty() {
+        trace::TraceFormat::Text
+    } else {
+        match trace::TraceFormat::from_string(&opt.trace_format) {
+            Ok(format) => format,
+            Err(error) => {
+                println!("Error: {}", error);
+                return;
+            }
+        }
+    };
     let result = server.execute(&opt.input, opt.max_steps);
     match result {
-        Ok((state, _, tape, steps, computation)) => {
+        Ok(result) => {
             if opt.verbose < 0 {
                 eprintln!("Error: Invalid verbose level");
                 return;
             }
             if opt.verbose >= 0 {
-                println!("{}", state);
-                println!("{}", tape);
+                println!("{}", result.outcome);
+                println!("{}", result.output());
             }
             if opt.verbose >= 1 {
-                println!("Steps: {}", steps);
+                println!("Steps: {}", result.steps);
             }
             if opt.verbose >= 2 {
-                println!("Computation: ");
-                for conf in computation {
-                    println!("  {}", conf);
+                // the text lines are indented after a title, the other formats are left
+                // untouched to be parsed
+                let indent = if format == trace::TraceFormat::Text {
+                    println!("Computation: ");
+                    "  "
+                } else {
+                    ""
+                };
+                for line in trace::render(&result.computation, &format) {
+                    println!("{}{}", indent, line);
                 }
             }
         }
@@ -169,6 +464,50 @@ fn process_results(server: computer::Server, opt: options::Options) {
     }
 }
 
+/// Runs the step-by-step debugger of a computer on the input, reading its commands from the
+/// standard input until `exit` or the end of the input.
+///
+/// # Arguments
+///
+/// * `c` - The computer to debug
+/// * `server` - The context of the computer
+/// * `opt` - The options, giving the input and the maximum number of steps
+fn debug_tui(c: &computer::Computer, server: &computer::Server, opt: &options::Options) {
+    let mut debugger = match debugger::Debugger::new(c, server, &opt.input, opt.max_steps) {
+        Ok(debugger) => debugger,
+        Err(error) => {
+            println!("Error: {}", error);
+            return;
+        }
+    };
+    println!("{}", debugger.describe());
+    let mut input = String::new();
+    loop {
+        print!("(debug) ");
+        match std::io::stdout().flush() {
+            Ok(_) => {}
+            Err(error) => println!("Error: {}", error),
+        }
+        input.clear();
+        match std::io::stdin().read_line(&mut input) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) => {
+                println!("Error: {}", error);
+                break;
+            }
+        }
+        let command = input.trim();
+        if command == "exit" || command == "quit" {
+            break;
+        }
+        match debugger.command(command) {
+            Ok(output) => println!("{}", output),
+            Err(error) => println!("Error: {}", error),
+        }
+    }
+}
+
 /// Provides an interactive Terminal User Interface for the computing simulator
 ///
 /// # Arguments
@@ -203,6 +542,9 @@ fn interactive_tui(server: &mut computer::Server, opt: options::Options) {
                     computer::ComputingElem::Tm(m) => print_status_tm(&m),
                     computer::ComputingElem::Ram(m) => print_status_ram(&m),
                     computer::ComputingElem::Lambda(_) => {}
+                    computer::ComputingElem::Fsm(a) => print_status_fsm(&a),
+                    computer::ComputingElem::Pda(a) => print_status_pda(&a),
+                    computer::ComputingElem::Cfg(g) => print_status_cfg(&g),
                 },
                 None => println!("Error: Could not get computer status"),
             }
@@ -296,6 +638,49 @@ pub fn print_lambda_as_tree(l: &lambda::Lambda) {
     print_expr(&l.expr, 0);
 }
 
+/// Prints whether the input given with `--input` belongs to the language of a grammar, followed
+/// by a parse tree if it does
+///
+/// # Arguments
+///
+/// * `g` - Reference to the Grammar to parse with
+/// * `options` - Reference to Options instance, holding the input and the parsing algorithm
+fn print_parse(g: &grammar::Grammar, options: &options::Options) {
+    let input = utils::input_string_to_vec(g.terminals.clone(), options.input.clone());
+    let tree = if options.parse == "cyk" {
+        g.cyk(&input)
+    } else {
+        g.earley(&input)
+    };
+    match tree {
+        Some(tree) => {
+            println!("accept");
+            println!("{}", tree);
+        }
+        None => println!("reject"),
+    }
+}
+
+/// Prints a grammar converted into a normal form, preceded by the grammar produced by each phase
+/// of the conversion if the verbosity level is at least 2
+///
+/// # Arguments
+///
+/// * `(normal_form, steps)` - The converted grammar and the log of its phases
+/// * `options` - Reference to Options instance, holding the verbosity level
+fn print_normal_form(
+    (normal_form, steps): (grammar::Grammar, Vec<String>),
+    options: &options::Options,
+) {
+    if options.verbose >= 2 {
+        for step in steps {
+            println!("{}", step);
+        }
+    } else {
+        println!("{}", normal_form);
+    }
+}
+
 /// Prints the definition of a Turing Machine
 ///
 /// # Arguments
@@ -397,6 +782,11 @@ pub fn main_cli_with_options(mut options: options::Options) {
         return;
     }
 
+    if !options.error.is_empty() {
+        println!("Error: {}. Use --help for more information.", options.error);
+        return;
+    }
+
     if !validate_options(&options) {
         println!("Error: Invalid options. Use --help for more information.");
         return;
@@ -413,9 +803,47 @@ pub fn main_cli_with_options(mut options: options::Options) {
         return;
     }
 
+    if !options.busy_beaver.is_empty() {
+        print_busy_beavers(&options);
+        return;
+    }
+
     handle_computation(&mut options);
 }
 
+/// Enumerates the busy beaver candidates with the number of states and symbols of the options,
+/// printing the champions and the number of machines of each outcome
+///
+/// # Arguments
+///
+/// * `options` - Options with the number of states and symbols, the step limit of each machine
+///   and the table file, if any
+fn print_busy_beavers(options: &options::Options) {
+    let mut sizes = options.busy_beaver.splitn(2, ',').map(|n| n.trim().parse::<usize>());
+    let (states, symbols) = match (sizes.next(), sizes.next()) {
+        (Some(Ok(states)), None) => (states, 2),
+        (Some(Ok(states)), Some(Ok(symbols))) => (states, symbols),
+        _ => {
+            println!("Error: invalid busy beaver size '{}'", options.busy_beaver);
+            return;
+        }
+    };
+    match busy_beaver::enumerate(states, symbols, options.max_steps, &options.file) {
+        Ok(summary) => {
+            println!("BB({}, {})", states, symbols);
+            println!("Steps: {} ({})", summary.max_steps.0, summary.max_steps.1);
+            println!("Ones: {} ({})", summary.max_ones.0, summary.max_ones.1);
+            if options.verbose >= 1 {
+                println!(
+                    "Halting: {}, diverging: {}, undecided: {}",
+                    summary.halting, summary.diverging, summary.undecided
+                );
+            }
+        }
+        Err(error) => println!("Error: {}", error),
+    }
+}
+
 /// Validates that the provided options are valid
 ///
 /// # Arguments
@@ -426,7 +854,7 @@ pub fn main_cli_with_options(mut options: options::Options) {
 ///
 /// * `bool` - True if options are valid, false otherwise
 fn validate_options(options: &options::Options) -> bool {
-    !options.file.is_empty() || options.print_nth_tm != -1
+    !options.file.is_empty() || options.print_nth_tm != -1 || !options.busy_beaver.is_empty()
 }
 
 /// Handles the computation based on the provided options
@@ -436,6 +864,22 @@ fn validate_options(options: &options::Options) -> bool {
 /// * `options` - Mutable reference to Options instance
 fn handle_computation(options: &mut options::Options) {
     let mut s = computer::Server::new();
+    s.simulation.regex_dfa = options.regex_dfa;
+    if !options.grep.is_empty() || !options.search.is_empty() {
+        s.simulation.regex_alphabet = searched_symbols(options);
+    }
+    s.simulation.trace = options.verbose >= 2;
+    s.simulation.block_size = options.block_size;
+    s.simulation.detect_loops = options.detect_loops;
+    if !options.strategy.is_empty() {
+        match turing_machine::Strategy::from_string(&options.strategy) {
+            Ok(strategy) => s.simulation.strategy = strategy,
+            Err(error) => {
+                println!("Error: {}", error);
+                return;
+            }
+        }
+    }
     let mut c;
     match file_handler::handle_file_reads(options.file.clone(), &mut s) {
         Ok(comp) => {
@@ -446,6 +890,93 @@ fn handle_computation(options: &mut options::Options) {
             return;
         }
     }
+    if options.determinize {
+        match c.to_fsm() {
+            Ok(fa) => println!("{}", fa.determinize()),
+            Err(error) => println!("Error: {}", error),
+        }
+        return;
+    }
+    if !options.equiv.is_empty() {
+        print_equivalence(&c, options, &mut s);
+        return;
+    }
+    if options.minimize {
+        match c.to_fsm() {
+            Ok(fa) => print_minimized_fsm(&fa),
+            Err(error) => println!("Error: {}", error),
+        }
+        return;
+    }
+    for (other_file, operation) in [
+        (&options.union, finite_automaton::ProductOperation::Union),
+        (
+            &options.intersection,
+            finite_automaton::ProductOperation::Intersection,
+        ),
+        (
+            &options.difference,
+            finite_automaton::ProductOperation::Difference,
+        ),
+    ] {
+        if !other_file.is_empty() {
+            print_product(&c, other_file, operation, &mut s);
+            return;
+        }
+    }
+    if options.complement {
+        match c.to_fsm() {
+            Ok(fa) => println!("{}", fa.complement()),
+            Err(error) => println!("Error: {}", error),
+        }
+        return;
+    }
+    if !options.grep.is_empty() || !options.search.is_empty() {
+        match c.to_fsm() {
+            Ok(fa) if options.grep.is_empty() => print_matches(&fa, options),
+            Ok(fa) => print_grep(&fa, options),
+            Err(error) => println!("Error: {}", error),
+        }
+        return;
+    }
+    if options.to_regex {
+        match c.to_fsm() {
+            Ok(fa) => println!("regex\n{}", regex::fsa_to_regex(&fa)),
+            Err(error) => println!("Error: {}", error),
+        }
+        return;
+    }
+    if !options.parse.is_empty() {
+        match &c.element {
+            computer::ComputingElem::Cfg(g) => print_parse(g, options),
+            _ => println!("Error: not a grammar"),
+        }
+        return;
+    }
+    if options.cnf || options.gnf {
+        match &c.element {
+            computer::ComputingElem::Cfg(g) if options.gnf => print_normal_form(g.to_gnf_steps(), options),
+            computer::ComputingElem::Cfg(g) => print_normal_form(g.to_cnf_steps(), options),
+            _ => println!("Error: not a grammar"),
+        }
+        return;
+    }
+    if options.to_pda {
+        match &c.element {
+            computer::ComputingElem::Cfg(g) => {
+                println!("{}", pushdown_automaton::PushdownAutomaton::from_cfg(g))
+            }
+            _ => println!("Error: not a grammar"),
+        }
+        return;
+    }
+    if options.to_cfg {
+        match &c.element {
+            computer::ComputingElem::Pda(a) => println!("{}", a.to_cfg()),
+            _ => println!("Error: not a pushdown automaton"),
+        }
+        return;
+    }
     match c.element.clone() {
         computer::ComputingElem::Tm(m) => {
             if options.convert_to_singletape {
@@ -497,6 +1028,35 @@ fn handle_computation(options: &mut options::Options) {
                 println!("Error: invalid option --print-number on non-tm file");
             }
         }
+        computer::ComputingElem::Fsm(_) | computer::ComputingElem::Pda(_) => {
+            if options.convert_to_singletape || options.print_number {
+                println!("Error: invalid option on non-tm, non-ram file");
+            } else if options.convert_to_tm {
+                match c.to_tm(options, &mut s) {
+                    Ok(comp) => c = comp,
+                    Err(error) => {
+                        println!("Error: {}", error);
+                        return;
+                    }
+                }
+            } else if options.convert_to_ram {
+                match c.to_ram(options, &mut s) {
+                    Ok(comp) => c = comp,
+                    Err(error) => {
+                        println!("Error: {}", error);
+                        return;
+                    }
+                }
+            }
+        }
+        computer::ComputingElem::Cfg(_) => {
+            if options.convert_to_singletape || options.print_number {
+                println!("Error: invalid option on non-tm, non-ram file");
+            } else if options.convert_to_tm || options.convert_to_ram {
+                println!("Error: a cfg file cannot be converted");
+                return;
+            }
+        }
         computer::ComputingElem::Lambda(_) => {
             if options.convert_to_singletape || options.print_number {
                 println!("Error: invalid option on non-tm, non-ram file");
@@ -526,6 +1086,9 @@ fn handle_computation(options: &mut options::Options) {
             computer::ComputingElem::Ram(m) => print_ram(*m),
             computer::ComputingElem::Tm(m) => print_tm(*m),
             computer::ComputingElem::Lambda(l) => print_lambda(&l),
+            computer::ComputingElem::Fsm(a) => println!("{}", a),
+            computer::ComputingElem::Pda(a) => println!("{}", a),
+            computer::ComputingElem::Cfg(g) => println!("{}", g),
         }
         return;
     }
@@ -535,11 +1098,26 @@ fn handle_computation(options: &mut options::Options) {
         return;
     }
 
+    if options.debug {
+        debug_tui(&c, &s, options);
+        return;
+    }
+
+    if options.visualize {
+        if let Err(error) = visualizer::run(&c, &s, &options.input, options.max_steps) {
+            println!("Error: {}", error);
+        }
+        return;
+    }
+
     if options.status {
         match c.element.clone() {
             computer::ComputingElem::Tm(m) => print_status_tm(&m),
             computer::ComputingElem::Ram(m) => print_status_ram(&m),
             computer::ComputingElem::Lambda(l) => print_lambda_as_tree(&l),
+            computer::ComputingElem::Fsm(a) => print_status_fsm(&a),
+            computer::ComputingElem::Pda(a) => print_status_pda(&a),
+            computer::ComputingElem::Cfg(g) => print_status_cfg(&g),
         }
     } else if options.clone().input.is_empty() {
         interactive_tui(&mut s, options.clone());
@@ -554,9 +1132,10 @@ mod tests {
 
     #[test]
     fn test_validate_options() {
-        let mut opt = options::Options::default();
-
-        opt.file = "test.tm".to_string();
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            ..Default::default()
+        };
         assert!(validate_options(&opt));
 
         opt.file = "".to_string();
@@ -595,10 +1174,12 @@ mod tests {
     #[test]
     fn test_process_results() {
         let mut server = computer::Server::new();
-        let mut opt = options::Options::default();
-        opt.verbose = 1;
-        opt.input = "test".to_string();
-        opt.max_steps = 100;
+        let opt = options::Options {
+            verbose: 1,
+            input: "test".to_string(),
+            max_steps: 100,
+            ..Default::default()
+        };
 
         let mut computer = computer::Computer::new();
         computer.set_turing(turing_machine::TuringMachine::new());
@@ -700,10 +1281,12 @@ mod tests {
     #[test]
     fn test_pseudo_invalid_verbose_level() {
         let server = computer::Server::new();
-        let mut opt = options::Options::default();
-        opt.verbose = -1;
-        opt.input = "test".to_string();
-        opt.max_steps = 100;
+        let opt = options::Options {
+            verbose: -1,
+            input: "test".to_string(),
+            max_steps: 100,
+            ..Default::default()
+        };
 
         let result = std::panic::catch_unwind(|| {
             process_results(server, opt);
@@ -713,9 +1296,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_tm_convert_to_singletape() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.convert_to_singletape = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            convert_to_singletape: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         let mut tm = turing_machine::TuringMachine::new();
@@ -727,9 +1312,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_tm_print_number() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.print_number = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            print_number: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -739,9 +1326,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_tm_convert_to_ram() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.convert_to_ram = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            convert_to_ram: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -751,9 +1340,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_tm_convert_to_tm() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.convert_to_tm = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            convert_to_tm: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -763,9 +1354,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_ram_convert_to_tm() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.convert_to_tm = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            convert_to_tm: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -779,9 +1372,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_ram_convert_to_tm_invalid() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.convert_to_tm = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            convert_to_tm: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -796,9 +1391,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_ram_convert_to_singletape() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.convert_to_singletape = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            convert_to_singletape: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -812,9 +1409,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_ram_print_number() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.print_number = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            print_number: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -828,9 +1427,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_lambda_convert_to_tm() {
-        let mut opt = options::Options::default();
-        opt.file = "test.lambda".to_string();
-        opt.convert_to_tm = true;
+        let mut opt = options::Options {
+            file: "test.lambda".to_string(),
+            convert_to_tm: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_lambda(lambda::Lambda {
@@ -845,9 +1446,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_lambda_convert_to_ram() {
-        let mut opt = options::Options::default();
-        opt.file = "test.lambda".to_string();
-        opt.convert_to_ram = true;
+        let mut opt = options::Options {
+            file: "test.lambda".to_string(),
+            convert_to_ram: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_lambda(lambda::Lambda {
@@ -862,10 +1465,12 @@ mod tests {
 
     #[test]
     fn test_handle_computation_lambda_convert_to_singletape_print_number() {
-        let mut opt = options::Options::default();
-        opt.file = "test.lambda".to_string();
-        opt.convert_to_singletape = true;
-        opt.print_number = true;
+        let mut opt = options::Options {
+            file: "test.lambda".to_string(),
+            convert_to_singletape: true,
+            print_number: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_lambda(lambda::Lambda {
@@ -880,9 +1485,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_print_computer_tm() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.print_computer = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            print_computer: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -892,9 +1499,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_print_computer_ram() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.print_computer = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            print_computer: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -908,9 +1517,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_print_computer_lambda() {
-        let mut opt = options::Options::default();
-        opt.file = "test.lambda".to_string();
-        opt.print_computer = true;
+        let mut opt = options::Options {
+            file: "test.lambda".to_string(),
+            print_computer: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_lambda(lambda::Lambda {
@@ -925,9 +1536,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_print_encoding() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.print_encoding = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            print_encoding: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -937,9 +1550,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_status_tm() {
-        let mut opt = options::Options::default();
-        opt.file = "test.tm".to_string();
-        opt.status = true;
+        let mut opt = options::Options {
+            file: "test.tm".to_string(),
+            status: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_turing(turing_machine::TuringMachine::new());
@@ -949,9 +1564,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_status_ram() {
-        let mut opt = options::Options::default();
-        opt.file = "test.ram".to_string();
-        opt.status = true;
+        let mut opt = options::Options {
+            file: "test.ram".to_string(),
+            status: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_ram(ram_machine::RamMachine {
@@ -965,9 +1582,11 @@ mod tests {
 
     #[test]
     fn test_handle_computation_status_lambda() {
-        let mut opt = options::Options::default();
-        opt.file = "test.lambda".to_string();
-        opt.status = true;
+        let mut opt = options::Options {
+            file: "test.lambda".to_string(),
+            status: true,
+            ..Default::default()
+        };
         let mut s = computer::Server::new();
         let mut c = computer::Computer::new();
         c.set_lambda(lambda::Lambda {
@@ -980,6 +1599,243 @@ mod tests {
         handle_computation(&mut opt);
     }
 
+    #[test]
+    fn test_handle_computation_determinize() {
+        let mut opt = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            determinize: true,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.file = "examples/regex.reg".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_minimize() {
+        let mut opt = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            minimize: true,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_equiv() {
+        let mut opt = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            equiv: "examples/regex.reg".to_string(),
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.equiv = "examples/bab.fsm".to_string();
+        handle_computation(&mut opt);
+        opt.equiv = "missing.fsm".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_boolean_operations() {
+        let mut opt = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            union: "examples/regex.reg".to_string(),
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.union = String::new();
+        opt.intersection = "examples/regex.reg".to_string();
+        handle_computation(&mut opt);
+        opt.intersection = String::new();
+        opt.difference = "missing.fsm".to_string();
+        handle_computation(&mut opt);
+        opt.difference = String::new();
+        opt.complement = true;
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_to_regex() {
+        let mut opt = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            to_regex: true,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.file = "examples/simple.ram".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_regex_dfa() {
+        let mut opt = options::Options {
+            file: "examples/regex.reg".to_string(),
+            input: "abbce".to_string(),
+            regex_dfa: true,
+            max_steps: 1000,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.status = true;
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_parse() {
+        let mut opt = options::Options {
+            file: "examples/expressions.cfg".to_string(),
+            input: "(x+x)*x".to_string(),
+            parse: "earley".to_string(),
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.parse = "cyk".to_string();
+        handle_computation(&mut opt);
+        opt.input = "x+".to_string();
+        handle_computation(&mut opt);
+        opt.parse = String::new();
+        opt.status = true;
+        handle_computation(&mut opt);
+        opt.status = false;
+        opt.print_computer = true;
+        handle_computation(&mut opt);
+        opt.file = "examples/bab.fsm".to_string();
+        opt.parse = "earley".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_pda_conversions() {
+        let mut opt = options::Options {
+            file: "examples/expressions.cfg".to_string(),
+            to_pda: true,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.file = "examples/0n1m2m3n.pda".to_string();
+        handle_computation(&mut opt);
+        opt.to_pda = false;
+        opt.to_cfg = true;
+        handle_computation(&mut opt);
+        opt.file = "examples/expressions.cfg".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_strategy() {
+        let mut opt = options::Options {
+            file: "examples/plusone.tm".to_string(),
+            input: "101".to_string(),
+            strategy: "iddfs".to_string(),
+            max_steps: 1000,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.strategy = "sideways".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_pda() {
+        let mut opt = options::Options {
+            file: "examples/palindromes.pda".to_string(),
+            status: true,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.status = false;
+        opt.print_computer = true;
+        handle_computation(&mut opt);
+        opt.print_computer = false;
+        opt.input = "abba".to_string();
+        opt.verbose = 2;
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_normal_forms() {
+        let mut opt = options::Options {
+            file: "examples/expressions.cfg".to_string(),
+            cnf: true,
+            verbose: 1,
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.verbose = 2;
+        handle_computation(&mut opt);
+        opt.gnf = true;
+        handle_computation(&mut opt);
+        opt.file = "examples/bab.fsm".to_string();
+        handle_computation(&mut opt);
+    }
+
+    #[test]
+    fn test_handle_computation_search() {
+        let mut opt = options::Options {
+            file: "examples/regex.reg".to_string(),
+            input: "xabbcd abde ac".to_string(),
+            search: "longest".to_string(),
+            ..Default::default()
+        };
+        handle_computation(&mut opt);
+        opt.search = "first".to_string();
+        handle_computation(&mut opt);
+        opt.search = "longest".to_string();
+        opt.regex_dfa = true;
+        handle_computation(&mut opt);
+        opt.grep = "examples/regex.reg".to_string();
+        handle_computation(&mut opt);
+        opt.grep = "missing.txt".to_string();
+        handle_computation(&mut opt);
+    }
+
+    /// Reads the regex file of the options as the automaton of a search, and finds its matches
+    /// inside a text
+    fn search_matches(opt: &options::Options, text: &str) -> Vec<(usize, usize)> {
+        let mut s = computer::Server::new();
+        s.simulation.regex_alphabet = searched_symbols(opt);
+        let fa = file_handler::handle_file_reads(opt.file.clone(), &mut s)
+            .unwrap()
+            .to_fsm()
+            .unwrap();
+        let input = utils::input_string_to_vec(fa.input_alphabet.clone(), text.to_string());
+        fa.find_matches(&input, match_semantics(&opt.search))
+    }
+
+    #[test]
+    fn test_search_symbols_outside_the_regex() {
+        let file = std::env::temp_dir().join(format!("search_{}.reg", std::process::id()));
+        std::fs::write(&file, "regex\na.c|[^a]x\n").unwrap();
+        let mut opt = options::Options {
+            file: file.to_string_lossy().to_string(),
+            input: "abcaacacc zx".to_string(),
+            search: "longest".to_string(),
+            ..Default::default()
+        };
+        let expected = vec![(0, 3), (3, 6), (6, 9), (10, 12)];
+        assert_eq!(search_matches(&opt, &opt.input), expected);
+        opt.regex_dfa = true;
+        assert_eq!(search_matches(&opt, &opt.input), expected);
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn test_grep_symbols_outside_the_regex() {
+        let file = std::env::temp_dir().join(format!("grep_{}.reg", std::process::id()));
+        let text = std::env::temp_dir().join(format!("grep_{}.txt", std::process::id()));
+        std::fs::write(&file, "regex\na.c\n").unwrap();
+        std::fs::write(&text, "xyz\nabc\n").unwrap();
+        let opt = options::Options {
+            file: file.to_string_lossy().to_string(),
+            grep: text.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        assert!(search_matches(&opt, "xyz").is_empty());
+        assert_eq!(search_matches(&opt, "abc"), vec![(0, 3)]);
+        std::fs::remove_file(file).unwrap();
+        std::fs::remove_file(text).unwrap();
+    }
+
     #[test]
     fn test_handle_computation_interactive_tui() {
         // This test is limited since interactive_tui waits for stdin.
@@ -989,15 +1845,19 @@ mod tests {
 
     #[test]
     fn test_main_cli_help() {
-        let mut opt = options::Options::default();
-        opt.help = true;
+        let opt = options::Options {
+            help: true,
+            ..Default::default()
+        };
         main_cli_with_options(opt);
     }
 
     #[test]
     fn test_main_cli_version() {
-        let mut opt = options::Options::default();
-        opt.version = true;
+        let opt = options::Options {
+            version: true,
+            ..Default::default()
+        };
         main_cli_with_options(opt);
     }
 
@@ -1009,8 +1869,24 @@ mod tests {
 
     #[test]
     fn test_main_cli_print_nth_tm() {
-        let mut opt = options::Options::default();
-        opt.print_nth_tm = 0;
+        let opt = options::Options {
+            print_nth_tm: 0,
+            ..Default::default()
+        };
+        main_cli_with_options(opt);
+    }
+
+    #[test]
+    fn test_main_cli_busy_beaver() {
+        let mut opt = options::Options {
+            busy_beaver: "2".to_string(),
+            print_nth_tm: -1,
+            max_steps: 100,
+            ..Default::default()
+        };
+        assert!(validate_options(&opt));
+        main_cli_with_options(opt.clone());
+        opt.busy_beaver = "2,x".to_string();
         main_cli_with_options(opt);
     }
 }
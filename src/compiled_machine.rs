@@ -0,0 +1,622 @@
+//! # Compiled Machine Module
+//!
+//! This module provides a fast simulation engine for deterministic Turing machines. The generic
+//! simulator of `turing_machine` looks transitions up with `String` keys and keeps the tapes as
+//! vectors of strings, which is flexible but slow; a `CompiledMachine` translates a machine into
+//! integers once, so that each step only costs a few array accesses.
+//!
+//! ## Main Structures
+//!
+//! - **CompiledMachine**: A deterministic Turing machine whose states and symbols are interned to
+//!   integers, with a dense transition table indexed by state and symbols read.
+//!
+//! ## Key Features
+//!
+//! - **Interning**: States and symbols are numbered in the order they are met, so that the
+//!   results can be translated back to the names of the original machine.
+//! - **Dense Transition Table**: A single table with one entry for each state and combination of
+//!   symbols read, holding the next state, the symbols to write and the head movements.
+//! - **Two-way Tapes**: Tapes grow in both directions in amortized constant time.
+//! - **Optional Trace**: The computation history, which costs the length of the tape at every
+//!   step, is only recorded when requested, so that runs of hundreds of millions of steps are
+//!   practical.
+//!
+//! The engine reproduces the results of the generic simulator of deterministic machines, step
+//! counts included: machines calling subroutines and machines whose transition table would be too
+//! large are left to the generic simulator.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::loop_detector;
+use crate::trace;
+use crate::turing_machine;
+
+/// A configuration of a compiled machine: its state, the positions of its heads and its tapes.
+#[derive(Clone, Debug, PartialEq)]
+struct Configuration {
+    state: u32,
+    heads: Vec<usize>,
+    tapes: Vec<std::collections::VecDeque<u32>>,
+}
+
+/// The largest number of entries of the transition table of a compiled machine.
+const MAX_TABLE_SIZE: usize = 1 << 24;
+
+/// The entry of the transition table of the state and symbols without a transition.
+pub const NO_TRANSITION: u32 = u32::MAX;
+
+/// A deterministic Turing machine compiled to integers, for fast simulation.
+///
+/// # Fields
+/// * `states` - The names of the states, indexed by their number
+/// * `symbols` - The names of the symbols, indexed by their number
+/// * `tape_count` - Number of tapes of the machine
+/// * `initial_state` - The number of the initial state
+/// * `blank_symbol` - The number of the blank symbol
+/// * `accept_state` - The number of the accept state, if any
+/// * `reject_state` - The number of the reject state, if any
+/// * `final_states` - Whether each state is final (accept, reject or halt state)
+/// * `next_states` - The state reached from each entry of the table, `NO_TRANSITION` if none
+/// * `writes` - The symbols written on each tape, `tape_count` for each entry of the table
+/// * `moves` - The movements of each head (-1, 0 or 1), `tape_count` for each entry of the table
+///
+/// The entry of a state `q` reading the symbols `s0, ..., sk` is
+/// `q * n^(k+1) + s0 * n^k + ... + sk`, where `n` is the number of symbols.
+///
+#[derive(Clone, Debug)]
+pub struct CompiledMachine {
+    pub states: Vec<String>,
+    pub symbols: Vec<String>,
+    pub tape_count: usize,
+    pub initial_state: u32,
+    pub blank_symbol: u32,
+    pub accept_state: Option<u32>,
+    pub reject_state: Option<u32>,
+    pub final_states: Vec<bool>,
+    pub next_states: Vec<u32>,
+    pub writes: Vec<u32>,
+    pub moves: Vec<i8>,
+}
+
+/// Returns the number of a name, adding it to the names if it is new.
+fn intern(
+    name: &String,
+    names: &mut Vec<String>,
+    numbers: &mut std::collections::HashMap<String, u32>,
+) -> u32 {
+    if let Some(number) = numbers.get(name) {
+        return *number;
+    }
+    names.push(name.clone());
+    numbers.insert(name.clone(), names.len() as u32 - 1);
+    names.len() as u32 - 1
+}
+
+impl CompiledMachine {
+    /// Compiles a deterministic Turing machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `tm` - The machine to compile, assumed to be deterministic
+    /// * `input` - The symbols which will be on the first tape, interned along with the ones of
+    ///   the machine
+    ///
+    /// # Returns
+    ///
+    /// * `Some(CompiledMachine)` - The compiled machine
+    /// * `None` - If a transition does not have one symbol and direction for each tape, or if
+    ///   the transition table would be too large
+    pub fn new(tm: &turing_machine::TuringMachine, input: &[String]) -> Option<CompiledMachine> {
+        let mut states = Vec::new();
+        let mut state_numbers = std::collections::HashMap::new();
+        let mut symbols = Vec::new();
+        let mut symbol_numbers = std::collections::HashMap::new();
+        let initial_state = intern(&tm.initial_state, &mut states, &mut state_numbers);
+        let blank_symbol = intern(&tm.blank_symbol, &mut symbols, &mut symbol_numbers);
+        for state in tm.states.iter().chain(tm.final_states().iter()) {
+            intern(state, &mut states, &mut state_numbers);
+        }
+        for symbol in tm.tape_alphabet.iter().chain(input.iter()) {
+            intern(symbol, &mut symbols, &mut symbol_numbers);
+        }
+        for t in tm.transitions.iter() {
+            if t.symbols.len() != tm.tape_count
+                || t.new_symbols.len() != tm.tape_count
+                || t.directions.len() != tm.tape_count
+            {
+                return None;
+            }
+            intern(&t.state, &mut states, &mut state_numbers);
+            intern(&t.new_state, &mut states, &mut state_numbers);
+            for symbol in t.symbols.iter().chain(t.new_symbols.iter()) {
+                intern(symbol, &mut symbols, &mut symbol_numbers);
+            }
+        }
+
+        let entries = (0..tm.tape_count).try_fold(states.len(), |size, _| {
+            size.checked_mul(symbols.len())
+                .filter(|size| *size <= MAX_TABLE_SIZE)
+        })?;
+        let mut machine = CompiledMachine {
+            final_states: states.iter().map(|s| tm.is_final(s)).collect(),
+            accept_state: state_numbers.get(&tm.accept_state).copied(),
+            reject_state: state_numbers.get(&tm.reject_state).copied(),
+            states,
+            symbols,
+            tape_count: tm.tape_count,
+            initial_state,
+            blank_symbol,
+            next_states: vec![NO_TRANSITION; entries],
+            writes: vec![0; entries * tm.tape_count],
+            moves: vec![0; entries * tm.tape_count],
+        };
+        for t in tm.transitions.iter() {
+            let read: Vec<u32> = t.symbols.iter().map(|s| symbol_numbers[s]).collect();
+            let entry = machine.entry(state_numbers[&t.state], &read);
+            if machine.next_states[entry] != NO_TRANSITION {
+                continue;
+            }
+            machine.next_states[entry] = state_numbers[&t.new_state];
+            for (tape, (symbol, direction)) in
+                t.new_symbols.iter().zip(t.directions.iter()).enumerate()
+            {
+                machine.writes[entry * tm.tape_count + tape] = symbol_numbers[symbol];
+                machine.moves[entry * tm.tape_count + tape] = match direction {
+                    turing_machine::Direction::Left => -1,
+                    turing_machine::Direction::Stay => 0,
+                    turing_machine::Direction::Right => 1,
+                };
+            }
+        }
+        Some(machine)
+    }
+
+    /// Computes the entry of the transition table of a state reading some symbols.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The number of the state
+    /// * `read` - The numbers of the symbols read, one for each tape
+    ///
+    /// # Returns
+    ///
+    /// The index of the entry in `next_states`.
+    pub fn entry(&self, state: u32, read: &[u32]) -> usize {
+        read.iter().fold(state as usize, |entry, symbol| {
+            entry * self.symbols.len() + *symbol as usize
+        })
+    }
+
+    /// Returns the outcome of a computation stopped in a state, reading the given symbols.
+    ///
+    /// # Returns
+    ///
+    /// The outcome of the final state reached, `Stuck` if the state has no transition on the
+    /// symbols, `Timeout` otherwise.
+    pub fn outcome(&self, state: u32, read: &[u32]) -> computer::Outcome {
+        if Some(state) == self.accept_state {
+            computer::Outcome::Accept
+        } else if Some(state) == self.reject_state {
+            computer::Outcome::Reject
+        } else if self.final_states[state as usize] {
+            computer::Outcome::Halt
+        } else if self.next_states[self.entry(state, read)] == NO_TRANSITION {
+            computer::Outcome::Stuck(self.states[state as usize].clone())
+        } else {
+            computer::Outcome::Timeout
+        }
+    }
+
+    /// Records a configuration as an event of the computation history.
+    fn trace_event(
+        &self,
+        configuration: &Configuration,
+        transition: Option<turing_machine::Transition>,
+    ) -> trace::TraceEvent {
+        trace::TraceEvent::Tm {
+            depth: 0,
+            state: self.states[configuration.state as usize].clone(),
+            tapes: configuration
+                .tapes
+                .iter()
+                .zip(configuration.heads.iter())
+                .map(|(tape, head)| turing_machine::Tape {
+                    tape: tape
+                        .iter()
+                        .map(|symbol| self.symbols[*symbol as usize].clone())
+                        .collect(),
+                    head: *head,
+                })
+                .collect(),
+            transition,
+        }
+    }
+
+    /// Rebuilds the transition of an entry of the table, reading the given symbols.
+    fn transition(&self, state: u32, read: &[u32], entry: usize) -> turing_machine::Transition {
+        let tapes = entry * self.tape_count..(entry + 1) * self.tape_count;
+        turing_machine::Transition {
+            state: self.states[state as usize].clone(),
+            symbols: read
+                .iter()
+                .map(|symbol| self.symbols[*symbol as usize].clone())
+                .collect(),
+            new_state: self.states[self.next_states[entry] as usize].clone(),
+            new_symbols: self.writes[tapes.clone()]
+                .iter()
+                .map(|symbol| self.symbols[*symbol as usize].clone())
+                .collect(),
+            directions: self.moves[tapes]
+                .iter()
+                .map(|direction| match direction {
+                    -1 => turing_machine::Direction::Left,
+                    1 => turing_machine::Direction::Right,
+                    _ => turing_machine::Direction::Stay,
+                })
+                .collect(),
+        }
+    }
+
+    /// Simulates the compiled machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `tape` - The initial content of the first tape, whose symbols must have been interned
+    /// * `head` - The initial position of the head of the first tape
+    /// * `max_steps` - Maximum number of steps the simulation should run before stopping
+    /// * `trace` - Whether to record the computation history
+    /// * `detect_loops` - Whether to stop as soon as the computation is proved to diverge
+    ///
+    /// # Returns
+    ///
+    /// A `SimulationResult` containing:
+    /// - The outcome of the computation, with the proof that it diverges if it was found
+    /// - Final head position
+    /// - Final tape contents
+    /// - Number of steps executed
+    /// - Vector of computation history, one event for each configuration, empty if not traced
+    ///
+    /// # Notes
+    ///
+    /// - Like the generic simulator, the steps count the configurations reached, the initial one
+    ///   included, so at most `max_steps - 1` moves are made
+    /// - The tapes are extended with a blank symbol whenever a head moves past one of their ends
+    pub fn simulate(
+        &self,
+        tape: &[String],
+        head: usize,
+        max_steps: usize,
+        trace: bool,
+        detect_loops: bool,
+    ) -> computer::SimulationResult {
+        let numbers: std::collections::HashMap<&String, u32> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(number, symbol)| (symbol, number as u32))
+            .collect();
+        let mut configuration = Configuration {
+            state: self.initial_state,
+            heads: vec![head],
+            tapes: vec![tape.iter().map(|s| numbers[s]).collect()],
+        };
+        for _ in 1..self.tape_count {
+            configuration
+                .tapes
+                .push(std::collections::VecDeque::from([self.blank_symbol]));
+            configuration.heads.push(0);
+        }
+        let mut computation = Vec::new();
+        if trace {
+            computation.push(self.trace_event(&configuration, None));
+        }
+        let mut cycles = loop_detector::CycleDetector::new();
+        let mut translations = loop_detector::TranslationDetector::new(self, tape.len(), head);
+        // the cells added to the front of the first tape
+        let mut origin = 0;
+        let mut proof = None;
+        let mut moves = 0;
+        let mut read = vec![0; self.tape_count];
+        loop {
+            if detect_loops {
+                proof = cycles.observe(moves, &configuration).or_else(|| {
+                    if self.tape_count > 1 {
+                        return None;
+                    }
+                    translations.observe(
+                        moves,
+                        configuration.state,
+                        configuration.heads[0] as i64 - origin,
+                        &configuration.tapes[0],
+                        origin,
+                    )
+                });
+            }
+            let Configuration {
+                state,
+                heads,
+                tapes,
+            } = &mut configuration;
+            if proof.is_some() || self.final_states[*state as usize] {
+                break;
+            }
+            for (tape, symbol) in read.iter_mut().enumerate() {
+                *symbol = tapes[tape][heads[tape]];
+            }
+            let entry = self.entry(*state, &read);
+            let next_state = self.next_states[entry];
+            if next_state == NO_TRANSITION || moves + 1 >= max_steps {
+                break;
+            }
+            let transition = trace.then(|| self.transition(*state, &read, entry));
+            for tape in 0..self.tape_count {
+                let cells = &mut tapes[tape];
+                cells[heads[tape]] = self.writes[entry * self.tape_count + tape];
+                match self.moves[entry * self.tape_count + tape] {
+                    -1 if heads[tape] == 0 => {
+                        cells.push_front(self.blank_symbol);
+                        if tape == 0 {
+                            origin += 1;
+                        }
+                    }
+                    -1 => heads[tape] -= 1,
+                    1 => {
+                        if heads[tape] == cells.len() - 1 {
+                            cells.push_back(self.blank_symbol);
+                        }
+                        heads[tape] += 1;
+                    }
+                    _ => {}
+                }
+            }
+            *state = next_state;
+            moves += 1;
+            if trace {
+                computation.push(self.trace_event(&configuration, transition));
+            }
+        }
+
+        let Configuration {
+            state,
+            heads,
+            tapes,
+        } = configuration;
+        let outcome = match proof {
+            Some(proof) => computer::Outcome::Diverges(proof),
+            None => {
+                for (tape, symbol) in read.iter_mut().enumerate() {
+                    *symbol = tapes[tape][heads[tape]];
+                }
+                self.outcome(state, &read)
+            }
+        };
+        let tape = tapes[0]
+            .iter()
+            .map(|symbol| self.symbols[*symbol as usize].clone())
+            .collect();
+        computer::SimulationResult {
+            outcome,
+            head: heads[0],
+            tape,
+            steps: moves + 1,
+            computation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_counter() -> turing_machine::TuringMachine {
+        // increments a binary number, starting on the blank before it
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = "_".to_string();
+        tm.initial_state = "start".to_string();
+        tm.halt_state = "done".to_string();
+        tm.states = vec![
+            "start".to_string(),
+            "right".to_string(),
+            "carry".to_string(),
+            "done".to_string(),
+        ];
+        tm.input_alphabet = vec!["0".to_string(), "1".to_string()];
+        tm.tape_alphabet = vec!["_".to_string(), "0".to_string(), "1".to_string()];
+        use turing_machine::Direction::{Left, Right, Stay};
+        for (state, read, new_state, write, direction) in [
+            ("start", "_", "right", "_", Right),
+            ("right", "0", "right", "0", Right),
+            ("right", "1", "right", "1", Right),
+            ("right", "_", "carry", "_", Left),
+            ("carry", "1", "carry", "0", Left),
+            ("carry", "0", "done", "1", Stay),
+            ("carry", "_", "done", "1", Stay),
+        ] {
+            tm.add_transition(
+                state.to_string(),
+                vec![read.to_string()],
+                new_state.to_string(),
+                vec![write.to_string()],
+                vec![direction],
+            );
+        }
+        tm
+    }
+
+    fn tape(content: &str) -> Vec<String> {
+        content.chars().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_compile() {
+        let tm = binary_counter();
+        let machine = CompiledMachine::new(&tm, &tape("2")).unwrap();
+        assert_eq!(machine.states, vec!["start", "right", "carry", "done"]);
+        assert_eq!(machine.symbols, vec!["_", "0", "1", "2"]);
+        assert_eq!(machine.next_states.len(), 16);
+        assert_eq!(machine.next_states[machine.entry(0, &[0])], 1);
+        assert_eq!(machine.next_states[machine.entry(0, &[3])], NO_TRANSITION);
+        assert!(machine.final_states[3]);
+        assert_eq!(machine.accept_state, None);
+
+        let mut broken = tm.clone();
+        broken.transitions[0].directions.clear();
+        assert!(CompiledMachine::new(&broken, &[]).is_none());
+    }
+
+    #[test]
+    fn test_simulate() {
+        let machine = CompiledMachine::new(&binary_counter(), &[]).unwrap();
+        let computer::SimulationResult {
+            outcome: state,
+            head,
+            tape: result,
+            steps,
+            computation,
+        } = machine.simulate(&tape("_11"), 0, 1000, true, false);
+        assert_eq!(state, computer::Outcome::Halt);
+        assert_eq!(result.join(""), "100_");
+        assert_eq!(head, 0);
+        assert_eq!(steps, 8);
+        assert_eq!(computation.len(), 8);
+        assert_eq!(computation[0].to_string(), "tm;start;_11");
+        assert_eq!(computation[7].to_string(), "tm;done;100_");
+        match &computation[4] {
+            trace::TraceEvent::Tm {
+                tapes, transition, ..
+            } => {
+                assert_eq!(tapes[0].head, 2);
+                assert_eq!(
+                    transition.as_ref().unwrap().to_string(),
+                    "right carry _ _ L"
+                );
+            }
+            _ => panic!("expected a Turing machine event"),
+        }
+
+        let computer::SimulationResult {
+            outcome: state,
+            head,
+            tape: result,
+            steps,
+            computation,
+        } = machine.simulate(&tape("_11"), 0, 3, false, false);
+        assert_eq!(state, computer::Outcome::Timeout);
+        assert_eq!(result.join(""), "_11");
+        assert_eq!(head, 2);
+        assert_eq!(steps, 3);
+        assert!(computation.is_empty());
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        use turing_machine::Direction::{Left, Right};
+        let run = |rules: &[(&str, &str, &str, &str, turing_machine::Direction)]| {
+            let mut tm = turing_machine::TuringMachine::new();
+            tm.blank_symbol = "_".to_string();
+            tm.initial_state = "A".to_string();
+            tm.tape_alphabet = vec!["_".to_string(), "0".to_string(), "1".to_string()];
+            for (state, read, new_state, write, direction) in rules {
+                for state in [state, new_state] {
+                    if !tm.states.contains(&state.to_string()) {
+                        tm.states.push(state.to_string());
+                    }
+                }
+                tm.add_transition(
+                    state.to_string(),
+                    vec![read.to_string()],
+                    new_state.to_string(),
+                    vec![write.to_string()],
+                    vec![direction.clone()],
+                );
+            }
+            let machine = CompiledMachine::new(&tm, &[]).unwrap();
+            machine.simulate(&tape("_"), 0, 10000, false, true)
+        };
+
+        let computer::SimulationResult {
+            outcome: state,
+            head,
+            steps,
+            ..
+        } = run(&[("A", "_", "B", "_", Right), ("B", "_", "A", "_", Left)]);
+        assert_eq!(
+            state,
+            computer::Outcome::Diverges("cycle of period 2 from step 1".to_string())
+        );
+        assert_eq!(head, 1);
+        assert_eq!(steps, 4);
+
+        let computer::SimulationResult {
+            outcome: state,
+            tape: result,
+            ..
+        } = run(&[
+            ("A", "_", "B", "1", Right),
+            ("B", "_", "C", "0", Left),
+            ("C", "1", "D", "1", Right),
+            ("D", "0", "A", "0", Right),
+        ]);
+        assert!(
+            matches!(
+                &state,
+                computer::Outcome::Diverges(proof)
+                    if proof.starts_with("translated cycle of period 4 and shift 2 from step ")
+            ),
+            "{}",
+            state
+        );
+        assert!(result.join("").starts_with("1010"));
+
+        let computer::SimulationResult {
+            outcome: state,
+            steps,
+            ..
+        } = run(&[("A", "_", "B", "1", Right), ("B", "_", "A", "0", Right)]);
+        assert_eq!(
+            state,
+            computer::Outcome::Diverges("tape grows forever from step 1".to_string())
+        );
+        assert_eq!(steps, 2);
+        let computer::SimulationResult {
+            outcome: state,
+            head,
+            ..
+        } = run(&[("A", "_", "A", "1", Left)]);
+        assert_eq!(
+            state,
+            computer::Outcome::Diverges("tape grows forever from step 1".to_string())
+        );
+        assert_eq!(head, 0);
+
+        // counts in binary forever, without ever repeating itself
+        let computer::SimulationResult {
+            outcome: state,
+            steps,
+            ..
+        } = run(&[
+            ("A", "_", "R", "_", Right),
+            ("R", "0", "R", "0", Right),
+            ("R", "1", "R", "1", Right),
+            ("R", "_", "C", "_", Left),
+            ("C", "1", "C", "0", Left),
+            ("C", "0", "R", "1", Right),
+            ("C", "_", "R", "1", Right),
+        ]);
+        assert_eq!(state, computer::Outcome::Timeout);
+        assert_eq!(steps, 10000);
+
+        let machine = CompiledMachine::new(&binary_counter(), &[]).unwrap();
+        assert_eq!(
+            machine.simulate(&tape("_11"), 0, 1000, true, true),
+            machine.simulate(&tape("_11"), 0, 1000, true, false)
+        );
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module provides core functionality for simulating various types of computing machines
 //! and managing their interactions. It implements a unified interface for different computational
-//! models including RAM machines, Turing machines, and Lambda calculus.
+//! models including RAM machines, Turing machines, finite automata, and Lambda calculus.
 //!
 //! ## Core Components
 //!
@@ -15,6 +15,8 @@
 //! * Supports multiple computation models:
 //!   - RAM machines
 //!   - Turing machines (single and multi-tape)
+//!   - Finite automata (DFA, NFA and ε-NFA)
+//!   - Pushdown automata
 //!   - Lambda calculus
 //! * Conversion between different computation models
 //! * Simulation of computations with step limits
@@ -24,7 +26,9 @@
 //! ## Type Definitions
 //!
 //! * `EncodingResult` - Represents the result of encoding a computer
-//! * `SimulationResult` - Contains the results of a simulation including state, output, and steps
+//! * `SimulationResult` - Contains the results of a simulation including outcome, output, and steps
+//! * `Outcome` - How a simulation ended: accepting, rejecting, halting, running out of steps,
+//!   stuck in a state without transitions, or proved to diverge
 //!
 //! ## Key Structures
 //!
@@ -42,6 +46,7 @@
 //! * RAM Machines to Turing machine
 //! * Turing machine to RAM machine
 //! * Multi-tape to single-tape Turing machine
+//! * Finite automata to Turing machine
 //!
 //! ## Error Handling
 //!
@@ -57,9 +62,13 @@
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
 use crate::file_handler;
+use crate::finite_automaton;
+use crate::grammar;
 use crate::lambda;
 use crate::options;
 use crate::ram_machine;
+use crate::pushdown_automaton;
+use crate::trace;
 use crate::turing_machine;
 use crate::utils;
 
@@ -75,11 +84,17 @@ pub type EncodingResult = (
 /// * RAM machines - Basic register-based computational model
 /// * Turing machines - Standard and multi-tape variants
 /// * Lambda calculus - Functional computation model
+/// * Finite automata - Deterministic and non-deterministic finite automata, with ε-moves
+/// * Pushdown automata - Non-deterministic pushdown automata, accepting by final state or empty stack
+/// * Context-free grammars - Grammars decided with the CYK and Earley algorithms
 ///
 /// Each variant contains the corresponding machine implementation:
 /// * `Ram` - Contains a boxed `RamMachine` instance
 /// * `Tm` - Contains a boxed `TuringMachine` instance
 /// * `Lambda` - Contains a boxed `Lambda` instance for lambda calculus computations
+/// * `Fsm` - Contains a boxed `FiniteAutomaton` instance
+/// * `Pda` - Contains a boxed `PushdownAutomaton` instance
+/// * `Cfg` - Contains a boxed `Grammar` instance
 ///
 /// The enum implements `Clone` to allow duplication of computing elements when needed.
 #[derive(Clone)]
@@ -87,18 +102,23 @@ pub enum ComputingElem {
     Ram(Box<ram_machine::RamMachine>),
     Tm(Box<turing_machine::TuringMachine>),
     Lambda(Box<lambda::Lambda>),
+    Fsm(Box<finite_automaton::FiniteAutomaton>),
+    Pda(Box<pushdown_automaton::PushdownAutomaton>),
+    Cfg(Box<grammar::Grammar>),
 }
 
 /// A structure representing a computing machine with its associated mappings and configuration.
 ///
 /// The `Computer` struct serves as a container for different types of computing elements
-/// (RAM machines, Turing machines, or Lambda calculus) and maintains their associated mappings.
+/// (RAM machines, Turing machines, Lambda calculus, finite automata, pushdown automata or
+/// context-free grammars) and maintains their associated mappings.
 /// It provides a unified interface for working with different computational models and
 /// managing their state and behavior.
 ///
 /// # Fields
 ///
-/// * `element` - The core computing element (RAM, Turing machine, or Lambda calculus)
+/// * `element` - The core computing element (RAM, Turing machine, Lambda calculus, finite automaton,
+///   pushdown automaton or grammar)
 /// * `mapping` - A HashMap containing name-value pairs for subroutines calling, mapping internal names to computer names in the context
 ///
 /// # Features
@@ -128,7 +148,65 @@ pub struct Computer {
     pub mapping: std::collections::HashMap<String, String>,
 }
 
-pub type SimulationResult = (String, usize, Vec<String>, usize, Vec<String>);
+/// How a simulation ended.
+///
+/// # Variants
+/// * `Accept` - The input was accepted
+/// * `Reject` - The input was rejected
+/// * `Halt` - The computation stopped in a halting state, or reached its result
+/// * `Timeout` - The maximum number of steps was reached
+/// * `Stuck` - The computation stopped in the given state, which is not final, without a
+///   transition to follow
+/// * `Diverges` - The computation was proved to never halt, with the given proof
+///
+/// The outcomes are displayed as `accept`, `reject`, `halt`, `timeout`, the name of the state
+/// and `diverges (proof: <proof>)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Accept,
+    Reject,
+    Halt,
+    Timeout,
+    Stuck(String),
+    Diverges(String),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Accept => write!(f, "accept"),
+            Outcome::Reject => write!(f, "reject"),
+            Outcome::Halt => write!(f, "halt"),
+            Outcome::Timeout => write!(f, "timeout"),
+            Outcome::Stuck(state) => write!(f, "{}", state),
+            Outcome::Diverges(proof) => write!(f, "diverges (proof: {})", proof),
+        }
+    }
+}
+
+/// The result of a simulation.
+///
+/// # Fields
+/// * `outcome` - How the simulation ended
+/// * `head` - The final position of the head
+/// * `tape` - The final content of the tape, or the output of the computation
+/// * `steps` - The number of steps executed
+/// * `computation` - The computation history, empty if not recorded
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationResult {
+    pub outcome: Outcome,
+    pub head: usize,
+    pub tape: Vec<String>,
+    pub steps: usize,
+    pub computation: Vec<trace::TraceEvent>,
+}
+
+impl SimulationResult {
+    /// Returns the content of the tape as a single string.
+    pub fn output(&self) -> String {
+        self.tape.join("")
+    }
+}
 
 /// A management structure that coordinates multiple computing machines and their execution sequence.
 ///
@@ -140,6 +218,8 @@ pub type SimulationResult = (String, usize, Vec<String>, usize, Vec<String>);
 ///
 /// * `map_computers` - A HashMap storing computing machines indexed by their names
 /// * `computation_order` - A vector defining the sequence of computer executions
+/// * `simulation` - The options with which the files are read and the computers are simulated
+///   in this context
 ///
 /// # Features
 ///
@@ -152,12 +232,12 @@ pub type SimulationResult = (String, usize, Vec<String>, usize, Vec<String>);
 ///
 /// # Returns
 ///
-/// The `execute` method returns a `Result` containing:
-/// * Final state of computation
-/// * Final head position
-/// * Output string
-/// * Total steps executed
-/// * Vector of computation log entries
+/// The `execute` method returns a `Result` containing a `SimulationResult` with:
+/// * `outcome` - How the last computer of the chain ended
+/// * `head` - The final head position of the last computer
+/// * `tape` - The output of the last computer
+/// * `steps` - The total number of steps executed
+/// * `computation` - The computation history of all the computers
 ///
 /// # Error Handling
 ///
@@ -176,6 +256,54 @@ pub type SimulationResult = (String, usize, Vec<String>, usize, Vec<String>);
 pub struct Server {
     pub map_computers: std::collections::HashMap<String, Computer>,
     pub computation_order: Vec<String>,
+    pub simulation: SimulationOptions,
+}
+
+/// The options with which a `Server` reads files and simulates its computers.
+///
+/// # Fields
+///
+/// * `regex_dfa` - Whether the `regex` files are converted into DFAs with Brzozowski derivatives
+///   instead of the Thompson construction
+/// * `regex_alphabet` - The symbols, besides their own, over which the negated classes and the
+///   wildcards of the `regex` files range
+/// * `strategy` - The order in which the configurations of non-deterministic Turing machines
+///   are explored
+/// * `trace` - Whether the simulations of deterministic Turing machines and RAM machines record
+///   their computation history, which long runs cannot afford
+/// * `block_size` - If greater than 0, the number of cells of the blocks with which the
+///   simulations of deterministic single-tape Turing machines are accelerated
+/// * `detect_loops` - Whether the simulations of deterministic Turing machines and RAM machines
+///   stop as soon as they are proved to diverge
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationOptions {
+    pub regex_dfa: bool,
+    pub regex_alphabet: Vec<String>,
+    pub strategy: turing_machine::Strategy,
+    pub trace: bool,
+    pub block_size: usize,
+    pub detect_loops: bool,
+}
+
+impl Default for SimulationOptions {
+    /// The Thompson construction, breadth-first search and a recorded computation history,
+    /// without acceleration nor loop detection.
+    fn default() -> Self {
+        SimulationOptions {
+            regex_dfa: false,
+            regex_alphabet: Vec::new(),
+            strategy: turing_machine::Strategy::Bfs,
+            trace: true,
+            block_size: 0,
+            detect_loops: false,
+        }
+    }
+}
+
+impl Default for Computer {
+    fn default() -> Self {
+        Computer::new()
+    }
 }
 
 impl Computer {
@@ -191,6 +319,9 @@ impl Computer {
             ComputingElem::Ram(_) => true,
             ComputingElem::Tm(_) => false,
             ComputingElem::Lambda(_) => false,
+            ComputingElem::Fsm(_) => false,
+            ComputingElem::Pda(_) => false,
+            ComputingElem::Cfg(_) => false,
         }
     }
 
@@ -269,7 +400,7 @@ impl Computer {
     ///
     pub fn new() -> Computer {
         Computer {
-            element: ComputingElem::Tm(Box::new(turing_machine::TuringMachine::new())),
+            element: ComputingElem::Tm(Box::default()),
             mapping: std::collections::HashMap::new(),
         }
     }
@@ -292,15 +423,20 @@ impl Computer {
     /// - For Turing machines: Uses the TM-specific encoding format
     /// - For RAM machines: Uses the RAM-specific encoding format
     /// - For Lambda calculus: Returns the string representation with empty mappings
+    /// - For finite and pushdown automata: Uses the TM-specific encoding format of the equivalent Turing machine
+    /// - For grammars: Returns an error, as grammars have no encoding
     pub fn to_encoding(&self) -> Result<EncodingResult, String> {
         match &self.element {
             ComputingElem::Tm(m) => m.to_encoding(),
+            ComputingElem::Fsm(a) => a.to_tm().to_encoding(),
+            ComputingElem::Pda(a) => a.to_tm().to_encoding(),
             ComputingElem::Ram(m) => m.to_encoding(),
             ComputingElem::Lambda(l) => Ok((
                 l.to_string(),
                 std::collections::HashMap::new(),
                 std::collections::HashMap::new(),
             )),
+            ComputingElem::Cfg(_) => Err("cannot encode a grammar".to_string()),
         }
     }
 
@@ -342,6 +478,60 @@ impl Computer {
         self.element = ComputingElem::Lambda(Box::new(lambda));
     }
 
+    /// Sets the computer's computing element to a finite automaton.
+    ///
+    /// This method updates the computer's internal element to use the provided finite automaton,
+    /// replacing any existing computing element.
+    ///
+    /// # Arguments
+    ///
+    /// * `automaton` - A finite automaton instance to be set as the computer's computing element
+    ///
+    pub fn set_fsm(&mut self, automaton: finite_automaton::FiniteAutomaton) {
+        self.element = ComputingElem::Fsm(Box::new(automaton));
+    }
+
+    /// Sets the computer's computing element to a pushdown automaton.
+    ///
+    /// This method updates the computer's internal element to use the provided pushdown automaton,
+    /// replacing any existing computing element.
+    ///
+    /// # Arguments
+    ///
+    /// * `automaton` - A pushdown automaton instance to be set as the computer's computing element
+    ///
+    pub fn set_pda(&mut self, automaton: pushdown_automaton::PushdownAutomaton) {
+        self.element = ComputingElem::Pda(Box::new(automaton));
+    }
+
+    /// Sets the computer's computing element to a context-free grammar.
+    ///
+    /// This method updates the computer's internal element to use the provided grammar,
+    /// replacing any existing computing element.
+    ///
+    /// # Arguments
+    ///
+    /// * `grammar` - A grammar instance to be set as the computer's computing element
+    ///
+    pub fn set_cfg(&mut self, grammar: grammar::Grammar) {
+        self.element = ComputingElem::Cfg(Box::new(grammar));
+    }
+
+    /// Returns the finite automaton held by the computer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FiniteAutomaton)` - A copy of the automaton, or the automaton read back from a
+    ///   Turing machine built like the ones produced from `fsm` and `regex` files
+    /// * `Err(String)` - An error message if the computing element is not a finite automaton
+    pub fn to_fsm(&self) -> Result<finite_automaton::FiniteAutomaton, String> {
+        match &self.element {
+            ComputingElem::Fsm(a) => Ok(*a.clone()),
+            ComputingElem::Tm(m) => finite_automaton::FiniteAutomaton::from_tm(m),
+            _ => Err("not a finite automaton".to_string()),
+        }
+    }
+
     /// Simulates the execution of the current computing element with the given input.
     ///
     /// This method runs the simulation of the computer's computing element (RAM machine,
@@ -366,8 +556,12 @@ impl Computer {
     ///
     /// The behavior varies depending on the type of computing element:
     /// - For RAM machines: Processes the input as RAM instructions
-    /// - For Turing machines: Converts input to tape symbols and simulates TM execution
+    /// - For Turing machines: Converts input to tape symbols and simulates TM execution, exploring
+    ///   non-deterministic machines with the strategy of the context
     /// - For Lambda calculus: Evaluates the lambda expression
+    /// - For finite automata: Reads the input and leaves it, and the head position, unchanged
+    /// - For pushdown automata: Reads the input tracing the stack, leaving the input and the head position unchanged
+    /// - For grammars: Decides membership with the Earley algorithm, leaving the input unchanged
     pub fn simulate(
         &self,
         input: &str,
@@ -390,6 +584,27 @@ impl Computer {
                 };
                 l_new.simulate(max_steps)
             }
+            ComputingElem::Fsm(a) => {
+                let input_vec = utils::input_string_to_vec(a.input_alphabet.clone(), input.to_string());
+                Ok(SimulationResult {
+                    head,
+                    ..a.simulate(input_vec, max_steps)?
+                })
+            }
+            ComputingElem::Pda(a) => {
+                let input_vec = utils::input_string_to_vec(a.input_alphabet.clone(), input.to_string());
+                Ok(SimulationResult {
+                    head,
+                    ..a.simulate(input_vec, max_steps)?
+                })
+            }
+            ComputingElem::Cfg(g) => {
+                let input_vec = utils::input_string_to_vec(g.terminals.clone(), input.to_string());
+                Ok(SimulationResult {
+                    head,
+                    ..g.simulate(input_vec)?
+                })
+            }
         }
     }
 
@@ -458,6 +673,9 @@ impl Computer {
     /// - Preserves the original RAM program's behavior
     /// - Maintains instruction mappings
     ///
+    /// For finite automata:
+    /// - Uses the construction of `FiniteAutomaton::to_tm`, reading a symbol per step
+    ///
     /// For Turing machines:
     /// - Returns an error as conversion is unnecessary
     ///
@@ -496,6 +714,9 @@ impl Computer {
                 match self.element.clone() {
                     ComputingElem::Ram(_) => return Err("something went wrong".to_string()),
                     ComputingElem::Lambda(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Fsm(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Pda(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Cfg(_) => return Err("something went wrong".to_string()),
                     ComputingElem::Tm(m) => {
                         let mut this = m.clone();
                         let old_transitions = m.transitions.clone();
@@ -648,6 +869,15 @@ impl Computer {
                 Ok(self.clone())
             }
             ComputingElem::Tm(_) => Err("already TM".to_string()),
+            ComputingElem::Fsm(a) => {
+                self.set_turing(a.to_tm());
+                Ok(self.clone())
+            }
+            ComputingElem::Pda(a) => {
+                self.set_turing(a.to_tm());
+                Ok(self.clone())
+            }
+            ComputingElem::Cfg(_) => Err("cannot convert a grammar to a TM".to_string()),
             ComputingElem::Ram(m) => {
                 options.file = "src/standard/ram over tm.tm".to_string();
                 options.input = options.input.clone() + &(m.to_encoding()?).0;
@@ -661,6 +891,9 @@ impl Computer {
                 match self.element.clone() {
                     ComputingElem::Lambda(_) => return Err("something went wrong".to_string()),
                     ComputingElem::Ram(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Fsm(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Pda(_) => return Err("something went wrong".to_string()),
+                    ComputingElem::Cfg(_) => return Err("something went wrong".to_string()),
                     ComputingElem::Tm(mut m) => {
                         m.add_transition(
                             (131).to_string(),
@@ -848,6 +1081,10 @@ impl Computer {
     /// - Then converts the resulting TM to RAM
     /// - Note: the feature is higly experimental and is inefficent. Use with caution.
     ///
+    /// For finite automata:
+    /// - First converts to Turing machine
+    /// - Then converts the resulting TM to RAM
+    ///
     /// For RAM machines:
     /// - Returns an error as conversion is unnecessary
     ///
@@ -1000,6 +1237,9 @@ impl Computer {
                     }
                     ComputingElem::Tm(_) => Err("something went wrong".to_string()),
                     ComputingElem::Lambda(_) => Err("something went wrong".to_string()),
+                    ComputingElem::Fsm(_) => Err("something went wrong".to_string()),
+                    ComputingElem::Pda(_) => Err("something went wrong".to_string()),
+                    ComputingElem::Cfg(_) => Err("something went wrong".to_string()),
                 }
             }
             ComputingElem::Lambda(_) => {
@@ -1007,11 +1247,22 @@ impl Computer {
                 self.convert_to_singletape()?;
                 self.to_ram(options, s)
             }
+            ComputingElem::Fsm(_) | ComputingElem::Pda(_) => {
+                *self = self.to_tm(options, s)?;
+                self.to_ram(options, s)
+            }
+            ComputingElem::Cfg(_) => Err("cannot convert a grammar to a RAM".to_string()),
         }
     }
 }
 
 /// Implementation of the Server struct which manages multiple computing elements
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
 impl Server {
     /// Creates a new empty Server instance
     ///
@@ -1021,6 +1272,7 @@ impl Server {
         Server {
             map_computers: std::collections::HashMap::new(),
             computation_order: Vec::new(),
+            simulation: SimulationOptions::default(),
         }
     }
 
@@ -1094,8 +1346,13 @@ impl Server {
     /// * `max_steps` - Maximum number of computation steps allowed
     ///
     /// # Returns
-    /// * `Result<(String, usize, String, usize, Vec<String>), String>` - On success returns
-    ///   (final_state, head_position, output, steps_taken, computation_trace)
+    /// * `Result<SimulationResult, String>` - On success returns a `SimulationResult` with:
+    ///   - `outcome` - How the last computer ended
+    ///   - `head` - The head position of the last computer
+    ///   - `tape` - The output of the last computer, without the blank symbols of Turing machines
+    ///   - `steps` - The steps taken by all the computers
+    ///   - `computation` - The computation history of all the computers
+    ///
     ///   On error returns error message as string
     ///
     /// # Errors
@@ -1106,12 +1363,14 @@ impl Server {
         &self,
         input: &str,
         max_steps: usize,
-    ) -> Result<(String, usize, String, usize, Vec<String>), String> {
-        let mut steps: usize = 0;
-        let mut output: String = input.to_string();
-        let mut final_state = String::new();
-        let mut current_head = 0;
-        let mut tot_comp = Vec::new();
+    ) -> Result<SimulationResult, String> {
+        let mut result = SimulationResult {
+            outcome: Outcome::Halt,
+            head: 0,
+            tape: vec![input.to_string()],
+            steps: 0,
+            computation: Vec::new(),
+        };
         if self.map_computers.is_empty() {
             return Err("empty server".to_string());
         }
@@ -1125,14 +1384,18 @@ impl Server {
             let computer = self.get_computer(name).ok_or_else(|| {
                 format!("cannot find computer with name '{}'", name)
             })?;
-            let (state, head, tape, s, computation) =
-                computer
-                    .simulate(&output, max_steps.saturating_sub(steps), self, current_head)?;
-            final_state = state;
-            current_head = head;
-            output = tape.join("");
-            steps += s;
-            tot_comp.extend(computation);
+            let step = computer.simulate(
+                &result.output(),
+                max_steps.saturating_sub(result.steps),
+                self,
+                result.head,
+            )?;
+            result.computation.extend(step.computation);
+            result = SimulationResult {
+                steps: result.steps + step.steps,
+                computation: result.computation,
+                ..step
+            };
         }
         let last_name = &self.computation_order[self.computation_order.len() - 1];
         let last_computer = self
@@ -1141,15 +1404,17 @@ impl Server {
         match &last_computer.element {
             ComputingElem::Lambda(_) => {}
             ComputingElem::Ram(_) => {}
+            ComputingElem::Fsm(_) => {}
+            ComputingElem::Pda(_) => {}
+            ComputingElem::Cfg(_) => {}
             ComputingElem::Tm(m) => {
-                output = utils::input_string_to_vec(m.tape_alphabet.clone(), output)
+                result.tape = utils::input_string_to_vec(m.tape_alphabet.clone(), result.output())
                     .into_iter()
                     .filter(|e| *e != m.blank_symbol)
-                    .collect::<Vec<String>>()
-                    .join("");
+                    .collect::<Vec<String>>();
             }
         }
-        Ok((final_state, current_head, output, steps, tot_comp))
+        Ok(result)
     }
 }
 
@@ -1411,7 +1676,7 @@ mod tests {
         computer.set_lambda(lambda);
 
         let context = Server::new();
-        let result = computer.simulate(&"(x)", 100, &context, 0);
+        let result = computer.simulate("(x)", 100, &context, 0);
         assert!(result.is_ok());
     }
 
@@ -1452,7 +1717,7 @@ mod tests {
         computer.set_ram(ram);
 
         let context = Server::new();
-        let result = computer.simulate(&"", 100, &context, 0);
+        let result = computer.simulate("", 100, &context, 0);
         assert!(result.is_ok());
     }
 
@@ -1524,7 +1789,7 @@ mod tests {
 
         let context = Server::new();
 
-        let result = computer.simulate(&"test", 0, &context, 0);
+        let result = computer.simulate("test", 0, &context, 0);
         assert!(result.is_ok());
     }
     #[test]
@@ -1553,6 +1818,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1593,6 +1859,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1625,6 +1892,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1659,6 +1927,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1703,6 +1972,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1760,6 +2030,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1796,6 +2067,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1842,6 +2114,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1896,6 +2169,7 @@ mod tests {
             status: false,
             print_encoding: false,
             verbose: 1,
+            ..Default::default()
         };
         let mut server = Server::new();
 
@@ -1936,10 +2210,10 @@ mod tests {
         let result = server.execute("", 1000);
         assert!(result.is_ok());
 
-        if let Ok((state, _, output, _, comp)) = result {
-            assert!(!state.is_empty());
-            assert!(output.is_empty());
-            assert!(!comp.is_empty());
+        if let Ok(result) = result {
+            assert!(!result.outcome.to_string().is_empty());
+            assert!(result.output().is_empty());
+            assert!(!result.computation.is_empty());
         }
     }
 
@@ -1968,12 +2242,12 @@ mod tests {
         computer.set_turing(tm);
 
         let context = Server::new();
-        let result = computer.simulate(&"0", 100, &context, 0);
+        let result = computer.simulate("0", 100, &context, 0);
         assert!(result.is_ok());
-        if let Ok((state, _, tape, _, comp)) = result {
-            assert!(!state.is_empty());
-            assert!(!tape.is_empty());
-            assert!(!comp.is_empty());
+        if let Ok(result) = result {
+            assert!(!result.outcome.to_string().is_empty());
+            assert!(!result.output().is_empty());
+            assert!(!result.computation.is_empty());
         }
     }
 
@@ -1982,7 +2256,7 @@ mod tests {
         let computer = Computer::new();
         let context = Server::new();
 
-        let result = computer.simulate(&"", 100, &context, 0);
+        let result = computer.simulate("", 100, &context, 0);
         assert!(result.is_ok());
     }
 
@@ -2013,7 +2287,7 @@ mod tests {
         let computer = Computer::new();
         let context = Server::new();
 
-        let result = computer.simulate(&"test", 0, &context, 0);
+        let result = computer.simulate("test", 0, &context, 0);
         assert!(result.is_err());
     }
 
@@ -2057,7 +2331,7 @@ mod tests {
         computer.set_turing(tm);
 
         let context = Server::new();
-        let result = computer.simulate(&"0", 100, &context, 0);
+        let result = computer.simulate("0", 100, &context, 0);
         assert!(result.is_ok());
     }
 
@@ -2069,7 +2343,7 @@ mod tests {
         }
 
         let computer = Computer::new();
-        let result = computer.simulate(&"test", 100, &server, 0);
+        let result = computer.simulate("test", 100, &server, 0);
         assert!(result.is_ok());
     }
 
@@ -2082,55 +2356,59 @@ mod tests {
         outer_server.add_computer("outer".to_string(), Computer::new());
 
         let computer = Computer::new();
-        let result = computer.simulate(&"test", 100, &outer_server, 0);
+        let result = computer.simulate("test", 100, &outer_server, 0);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_matches_b_tm_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/matches b.tm".to_string();
-        opt.input = "aa".to_string();
+        let opt = options::Options {
+            file: "examples/matches b.tm".to_string(),
+            input: "aa".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "aabb");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "aabb");
         }
     }
 
     #[test]
     fn test_matches_b_multitape_tm_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/matches b multitape.tm".to_string();
-        opt.input = "aa".to_string();
+        let opt = options::Options {
+            file: "examples/matches b multitape.tm".to_string(),
+            input: "aa".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "aabb");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "aabb");
         }
     }
 
     #[test]
     fn test_matches_b_multitape_conversion_tm_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/matches b multitape.tm".to_string();
-        opt.input = "aa".to_string();
+        let opt = options::Options {
+            file: "examples/matches b multitape.tm".to_string(),
+            input: "aa".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let mut computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
@@ -2138,39 +2416,42 @@ mod tests {
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "aabb");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "aabb");
         }
     }
 
     #[test]
     fn test_lambda_fact_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "src/standard/library.lambda".to_string();
-        opt.input = "(FACT 2)".to_string();
+        let opt = options::Options {
+            file: "src/standard/library.lambda".to_string(),
+            input: "(FACT 2)".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, _, _, _)) = result {
-            assert_eq!(state, "2");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "2");
         }
     }
     #[test]
     fn test_lambda_succ_conversion_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "src/standard/library.lambda".to_string();
-        opt.input = "(SUCC 3)".to_string();
-        opt.max_steps = 10000;
+        let mut opt = options::Options {
+            file: "src/standard/library.lambda".to_string(),
+            input: "(SUCC 3)".to_string(),
+            max_steps: 10000,
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server)
@@ -2180,12 +2461,11 @@ mod tests {
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, opt.max_steps);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, opt.max_steps);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "(/fx.(f(f(f(fx)))))");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "(/fx.(f(f(f(fx)))))");
         }
     }
 
@@ -2201,79 +2481,84 @@ mod tests {
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> = server.execute(&opt.input, opt.max_steps);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, opt.max_steps);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "(/fx.(f(f(f(fx)))))");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "(/fx.(f(f(f(fx)))))");
         }
     } */
 
     #[test]
     fn test_plusfive_multitape_tm_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/plusfive.tm".to_string();
-        opt.input = "010".to_string();
+        let opt = options::Options {
+            file: "examples/plusfive.tm".to_string(),
+            input: "010".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "111");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "111");
         }
     }
 
     #[test]
     fn test_plusfive_ram_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/plusfive.ram".to_string();
-        opt.input = "010".to_string();
+        let opt = options::Options {
+            file: "examples/plusfive.ram".to_string(),
+            input: "010".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "111");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "111");
         }
     }
 
     #[test]
     fn test_dyn_ram_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/dyn.ram".to_string();
-        opt.input = "010".to_string();
+        let opt = options::Options {
+            file: "examples/dyn.ram".to_string(),
+            input: "010".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "1");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "1");
         }
     }
 
     #[test]
     fn test_dyn_conversion_ram_integration() {
-        let mut opt = options::Options::default();
-        opt.file = "examples/dyn.ram".to_string();
-        opt.input = "010".to_string();
+        let mut opt = options::Options {
+            file: "examples/dyn.ram".to_string(),
+            input: "010".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server)
@@ -2283,52 +2568,102 @@ mod tests {
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 100000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 100000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "0000001");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "0000001");
         }
     }
 
     #[test]
     fn test_pda_integration() {
-        let mut opt: options::Options = options::Options::default();
-        opt.file = "examples/0n1m2m3n.pda".to_string();
-        opt.input = "0011122233".to_string();
+        let mut opt: options::Options = options::Options {
+            file: "examples/0n1m2m3n.pda".to_string(),
+            input: "0011122233".to_string(),
+            ..Default::default()
+        };
+
+        let mut server = Server::new();
+        let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
+        server.add_computer(opt.file.clone(), computer);
+        server.set_computation_order_at(0, opt.file.clone());
+
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
+        assert!(result.is_ok());
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Accept);
+            assert_eq!(result.output(), "0011122233");
+        }
+        opt.input = "0011122333".to_string();
+        assert_eq!(server.execute(&opt.input, 1000).unwrap().outcome, Outcome::Reject);
+    }
+
+    #[test]
+    fn test_fsm_integration() {
+        let opt: options::Options = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
+        assert!(matches!(computer.element, ComputingElem::Fsm(_)));
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result = server.execute("babbab", 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "halt");
-            assert_eq!(output, "");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Accept);
+            assert_eq!(result.output(), "babbab");
+            assert_eq!(result.steps, 6);
+        }
+        assert_eq!(server.execute("bba", 1000).unwrap().outcome, Outcome::Reject);
+    }
+
+    #[test]
+    fn test_fsm_to_tm_integration() {
+        let mut opt: options::Options = options::Options {
+            file: "examples/bab.fsm".to_string(),
+            ..Default::default()
+        };
+
+        let mut server = Server::new();
+        let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server)
+            .unwrap()
+            .to_tm(&mut opt, &mut server)
+            .unwrap();
+        assert!(matches!(computer.element, ComputingElem::Tm(_)));
+        server.add_computer(opt.file.clone(), computer);
+        server.set_computation_order_at(0, opt.file.clone());
+
+        let result = server.execute("babbab", 1000);
+        assert!(result.is_ok());
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Halt);
+            assert_eq!(result.output(), "");
         }
     }
 
     #[test]
     fn test_regex_integration() {
-        let mut opt: options::Options = options::Options::default();
-        opt.file = "examples/regex.reg".to_string();
-        opt.input = "abbbcddce".to_string();
+        let opt: options::Options = options::Options {
+            file: "examples/regex.reg".to_string(),
+            input: "abbbcddce".to_string(),
+            ..Default::default()
+        };
 
         let mut server = Server::new();
         let computer = file_handler::handle_file_reads(opt.file.clone(), &mut server).unwrap();
         server.add_computer(opt.file.clone(), computer);
         server.set_computation_order_at(0, opt.file.clone());
 
-        let result: Result<(String, usize, String, usize, Vec<String>), String> =
-            server.execute(&opt.input, 1000);
+        let result: Result<SimulationResult, String> = server.execute(&opt.input, 1000);
         assert!(result.is_ok());
-        if let Ok((state, _, output, _, _)) = result {
-            assert_eq!(state, "accept");
-            assert_eq!(output, "");
+        if let Ok(result) = result {
+            assert_eq!(result.outcome, Outcome::Accept);
+            assert_eq!(result.output(), "");
         }
     }
 }
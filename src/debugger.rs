@@ -0,0 +1,674 @@
+//! # Debugger Module
+//!
+//! This module provides a step-by-step debugger for Turing machines, RAM machines and lambda
+//! expressions, driven by textual commands.
+//!
+//! ## Main Structures
+//!
+//! - **Configuration**: A configuration of the machine being debugged.
+//! - **Debugger**: The machine being debugged, with the configurations reached so far, its
+//!   breakpoints and its watchpoints.
+//!
+//! ## Commands
+//!
+//! - `step [n]` (or `s`): Make one step, or `n` steps.
+//! - `back [n]` (or `b`): Go back one step, or `n` steps, to a stored configuration.
+//! - `continue` (or `c`): Make steps until a breakpoint or a watchpoint is hit, the machine stops
+//!   or the maximum number of steps is reached.
+//! - `break <state> [<symbol>]`: Stop on a state, or on a state reading a symbol. The states of
+//!   RAM machines are their program counters, in decimal, and the symbols their accumulators.
+//! - `watch <cell>`: Stop when a cell changes: a position of the first tape of a Turing machine,
+//!   0 being the first cell of the initial tape, or a memory address of a RAM machine, in decimal.
+//! - `delete`: Remove every breakpoint and watchpoint.
+//! - `info` (or `p`): Print the current configuration, the breakpoints and the watchpoints.
+//! - `help` (or `h`): Print the commands.
+//!
+//! Every configuration reached is stored, so that going back costs nothing. Turing machines must
+//! be deterministic and must not call subroutines, since a single computation path is followed
+//! one transition at a time, while RAM machines run their subroutines in a single step.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::lambda;
+use crate::ram_machine;
+use crate::turing_machine;
+use crate::utils;
+
+/// A configuration of the machine being debugged.
+///
+/// # Variants
+/// * `Tm` - The state and the tapes of a Turing machine, with the number of cells added to the
+///   front of its first tape
+/// * `Ram` - The configuration of a RAM machine, with its outcome once it stops
+/// * `Lambda` - The expression reduced so far
+#[derive(Clone)]
+pub enum Configuration {
+    Tm {
+        state: String,
+        tapes: Vec<turing_machine::Tape>,
+        origin: usize,
+    },
+    Ram(ram_machine::RamConfiguration, Option<computer::Outcome>),
+    Lambda(lambda::Lambda),
+}
+
+/// A step-by-step debugger.
+///
+/// # Fields
+/// * `computer` - The computer being debugged
+/// * `context` - The context of the computer, for the subroutines of RAM machines
+/// * `max_steps` - The maximum number of steps of the computation
+/// * `transitions_map` - The transitions of a Turing machine, by state and symbols read
+/// * `history` - The configurations reached so far, the current one being the last
+/// * `breakpoints` - The states, possibly with a symbol read, on which to stop
+/// * `watchpoints` - The cells whose changes stop the computation
+///
+pub struct Debugger<'a> {
+    computer: &'a computer::Computer,
+    context: &'a computer::Server,
+    max_steps: usize,
+    transitions_map: std::collections::HashMap<String, Vec<turing_machine::Transition>>,
+    pub history: Vec<Configuration>,
+    pub breakpoints: Vec<(String, Option<String>)>,
+    pub watchpoints: Vec<i64>,
+}
+
+impl<'a> Debugger<'a> {
+    /// Creates a debugger of a computer on an input.
+    ///
+    /// # Arguments
+    ///
+    /// * `computer` - The computer to debug, a Turing machine, a RAM machine or a lambda
+    ///   expression
+    /// * `context` - The context of the computer
+    /// * `input` - The input of the computation
+    /// * `max_steps` - The maximum number of steps of the computation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Debugger)` - The debugger, at the initial configuration
+    /// * `Err(String)` - An error message if the computer cannot be debugged, such as a
+    ///   non-deterministic Turing machine or one calling subroutines, or the input is invalid
+    pub fn new(
+        computer: &'a computer::Computer,
+        context: &'a computer::Server,
+        input: &str,
+        max_steps: usize,
+    ) -> Result<Debugger<'a>, String> {
+        let mut transitions_map = std::collections::HashMap::new();
+        let initial = match &computer.element {
+            computer::ComputingElem::Tm(m) => {
+                if !m.is_deterministic() {
+                    return Err("only deterministic Turing machines can be debugged".to_string());
+                }
+                if let Some(t) = m
+                    .transitions
+                    .iter()
+                    .find(|t| computer.mapping.contains_key(&t.new_state))
+                {
+                    return Err(format!(
+                        "Turing machines calling subroutines cannot be debugged (state '{}')",
+                        t.new_state
+                    ));
+                }
+                transitions_map = m.make_transition_map();
+                let input = utils::input_string_to_vec(m.tape_alphabet.clone(), input.to_string());
+                Configuration::Tm {
+                    state: m.initial_state.clone(),
+                    tapes: m.initial_tapes(input, 0),
+                    origin: 0,
+                }
+            }
+            computer::ComputingElem::Ram(m) => {
+                Configuration::Ram(m.initial_configuration(input.to_string())?, None)
+            }
+            computer::ComputingElem::Lambda(l) => {
+                let mut expression = lambda::Lambda {
+                    expr: lambda::parse_lambda(input)?,
+                    references: l.references.clone(),
+                    name: "".to_string(),
+                    force_currying: false,
+                };
+                expression.substitute_names();
+                Configuration::Lambda(expression)
+            }
+            _ => {
+                return Err(
+                    "only Turing machines, RAM machines and lambda expressions can be debugged"
+                        .to_string(),
+                )
+            }
+        };
+        Ok(Debugger {
+            computer,
+            context,
+            max_steps,
+            transitions_map,
+            history: vec![initial],
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+        })
+    }
+
+    /// Returns the current configuration.
+    pub fn current(&self) -> &Configuration {
+        &self.history[self.history.len() - 1]
+    }
+
+    /// Returns the number of steps made to reach the current configuration.
+    pub fn steps(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    /// Computes the configuration following the current one.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Configuration))` - The next configuration
+    /// * `Ok(None)` - If the machine has stopped
+    /// * `Err(String)` - An error message if the step fails
+    fn next(&self) -> Result<Option<Configuration>, String> {
+        match (self.current(), &self.computer.element) {
+            (
+                Configuration::Tm {
+                    state,
+                    tapes,
+                    origin,
+                },
+                computer::ComputingElem::Tm(m),
+            ) => {
+                if m.is_final(state) {
+                    return Ok(None);
+                }
+                let mut key = state.clone();
+                for tape in tapes.iter() {
+                    key += &tape.tape[tape.head];
+                }
+                // the machine is deterministic, so there is at most one transition
+                let Some(transition) = self.transitions_map.get(&key).and_then(|t| t.first())
+                else {
+                    return Ok(None);
+                };
+                let new_tapes = m.apply_transition(transition, tapes);
+                let added = new_tapes[0].tape.len() - tapes[0].tape.len();
+                // a blank added at the end leaves the head where it was before moving
+                let front = if added > 0 && new_tapes[0].head == 0 {
+                    added
+                } else {
+                    0
+                };
+                Ok(Some(Configuration::Tm {
+                    state: transition.new_state.clone(),
+                    tapes: new_tapes,
+                    origin: origin + front,
+                }))
+            }
+            (Configuration::Ram(configuration, None), computer::ComputingElem::Ram(m)) => {
+                let mut configuration = configuration.clone();
+                let stopped = m.step(
+                    &mut configuration,
+                    self.max_steps.saturating_sub(self.steps() + 1),
+                    self.computer,
+                    self.context,
+                    &mut Vec::new(),
+                )?;
+                Ok(Some(Configuration::Ram(configuration, stopped)))
+            }
+            (Configuration::Lambda(expression), _) => {
+                let reduced = lambda::Lambda {
+                    expr: lambda::beta_reduction(&expression.expr),
+                    ..expression.clone()
+                };
+                if reduced == *expression {
+                    return Ok(None);
+                }
+                Ok(Some(Configuration::Lambda(reduced)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Makes a step.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - If the step was made
+    /// * `Ok(false)` - If the machine has stopped or reached the maximum number of steps
+    /// * `Err(String)` - An error message if the step fails
+    pub fn step(&mut self) -> Result<bool, String> {
+        if self.steps() >= self.max_steps {
+            return Ok(false);
+        }
+        match self.next()? {
+            Some(configuration) => {
+                self.history.push(configuration);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Goes back to a previous configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The number of steps to undo
+    ///
+    /// # Returns
+    ///
+    /// The number of steps undone, fewer than `steps` if the initial configuration is reached.
+    pub fn back(&mut self, steps: usize) -> usize {
+        let undone = steps.min(self.steps());
+        self.history.truncate(self.history.len() - undone);
+        undone
+    }
+
+    /// Returns the state of a configuration and the symbol read, which breakpoints refer to.
+    fn location(&self, configuration: &Configuration) -> Option<(String, String)> {
+        match configuration {
+            Configuration::Tm { state, tapes, .. } => {
+                Some((state.clone(), tapes[0].tape[tapes[0].head].clone()))
+            }
+            Configuration::Ram(configuration, _) => Some((
+                utils::bin2int(configuration.pc.clone()).ok()?.to_string(),
+                configuration.acc.clone(),
+            )),
+            Configuration::Lambda(_) => None,
+        }
+    }
+
+    /// Returns the content of a cell of a configuration, which watchpoints refer to.
+    fn cell(&self, configuration: &Configuration, cell: i64) -> Option<String> {
+        match (configuration, &self.computer.element) {
+            (Configuration::Tm { tapes, origin, .. }, computer::ComputingElem::Tm(m)) => Some(
+                usize::try_from(cell + *origin as i64)
+                    .ok()
+                    .and_then(|index| tapes[0].tape.get(index).cloned())
+                    .unwrap_or(m.blank_symbol.clone()),
+            ),
+            (Configuration::Ram(configuration, _), _) => Some(
+                configuration
+                    .memory
+                    .get(&utils::int2bin(cell as i32, 0))
+                    .cloned()
+                    .unwrap_or("0".to_string()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Returns the reason why no step can follow the current configuration.
+    pub fn stop_reason(&self) -> String {
+        if self.steps() >= self.max_steps {
+            "maximum number of steps reached".to_string()
+        } else {
+            "stopped".to_string()
+        }
+    }
+
+    /// Describes the transition or the instruction leading to the current configuration, in the
+    /// format of the files describing the machines.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The transition of a Turing machine, as its state, its new state, the
+    ///   symbols read, the symbols written and the moves, or the instruction of a RAM machine,
+    ///   with its operand in decimal
+    /// * `None` - At the initial configuration, and for lambda expressions
+    pub fn fired(&self) -> Option<String> {
+        let previous = self
+            .history
+            .len()
+            .checked_sub(2)
+            .map(|i| &self.history[i])?;
+        match previous {
+            Configuration::Tm { state, tapes, .. } => {
+                let mut key = state.clone();
+                for tape in tapes.iter() {
+                    key += &tape.tape[tape.head];
+                }
+                Some(self.transitions_map.get(&key)?.first()?.to_string())
+            }
+            Configuration::Ram(configuration, _) => {
+                let value = configuration.memory.get(&configuration.pc)?;
+                let opcode = value.get(0..4)?;
+                let name = ram_machine::RamMachine::ram_opcode_lookup(opcode).ok()?;
+                match utils::bin2int(value[4..].to_string()) {
+                    Ok(operand) if value.len() > 4 => Some(format!("{} {}", name, operand)),
+                    _ => Some(name),
+                }
+            }
+            Configuration::Lambda(_) => None,
+        }
+    }
+
+    /// Makes steps until a breakpoint or a watchpoint is hit, the machine stops or the maximum
+    /// number of steps is reached.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The reason why the computation stopped
+    /// * `Err(String)` - An error message if a step fails
+    pub fn run(&mut self) -> Result<String, String> {
+        loop {
+            if !self.step()? {
+                return Ok(self.stop_reason());
+            }
+            let previous = &self.history[self.history.len() - 2];
+            for cell in self.watchpoints.iter() {
+                let before = self.cell(previous, *cell);
+                let after = self.cell(self.current(), *cell);
+                if before != after {
+                    return Ok(format!(
+                        "watchpoint {}: {} -> {}",
+                        cell,
+                        before.unwrap_or_default(),
+                        after.unwrap_or_default()
+                    ));
+                }
+            }
+            if let Some((state, symbol)) = self.location(self.current()) {
+                for (break_state, break_symbol) in self.breakpoints.iter() {
+                    if *break_state == state && break_symbol.as_ref().is_none_or(|s| *s == symbol) {
+                        return Ok(format!("breakpoint {}", break_state));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Describes the current configuration.
+    pub fn describe(&self) -> String {
+        let step = format!("step {}: ", self.steps());
+        match self.current() {
+            Configuration::Tm { state, tapes, .. } => {
+                let mut description = step + "state " + state;
+                for tape in tapes.iter() {
+                    description += "\n  ";
+                    for (index, symbol) in tape.tape.iter().enumerate() {
+                        if index == tape.head {
+                            description += &format!("[{}]", symbol);
+                        } else {
+                            description += symbol;
+                        }
+                    }
+                }
+                description
+            }
+            Configuration::Ram(configuration, stopped) => {
+                let mut description = format!(
+                    "{}pc {}, acc {}, mov {}, input head {}, output '{}'",
+                    step,
+                    utils::bin2int(configuration.pc.clone()).unwrap_or(0),
+                    configuration.acc,
+                    configuration.mov,
+                    configuration.input_head,
+                    configuration.out
+                );
+                if let Some(outcome) = stopped {
+                    description += &format!(" ({})", outcome);
+                }
+                description
+            }
+            Configuration::Lambda(expression) => step + &expression.to_string(),
+        }
+    }
+
+    /// Executes a debugger command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command, with its arguments separated by spaces
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The output of the command
+    /// * `Err(String)` - An error message if the command is invalid or a step fails
+    pub fn command(&mut self, command: &str) -> Result<String, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let count = || -> Result<usize, String> {
+            words.get(1).map_or(Ok(1), |n| {
+                n.parse().map_err(|_| format!("invalid count: {}", n))
+            })
+        };
+        match words.first().copied() {
+            None => Ok(String::new()),
+            Some("step") | Some("s") => {
+                for _ in 0..count()? {
+                    if !self.step()? {
+                        return Ok(format!("{}\nstopped", self.describe()));
+                    }
+                }
+                Ok(self.describe())
+            }
+            Some("back") | Some("b") => {
+                self.back(count()?);
+                Ok(self.describe())
+            }
+            Some("continue") | Some("c") => {
+                let reason = self.run()?;
+                Ok(format!("{}\n{}", self.describe(), reason))
+            }
+            Some("break") => match words[1..] {
+                [state] => {
+                    self.breakpoints.push((state.to_string(), None));
+                    Ok(format!("breakpoint on {}", state))
+                }
+                [state, symbol] => {
+                    self.breakpoints
+                        .push((state.to_string(), Some(symbol.to_string())));
+                    Ok(format!("breakpoint on {} reading {}", state, symbol))
+                }
+                _ => Err("usage: break <state> [<symbol>]".to_string()),
+            },
+            Some("watch") => match words[1..] {
+                [cell] => {
+                    let cell = cell
+                        .parse()
+                        .map_err(|_| format!("invalid cell: {}", cell))?;
+                    self.watchpoints.push(cell);
+                    Ok(format!("watchpoint on {}", cell))
+                }
+                _ => Err("usage: watch <cell>".to_string()),
+            },
+            Some("delete") => {
+                self.breakpoints.clear();
+                self.watchpoints.clear();
+                Ok("breakpoints and watchpoints deleted".to_string())
+            }
+            Some("info") | Some("p") => {
+                let mut info = self.describe();
+                for (state, symbol) in self.breakpoints.iter() {
+                    info += &format!("\nbreakpoint on {}", state);
+                    if let Some(symbol) = symbol {
+                        info += &format!(" reading {}", symbol);
+                    }
+                }
+                for cell in self.watchpoints.iter() {
+                    info += &format!("\nwatchpoint on {}", cell);
+                }
+                Ok(info)
+            }
+            Some("help") | Some("h") => Ok([
+                "step [n], back [n], continue, break <state> [<symbol>], watch <cell>, delete,",
+                "info, help, exit",
+            ]
+            .join("\n")),
+            Some(other) => Err(format!("unknown command: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(element: computer::ComputingElem) -> computer::Computer {
+        computer::Computer {
+            element,
+            mapping: std::collections::HashMap::new(),
+        }
+    }
+
+    fn bouncing_machine() -> turing_machine::TuringMachine {
+        // writes a 1 on the left of the input, then moves to its end and halts
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = "_".to_string();
+        tm.initial_state = "q0".to_string();
+        tm.halt_state = "qh".to_string();
+        tm.states = vec![
+            "q0".to_string(),
+            "q1".to_string(),
+            "q2".to_string(),
+            "qh".to_string(),
+        ];
+        tm.input_alphabet = vec!["0".to_string(), "1".to_string()];
+        tm.tape_alphabet = vec!["_".to_string(), "0".to_string(), "1".to_string()];
+        use turing_machine::Direction::{Left, Right, Stay};
+        for (state, read, new_state, write, direction) in [
+            ("q0", "_", "q2", "1", Left),
+            ("q2", "_", "q1", "_", Right),
+            ("q1", "_", "qh", "_", Stay),
+            ("q1", "1", "q1", "1", Right),
+            ("q1", "0", "q1", "0", Right),
+        ] {
+            tm.add_transition(
+                state.to_string(),
+                vec![read.to_string()],
+                new_state.to_string(),
+                vec![write.to_string()],
+                vec![direction],
+            );
+        }
+        tm
+    }
+
+    #[test]
+    fn test_turing_machine() {
+        let c = computer(computer::ComputingElem::Tm(Box::new(bouncing_machine())));
+        let context = computer::Server::new();
+        let mut debugger = Debugger::new(&c, &context, "00", 100).unwrap();
+        assert_eq!(debugger.describe(), "step 0: state q0\n  [_]00");
+        assert_eq!(debugger.command("s").unwrap(), "step 1: state q2\n  [_]100");
+        assert_eq!(
+            debugger.command("step 2").unwrap(),
+            "step 3: state q1\n  _1[0]0"
+        );
+        assert_eq!(
+            debugger.command("back 2").unwrap(),
+            "step 1: state q2\n  [_]100"
+        );
+
+        // the cell of the initial blank, moved by the blank added in front of it
+        debugger.command("watch 0").unwrap();
+        debugger.command("back").unwrap();
+        assert!(debugger
+            .command("c")
+            .unwrap()
+            .ends_with("watchpoint 0: _ -> 1"));
+        debugger.command("delete").unwrap();
+        debugger.command("break q1 _").unwrap();
+        assert_eq!(
+            debugger.command("continue").unwrap(),
+            "step 5: state q1\n  _100[_]\nbreakpoint q1"
+        );
+        assert_eq!(
+            debugger.command("c").unwrap(),
+            "step 6: state qh\n  _100[_]\nstopped"
+        );
+        assert_eq!(debugger.fired().unwrap(), "q1 qh _ _ S");
+        assert_eq!(debugger.back(10), 6);
+        assert!(debugger.fired().is_none());
+        assert!(debugger.command("jump").is_err());
+        assert!(debugger.command("step x").is_err());
+        assert!(debugger.command("break").is_err());
+
+        let mut tm = bouncing_machine();
+        tm.add_transition(
+            "q1".to_string(),
+            vec!["0".to_string()],
+            "qh".to_string(),
+            vec!["0".to_string()],
+            vec![turing_machine::Direction::Stay],
+        );
+        let c = computer(computer::ComputingElem::Tm(Box::new(tm)));
+        assert!(Debugger::new(&c, &context, "00", 100).is_err());
+        let mut c = computer(computer::ComputingElem::Tm(Box::new(bouncing_machine())));
+        c.add_mapping("q2".to_string(), "other".to_string());
+        assert!(Debugger::new(&c, &context, "00", 100).is_err());
+    }
+
+    #[test]
+    fn test_ram_machine() {
+        let instruction = |opcode: &str, operand: &str| ram_machine::Instruction {
+            opcode: opcode.to_string(),
+            operand: operand.to_string(),
+            label: "".to_string(),
+        };
+        // stores 1 at the address 1000, writes it and halts
+        let ram = ram_machine::RamMachine {
+            instructions: vec![
+                instruction("0111", "1"),
+                instruction("1000", "1000"),
+                instruction("0011", ""),
+                instruction("1011", ""),
+            ],
+            labels_map: std::collections::HashMap::new(),
+            translation_map: std::collections::HashMap::new(),
+        };
+        let c = computer(computer::ComputingElem::Ram(Box::new(ram)));
+        let context = computer::Server::new();
+        let mut debugger = Debugger::new(&c, &context, "", 100).unwrap();
+        debugger.command("watch 8").unwrap();
+        assert_eq!(
+            debugger.command("c").unwrap(),
+            "step 2: pc 2, acc 1, mov 0, input head 0, output ''\nwatchpoint 8: 0 -> 1"
+        );
+        debugger.command("delete").unwrap();
+        debugger.command("break 3").unwrap();
+        assert!(debugger
+            .command("c")
+            .unwrap()
+            .ends_with("output '1'\nbreakpoint 3"));
+        assert_eq!(
+            debugger.command("c").unwrap(),
+            "step 4: pc 4, acc 1, mov 0, input head 0, output '1' (halt)\nstopped"
+        );
+        assert!(debugger.command("s").unwrap().ends_with("stopped"));
+        debugger.command("b").unwrap();
+        assert_eq!(debugger.steps(), 3);
+        assert_eq!(debugger.fired().unwrap(), "W");
+        debugger.back(2);
+        assert_eq!(debugger.fired().unwrap(), "INIT 1");
+    }
+
+    #[test]
+    fn test_lambda() {
+        let l = lambda::Lambda {
+            expr: lambda::parse_lambda("(\\x.(x))").unwrap(),
+            references: Vec::new(),
+            name: "".to_string(),
+            force_currying: false,
+        };
+        let c = computer(computer::ComputingElem::Lambda(Box::new(l)));
+        let context = computer::Server::new();
+        let mut debugger = Debugger::new(&c, &context, "((\\x.(x)) (\\y.(y)))", 100).unwrap();
+        let before = debugger.describe();
+        assert!(debugger.step().unwrap());
+        assert_ne!(debugger.describe(), before);
+        assert!(!debugger.step().unwrap());
+        assert!(debugger.command("c").unwrap().ends_with("stopped"));
+        debugger.back(1);
+        assert_eq!(debugger.describe(), before);
+
+        let fsm = computer(computer::ComputingElem::Fsm(Box::default()));
+        assert!(Debugger::new(&fsm, &context, "", 100).is_err());
+    }
+}
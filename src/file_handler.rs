@@ -8,17 +8,19 @@
 //! - RAM Programs
 //! - Regular Expressions
 //! - Lambda Expressions
+//! - Context-Free Grammars
 //!
 //! # File Format Structure
 //! Each file should start with a type identifier on the first line:
 //! - "tm" for Turing Machines
 //! - "tm_e" for Turing Machines from encoding
-//! - "pda" for Pushdown Automata
+//! - "pda", "pda final-state" and "pda empty-stack" for Pushdown Automata
 //! - "fsm" for Finite State Machines
 //! - "regex" for Regular Expressions
 //! - "ram" for RAM Programs
 //! - "ram_e" for RAM Programs from encoding
 //! - "lambda" for Lambda Expressions
+//! - "cfg" for Context-Free Grammars
 //!
 //! # Mappings
 //! Files can include mappings to other files using the syntax:
@@ -58,10 +60,13 @@
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
 use crate::computer;
+use crate::finite_automaton;
+use crate::grammar;
 use crate::lambda;
+use crate::pushdown_automaton;
 use crate::ram_machine;
 use crate::regex;
-use crate::regex::regex_to_fsa;
+use crate::regex::regex_to_fsa_over;
 use crate::turing_machine;
 use crate::turing_machine::FromString;
 use crate::utils;
@@ -84,12 +89,16 @@ use crate::utils;
 /// of the file must contain one of the following type identifiers:
 /// - "tm" - Turing Machine
 /// - "tm_e" - Turing Machine from encoding
-/// - "pda" - Pushdown Automaton
+/// - "pda" - Pushdown Automaton accepting by final state, with unlimited blanks below its stack
+/// - "pda final-state" - Pushdown Automaton accepting by final state
+/// - "pda empty-stack" - Pushdown Automaton accepting by empty stack
 /// - "fsm" - Finite State Machine
-/// - "regex" - Regular Expression
+/// - "regex" - Regular Expression, converted with the construction selected by
+///   `context.simulation.regex_dfa`
 /// - "ram" - RAM Program
 /// - "ram_e" - RAM Program from encoding
 /// - "lambda" - Lambda Expression
+/// - "cfg" - Context-Free Grammar
 ///
 /// The function also processes mappings to other files using the syntax ": name filepath".
 ///
@@ -149,12 +158,33 @@ pub fn handle_file_reads(
     match line.as_str() {
         "tm" => read_turing_machine(lines, &mut c),
         "tm_e" => read_tm_from_encoding(lines, &mut c),
-        "pda" => read_pushdown_automaton(lines, &mut c),
+        "pda" => read_pushdown_automaton(
+            lines,
+            &mut c,
+            pushdown_automaton::Acceptance::FinalState,
+            true,
+        ),
+        "pda final-state" => read_pushdown_automaton(
+            lines,
+            &mut c,
+            pushdown_automaton::Acceptance::FinalState,
+            false,
+        ),
+        "pda empty-stack" => read_pushdown_automaton(
+            lines,
+            &mut c,
+            pushdown_automaton::Acceptance::EmptyStack,
+            false,
+        ),
         "fsm" => read_finite_state_machine(lines, &mut c),
-        "regex" => read_regex(lines, &mut c),
+        "regex" if context.simulation.regex_dfa => {
+            read_regex_dfa(lines, &mut c, &context.simulation.regex_alphabet)
+        }
+        "regex" => read_regex(lines, &mut c, &context.simulation.regex_alphabet),
         "ram" => read_ram_program(lines, &mut c),
         "ram_e" => read_ram_program_from_encoding(lines, &mut c),
         "lambda" => read_lambda(lines, &mut c),
+        "cfg" => read_grammar(lines, &mut c),
         &_ => Err("No valid type to read".to_string()),
     }
 }
@@ -272,85 +302,42 @@ pub fn read_finite_state_machine(
     if lines.len() < 4 {
         return Err("Finite State Machine definition must have at least 4 lines".to_string());
     }
-    let mut tm = turing_machine::TuringMachine::new();
-    tm.blank_symbol = " ".to_string();
-
-    let mut initial_state = lines[0].to_string() + "_init";
-    while tm.states.contains(&initial_state) {
-        initial_state += "_"
-    }
-    tm.initial_state = initial_state.clone();
-    tm.add_transition(
-        initial_state.clone(),
-        vec![tm.blank_symbol.clone()],
-        lines[0].to_string(),
-        vec![tm.blank_symbol.clone()],
-        vec![turing_machine::Direction::Right],
-    );
-
-    tm.states = lines[2]
+    let mut fa = finite_automaton::FiniteAutomaton::new();
+    fa.initial_state = lines[0].to_string();
+    fa.final_states = lines[1]
+        .split(" ")
+        .filter(|e| !e.is_empty())
+        .map(|e| e.to_string())
+        .collect();
+    fa.states = lines[2]
+        .split(" ")
+        .map(|e| e.to_string())
+        .collect::<Vec<String>>();
+    fa.input_alphabet = lines[3]
         .split(" ")
         .map(|e| e.to_string())
         .collect::<Vec<String>>();
-
-    let final_states: Vec<&str> = lines[1].split(" ").collect();
-    let mut final_state = "final".to_string();
-    while tm.states.contains(&final_state) {
-        final_state += "_"
-    }
-    tm.halt_state = final_state.clone();
-    tm.states.push(final_state.clone());
-    tm.states.push(initial_state);
-    for fs in final_states {
-        tm.add_transition(
-            fs.to_string(),
-            vec![tm.blank_symbol.clone()],
-            final_state.clone(),
-            vec![tm.blank_symbol.clone()],
-            vec![turing_machine::Direction::Stay],
-        );
-    }
-
-    let input_alphabet: Vec<&str> = lines[3].split(" ").collect();
-    for symbol in input_alphabet {
-        tm.input_alphabet.push(symbol.to_string());
-    }
-    tm.tape_alphabet = tm.input_alphabet.clone();
-    tm.tape_alphabet.push(tm.blank_symbol.clone());
 
     for line in lines.iter().skip(4) {
         let transition_data: Vec<&str> = line.split(" ").collect();
         if transition_data.len() == 2 {
             // epsilon moves
-            for symbol in tm.input_alphabet.clone().iter() {
-                tm.add_transition(
-                    transition_data[0].to_string(),
-                    vec![symbol.to_string()],
-                    transition_data[1].to_string(),
-                    vec![symbol.to_string()],
-                    vec![turing_machine::Direction::Stay],
-                );
-            }
-            tm.add_transition(
+            fa.add_transition(
                 transition_data[0].to_string(),
-                vec![tm.blank_symbol.clone()],
+                String::new(),
                 transition_data[1].to_string(),
-                vec![tm.blank_symbol.clone()],
-                vec![turing_machine::Direction::Stay],
             );
         } else if transition_data.len() == 3 {
-            tm.add_transition(
+            fa.add_transition(
                 transition_data[0].to_string(),
-                vec![transition_data[1].to_string()],
+                transition_data[1].to_string(),
                 transition_data[2].to_string(),
-                vec![" ".to_string()],
-                vec![turing_machine::Direction::Right],
             );
         } else {
             return Err(format!("Error: Transition format not valid: {}", line));
         }
     }
-    computer.set_turing(tm);
+    computer.set_fsm(fa);
     Ok(computer.clone())
 }
 
@@ -360,6 +347,8 @@ pub fn read_finite_state_machine(
 ///
 /// * `lines` - Vector of strings containing the PDA definition
 /// * `computer` - Mutable reference to a Computer object to store the PDA
+/// * `acceptance` - The acceptance mode chosen by the header of the file
+/// * `bottomless` - Whether unlimited blanks lie below the stack, as with the plain `pda` header
 ///
 /// # Returns
 ///
@@ -373,188 +362,16 @@ pub fn read_finite_state_machine(
 /// 4. Space-separated input alphabet
 /// 5. Space-separated stack alphabet
 /// 6. Blank symbol
-/// 7. Transitions in format: current_state input stack_symbol new_state new_stack_top or with two stack symbols: current_state input stack_symbol new_state new_top1 new_top2. One transition per line until EOF
+/// 7. Transitions in format: current_state input stack_symbol new_state new_top1 ... new_topk, replacing the stack symbol with the new ones (new_topk on top), or popping it when the only new symbol is the blank. One transition per line until EOF
 pub fn read_pushdown_automaton(
     lines: Vec<String>,
     computer: &mut computer::Computer,
+    acceptance: pushdown_automaton::Acceptance,
+    bottomless: bool,
 ) -> Result<computer::Computer, String> {
-    if lines.len() < 6 {
-        return Err("Pushdown Automaton definition must have at least 6 lines".to_string());
-    }
-    let mut tm = turing_machine::TuringMachine::new();
-    tm.tape_count = 2;
-    tm.blank_symbol = lines[5].to_string();
-
-    let mut initial_state = lines[0].to_string() + "_init";
-    while tm.states.contains(&initial_state) {
-        initial_state += "_"
-    }
-    tm.initial_state = initial_state.clone();
-    tm.add_transition(
-        initial_state.clone(),
-        vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-        lines[0].to_string(),
-        vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-        vec![
-            turing_machine::Direction::Right,
-            turing_machine::Direction::Stay,
-        ],
-    );
-
-    tm.states = lines[2].split(" ").map(|e| e.to_string()).collect();
-    tm.states.push(initial_state);
-
-    let final_states: Vec<&str> = lines[1].split(" ").collect();
-    let mut final_state = "final".to_string();
-    while tm.states.contains(&final_state) {
-        final_state += "_"
-    }
-    tm.halt_state = final_state.clone();
-    tm.states.push(final_state.clone());
-
-    for fs in final_states {
-        tm.add_transition(
-            fs.to_string(),
-            vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-            final_state.clone(),
-            vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-            vec![
-                turing_machine::Direction::Stay,
-                turing_machine::Direction::Stay,
-            ],
-        );
-    }
-
-    let input_alphabet: Vec<&str> = lines[3].split(" ").collect();
-    for symbol in input_alphabet {
-        tm.input_alphabet.push(symbol.to_string());
-    }
-
-    let stack_alphabet: Vec<&str> = lines[4].split(" ").collect();
-    tm.tape_alphabet = tm.input_alphabet.clone();
-    for symbol in stack_alphabet {
-        if !tm.tape_alphabet.contains(&symbol.to_string()) {
-            tm.tape_alphabet.push(symbol.to_string());
-        }
-    }
-
-    for line in lines.iter().skip(6) {
-        let transition_data: Vec<&str> = line.split(" ").collect();
-        if transition_data.len() < 5 {
-            return Err(format!("Error: Transition format not valid: {}", line));
-        } else if transition_data.len() == 5 {
-            let dir = if transition_data[4] != tm.blank_symbol {
-                turing_machine::Direction::Stay
-            } else {
-                turing_machine::Direction::Left
-            };
-            if transition_data[1] == tm.blank_symbol {
-                // epsilon move
-                for symb in tm.input_alphabet.clone().iter() {
-                    tm.add_transition(
-                        transition_data[0].to_string(),
-                        vec![symb.to_string(), transition_data[2].to_string()],
-                        transition_data[3].to_string(),
-                        vec![symb.clone(), transition_data[4].to_string()],
-                        vec![turing_machine::Direction::Stay, dir.clone()],
-                    );
-                }
-                tm.add_transition(
-                    transition_data[0].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[2].to_string()],
-                    transition_data[3].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[4].to_string()],
-                    vec![turing_machine::Direction::Stay, dir.clone()],
-                );
-            } else {
-                tm.add_transition(
-                    transition_data[0].to_string(),
-                    vec![
-                        transition_data[1].to_string(),
-                        transition_data[2].to_string(),
-                    ],
-                    transition_data[3].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[4].to_string()],
-                    vec![turing_machine::Direction::Right, dir],
-                );
-            }
-        } else if transition_data.len() == 6 {
-            let aux_state = format!("{}_aux_{}", transition_data[3], tm.transitions.len());
-            tm.states.push(aux_state.clone());
-            if transition_data[1] == tm.blank_symbol {
-                // epsilon move
-                for symb in tm.input_alphabet.clone().iter() {
-                    tm.add_transition(
-                        transition_data[0].to_string(),
-                        vec![symb.clone(), transition_data[2].to_string()],
-                        aux_state.clone(),
-                        vec![symb.clone(), transition_data[4].to_string()],
-                        vec![
-                            turing_machine::Direction::Stay,
-                            turing_machine::Direction::Right,
-                        ],
-                    );
-                    tm.add_transition(
-                        aux_state.clone(),
-                        vec![symb.clone(), tm.blank_symbol.clone()],
-                        transition_data[3].to_string(),
-                        vec![symb.clone(), transition_data[5].to_string()],
-                        vec![
-                            turing_machine::Direction::Stay,
-                            turing_machine::Direction::Stay,
-                        ],
-                    );
-                }
-                tm.add_transition(
-                    transition_data[0].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[2].to_string()],
-                    aux_state.clone(),
-                    vec![tm.blank_symbol.clone(), transition_data[4].to_string()],
-                    vec![
-                        turing_machine::Direction::Stay,
-                        turing_machine::Direction::Right,
-                    ],
-                );
-                tm.add_transition(
-                    aux_state.clone(),
-                    vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-                    transition_data[3].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[5].to_string()],
-                    vec![
-                        turing_machine::Direction::Stay,
-                        turing_machine::Direction::Stay,
-                    ],
-                );
-            } else {
-                tm.add_transition(
-                    transition_data[0].to_string(),
-                    vec![
-                        transition_data[1].to_string(),
-                        transition_data[2].to_string(),
-                    ],
-                    aux_state.clone(),
-                    vec![tm.blank_symbol.clone(), transition_data[4].to_string()],
-                    vec![
-                        turing_machine::Direction::Stay,
-                        turing_machine::Direction::Right,
-                    ],
-                );
-                tm.add_transition(
-                    aux_state.clone(),
-                    vec![tm.blank_symbol.clone(), tm.blank_symbol.clone()],
-                    transition_data[3].to_string(),
-                    vec![tm.blank_symbol.clone(), transition_data[5].to_string()],
-                    vec![
-                        turing_machine::Direction::Right,
-                        turing_machine::Direction::Stay,
-                    ],
-                );
-            }
-        } else {
-            return Err(format!("Error: Transition format not valid: {}", line));
-        }
-    }
-    computer.set_turing(tm);
+    let mut pda = pushdown_automaton::PushdownAutomaton::from_lines(&lines, acceptance)?;
+    pda.bottomless = bottomless;
+    computer.set_pda(pda);
     Ok(computer.clone())
 }
 
@@ -816,6 +633,8 @@ pub fn read_ram_program_from_encoding(
 ///
 /// * `lines` - Vector of strings containing the regex
 /// * `computer` - Mutable reference to a Computer object to store the regex
+/// * `alphabet` - Symbols, besides the ones of the regex, over which its negated classes and
+///   wildcards range
 ///
 /// # Returns
 ///
@@ -826,11 +645,84 @@ pub fn read_ram_program_from_encoding(
 pub fn read_regex(
     lines: Vec<String>,
     computer: &mut computer::Computer,
+    alphabet: &[String],
 ) -> Result<computer::Computer, String> {
     if lines.is_empty() {
         return Err("Regex definition must have at least 1 line".to_string());
     }
-    computer.set_turing(regex_to_fsa(&regex::build_regex_tree(&lines[0])?)?);
+    computer.set_turing(regex_to_fsa_over(&regex::build_regex_tree(&lines[0])?, alphabet)?);
+    Ok(computer.clone())
+}
+
+/// Reads a regular expression from a vector of strings and converts it directly into a DFA.
+///
+/// # Arguments
+///
+/// * `lines` - Vector of strings containing the regex
+/// * `computer` - Mutable reference to a Computer object to store the automaton
+/// * `alphabet` - Symbols, besides the ones of the regex, over which its negated classes and
+///   wildcards range
+///
+/// # Returns
+///
+/// * `Result<Computer, String>` - Returns the computer with the DFA built with Brzozowski derivatives or an error
+///
+/// # Format
+/// First line must contain the regular expression using standard notation
+pub fn read_regex_dfa(
+    lines: Vec<String>,
+    computer: &mut computer::Computer,
+    alphabet: &[String],
+) -> Result<computer::Computer, String> {
+    if lines.is_empty() {
+        return Err("Regex definition must have at least 1 line".to_string());
+    }
+    computer.set_fsm(regex::regex_to_dfa_over(
+        &regex::build_regex_tree(&lines[0])?,
+        alphabet,
+    ));
+    Ok(computer.clone())
+}
+
+/// Reads the definition of a Pushdown Automaton from a `pda` file, without storing it in a
+/// computer.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file to read, with `handle_file_reads`
+///
+/// # Returns
+///
+/// * `Result<PushdownAutomaton, String>` - Returns the automaton described by the file or an error
+pub fn read_pushdown_automaton_file(
+    file_name: &str,
+) -> Result<pushdown_automaton::PushdownAutomaton, String> {
+    match handle_file_reads(file_name.to_string(), &mut computer::Server::new())?.element {
+        computer::ComputingElem::Pda(pda) => Ok(*pda),
+        _ => Err("not a pushdown automaton".to_string()),
+    }
+}
+
+/// Reads and processes a context-free grammar from a vector of strings.
+///
+/// # Arguments
+///
+/// * `lines` - Vector of strings containing the productions
+/// * `computer` - Mutable reference to a Computer object to store the grammar
+///
+/// # Returns
+///
+/// * `Result<Computer, String>` - Returns the computer with the parsed grammar or an error
+///
+/// # Format
+/// Each line contains: head -> body | body | ...
+/// Where the symbols of a body are separated by spaces, `ε` is the empty body and a line
+/// starting with `|` adds bodies to the previous head. The first head is the start symbol.
+pub fn read_grammar(
+    lines: Vec<String>,
+    computer: &mut computer::Computer,
+) -> Result<computer::Computer, String> {
+    computer.set_cfg(grammar::Grammar::from_lines(&lines)?);
     Ok(computer.clone())
 }
 
@@ -939,7 +831,12 @@ mod tests {
             "_".to_string(),
             "invalid pda transition".to_string(),
         ];
-        let result = read_pushdown_automaton(lines, &mut computer);
+        let result = read_pushdown_automaton(
+            lines,
+            &mut computer,
+            pushdown_automaton::Acceptance::FinalState,
+            true,
+        );
         assert!(result.is_err());
     }
 
@@ -973,7 +870,7 @@ mod tests {
         let lines = vec![
             "(((".to_string(), // Invalid regex
         ];
-        let result = read_regex(lines, &mut computer);
+        let result = read_regex(lines, &mut computer, &[]);
         assert!(result.is_err());
     }
 
@@ -1037,10 +934,23 @@ mod tests {
             "_".to_string(),
             "q0 0 Z qa Z Z".to_string(),
         ];
-        let result = read_pushdown_automaton(lines, &mut computer);
+        let result = read_pushdown_automaton(
+            lines,
+            &mut computer,
+            pushdown_automaton::Acceptance::FinalState,
+            true,
+        );
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_read_pushdown_automaton_file() {
+        let pda = read_pushdown_automaton_file("examples/0n1m2m3n.pda").unwrap();
+        assert_eq!(pda.states.len(), 7);
+        assert!(read_pushdown_automaton_file("examples/bab.fsm").is_err());
+        assert!(read_pushdown_automaton_file("missing.pda").is_err());
+    }
+
     #[test]
     fn test_read_ram_program_valid() {
         let mut computer = computer::Computer::new();
@@ -1053,10 +963,25 @@ mod tests {
     fn test_read_regex_valid() {
         let mut computer = computer::Computer::new();
         let lines = vec!["(a|b)*".to_string()];
-        let result = read_regex(lines, &mut computer);
+        let result = read_regex(lines, &mut computer, &[]);
         assert!(result.is_ok());
     }
     #[test]
+    fn test_read_regex_dfa() {
+        let mut computer = computer::Computer::new();
+        let result = read_regex_dfa(vec!["(a|b)*abb".to_string()], &mut computer, &[]).unwrap();
+        let fa = result.to_fsm().unwrap();
+        assert!(fa.is_deterministic());
+        assert_eq!(fa.states.len(), 4);
+        assert!(read_regex_dfa(vec!["(((".to_string()], &mut computer, &[]).is_err());
+        assert!(read_regex_dfa(Vec::new(), &mut computer, &[]).is_err());
+
+        let mut context = computer::Server::new();
+        context.simulation.regex_dfa = true;
+        let c = handle_file_reads("examples/regex.reg".to_string(), &mut context).unwrap();
+        assert!(c.to_fsm().unwrap().is_deterministic());
+    }
+    #[test]
     fn test_read_tm_from_encoding_valid() {
         let mut computer = computer::Computer::new();
         let lines = vec![
@@ -1085,6 +1010,16 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_read_grammar() {
+        let mut computer = computer::Computer::new();
+        let lines = vec!["S -> a S b".to_string(), "  | ε".to_string()];
+        let result = read_grammar(lines, &mut computer).unwrap();
+        assert!(matches!(result.element, computer::ComputingElem::Cfg(_)));
+        assert!(read_grammar(vec!["S a".to_string()], &mut computer).is_err());
+        assert!(read_grammar(Vec::new(), &mut computer).is_err());
+    }
+
     #[test]
     fn test_read_ram_program_from_encoding_valid() {
         let mut computer = computer::Computer::new();
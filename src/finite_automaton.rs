@@ -0,0 +1,1547 @@
+//! # Finite Automaton Module
+//!
+//! This module provides a first-class implementation of finite automata (DFA, NFA and ε-NFA).
+//! Until now finite state machines were lowered straight into a Turing machine when read from
+//! a `fsm` file; this module keeps them as automata so that they can be simulated directly and
+//! analysed with the classical algorithms of automata theory.
+//!
+//! ## Main Structures
+//!
+//! - **FiniteAutomaton**: Represents a (possibly non-deterministic) finite automaton with ε-moves,
+//!   including its states, alphabet, initial and final states, and transitions.
+//! - **Transition**: Represents a single move of the automaton. An empty `symbol` denotes an ε-move.
+//! - **FiniteAutomatonBuilder**: Constructs an automaton in code, completing its states and
+//!   alphabet from the transitions.
+//!
+//! ## Key Features
+//!
+//! - **Simulation**: Simulate the automaton on a given input by tracking the set of active states,
+//!   which works uniformly for deterministic and non-deterministic automata.
+//! - **ε-closure**: Compute the ε-closure of a set of states.
+//! - **Determinization**: Build an equivalent DFA with the subset construction.
+//! - **Minimization**: Build the minimal DFA with Hopcroft's partition refinement, together with
+//!   the mapping of the states to their Myhill–Nerode equivalence classes.
+//! - **Equivalence**: Decide whether two automata recognize the same language, finding the
+//!   shortest word telling them apart if they do not.
+//! - **Boolean operations**: Union, intersection and difference through the product
+//!   construction, and complementation.
+//! - **Search**: Find every match of the automaton inside a longer input, with leftmost-longest
+//!   or leftmost-first semantics.
+//! - **Conversion**: Lossless conversion to the Turing machine form used by the rest of the
+//!   simulator (`to_tm`) and back (`from_tm`), so that automata can still be chained in a `Server`
+//!   and converted to the other computational models.
+//! - **Printing**: The `Display` implementation produces a valid `fsm` file.
+//!
+//! ## File Format
+//!
+//! The `fsm` file format is the following (after the `fsm` header line):
+//!
+//! 1. initial state
+//! 2. final states, separated by spaces
+//! 3. states, separated by spaces
+//! 4. input alphabet, separated by spaces
+//! 5. one transition per line: `state symbol new_state`, or `state new_state` for an ε-move
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::trace;
+use crate::turing_machine;
+
+/// A complete representation of a finite automaton, possibly non-deterministic and with ε-moves.
+///
+/// # Fields
+/// * `initial_state` - The starting state of the automaton
+/// * `final_states` - The accepting states of the automaton
+/// * `states` - Collection of all states in the automaton
+/// * `input_alphabet` - Set of symbols allowed in the input
+/// * `transitions` - The transition relation as a collection of moves
+///
+/// # Notes
+/// - ε-moves are transitions whose `symbol` is the empty string
+/// - The automaton is deterministic when it has no ε-moves and at most one move for each
+///   state/symbol pair; missing moves are allowed and reject the input
+/// - States keep the order in which they were declared, which is also the order used when
+///   naming sets of states
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FiniteAutomaton {
+    pub initial_state: String,
+    pub final_states: Vec<String>,
+    pub states: Vec<String>,
+    pub input_alphabet: Vec<String>,
+    pub transitions: Vec<Transition>,
+}
+
+/// Represents the boolean operations between languages computed by the product construction.
+///
+/// # Variants
+///
+/// * `Union` - The words accepted by at least one of the automata
+/// * `Intersection` - The words accepted by both automata
+/// * `Difference` - The words accepted by the first automaton and rejected by the second one
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProductOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// Represents the rules used to choose a match when searching an input.
+///
+/// # Variants
+///
+/// * `LeftmostLongest` - The longest match starting at the leftmost possible position, as in POSIX
+/// * `LeftmostFirst` - The match starting at the leftmost possible position which is preferred by
+///   the order of the transitions, as in backtracking regex engines. Determinization loses this
+///   order, so on a deterministic automaton it gives the leftmost-longest match.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchSemantics {
+    LeftmostLongest,
+    LeftmostFirst,
+}
+
+/// Represents a move of a finite automaton.
+///
+/// # Fields
+///
+/// * `state` - The state the move starts from.
+/// * `symbol` - The input symbol consumed by the move, or the empty string for an ε-move.
+/// * `new_state` - The state reached by the move.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub state: String,
+    pub symbol: String,
+    pub new_state: String,
+}
+
+impl Default for FiniteAutomaton {
+    fn default() -> Self {
+        FiniteAutomaton::new()
+    }
+}
+
+impl FiniteAutomaton {
+    /// Creates a new empty `FiniteAutomaton` instance.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `FiniteAutomaton` with an empty initial state and no states, symbols or transitions.
+    pub fn new() -> Self {
+        FiniteAutomaton {
+            initial_state: String::new(),
+            final_states: Vec::new(),
+            states: Vec::new(),
+            input_alphabet: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Returns a builder constructing a finite automaton in code.
+    pub fn builder() -> FiniteAutomatonBuilder {
+        FiniteAutomatonBuilder::new()
+    }
+
+    /// Adds a new transition to the automaton.
+    ///
+    /// If the transition already exists, it will not be added again.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state from which the transition starts
+    /// * `symbol` - The symbol consumed by the transition, or the empty string for an ε-move
+    /// * `new_state` - The state reached by the transition
+    pub fn add_transition(&mut self, state: String, symbol: String, new_state: String) {
+        let transition = Transition {
+            state,
+            symbol,
+            new_state,
+        };
+        if !self.transitions.contains(&transition) {
+            self.transitions.push(transition);
+        }
+    }
+
+    /// Checks if a state is an accepting state of the automaton.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the state is one of the final states, `false` otherwise.
+    pub fn is_final(&self, state: &str) -> bool {
+        self.final_states.iter().any(|s| s == state)
+    }
+
+    /// Checks whether the automaton is deterministic.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the automaton has no ε-moves and at most one transition for each
+    /// state/symbol pair, `false` otherwise.
+    pub fn is_deterministic(&self) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        for t in self.transitions.iter() {
+            if t.symbol.is_empty() || !seen.insert((t.state.as_str(), t.symbol.as_str())) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Sorts a collection of states following the declaration order of the automaton.
+    ///
+    /// States which are not declared are placed at the end, in lexicographic order.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states to sort
+    ///
+    /// # Returns
+    ///
+    /// A vector with the given states, without duplicates, in canonical order.
+    pub fn sort_states(&self, states: Vec<String>) -> Vec<String> {
+        let mut states = states;
+        states.sort_by_key(|s| {
+            (
+                self.states.iter().position(|e| e == s).unwrap_or(self.states.len()),
+                s.clone(),
+            )
+        });
+        states.dedup();
+        states
+    }
+
+    /// Computes the ε-closure of a set of states.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states whose closure should be computed
+    ///
+    /// # Returns
+    ///
+    /// The set of states reachable from the given ones through ε-moves only (the given states
+    /// included), in canonical order.
+    pub fn epsilon_closure(&self, states: &[String]) -> Vec<String> {
+        let mut closure: Vec<String> = states.to_vec();
+        let mut stack: Vec<String> = states.to_vec();
+        while let Some(state) = stack.pop() {
+            for t in self.transitions.iter() {
+                if t.state == state && t.symbol.is_empty() && !closure.contains(&t.new_state) {
+                    closure.push(t.new_state.clone());
+                    stack.push(t.new_state.clone());
+                }
+            }
+        }
+        self.sort_states(closure)
+    }
+
+    /// Computes the set of states reached from a set of states by reading a symbol.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The current set of states, assumed to be ε-closed
+    /// * `symbol` - The symbol to read
+    ///
+    /// # Returns
+    ///
+    /// The ε-closure of the states reached by a transition on `symbol`, in canonical order.
+    pub fn step(&self, states: &[String], symbol: &str) -> Vec<String> {
+        let mut next = Vec::new();
+        for t in self.transitions.iter() {
+            if t.symbol == symbol && states.contains(&t.state) && !next.contains(&t.new_state) {
+                next.push(t.new_state.clone());
+            }
+        }
+        self.epsilon_closure(&next)
+    }
+
+    /// Simulates the automaton on the given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of strings representing the input symbols
+    /// * `max_steps` - Maximum number of symbols to read before stopping
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing either:
+    /// - Ok with a `SimulationResult` containing:
+    ///   - The outcome: `Accept`, `Reject` or `Timeout`
+    ///   - The number of symbols read
+    ///   - The input, unchanged (an automaton never writes)
+    ///   - Number of steps executed
+    ///   - Vector of computation history, one event for each set of states, with the input left
+    /// - Err with an error message if simulation fails
+    ///
+    /// # Notes
+    ///
+    /// - The simulation tracks the set of active states, so it runs in a single pass even
+    ///   for non-deterministic automata
+    /// - The simulation stops early when the set of active states becomes empty
+    pub fn simulate(
+        &self,
+        input: Vec<String>,
+        max_steps: usize,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut current = self.epsilon_closure(std::slice::from_ref(&self.initial_state));
+        let mut computation = vec![trace::TraceEvent::Fsm {
+            depth: 0,
+            states: current.clone(),
+            input: input.join(""),
+        }];
+        let mut steps = 0;
+        while steps < input.len() && !current.is_empty() {
+            if steps >= max_steps {
+                return Ok(computer::SimulationResult {
+                    outcome: computer::Outcome::Timeout,
+                    head: steps,
+                    tape: input,
+                    steps,
+                    computation,
+                });
+            }
+            current = self.step(&current, &input[steps]);
+            steps += 1;
+            computation.push(trace::TraceEvent::Fsm {
+                depth: 0,
+                states: current.clone(),
+                input: input[steps..].join(""),
+            });
+        }
+        let outcome = if steps == input.len() && current.iter().any(|s| self.is_final(s)) {
+            computer::Outcome::Accept
+        } else {
+            computer::Outcome::Reject
+        };
+        Ok(computer::SimulationResult {
+            outcome,
+            head: steps,
+            tape: input,
+            steps,
+            computation,
+        })
+    }
+
+    /// Converts the automaton into an equivalent single-tape Turing machine.
+    ///
+    /// # Returns
+    ///
+    /// A `TuringMachine` with blank symbol `" "` which:
+    /// - starts in a fresh initial state that skips the leading blank
+    /// - consumes a symbol moving right for each transition, overwriting it with a blank
+    /// - performs ε-moves without moving, on every symbol
+    /// - enters a fresh halting state from every final state when the input is over
+    ///
+    /// # Notes
+    ///
+    /// This is the construction historically used to read `fsm` files, and `from_tm` is its inverse.
+    pub fn to_tm(&self) -> turing_machine::TuringMachine {
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = " ".to_string();
+
+        let mut initial_state = self.initial_state.clone() + "_init";
+        while self.states.contains(&initial_state) {
+            initial_state += "_"
+        }
+        tm.initial_state = initial_state.clone();
+        tm.add_transition(
+            initial_state.clone(),
+            vec![tm.blank_symbol.clone()],
+            self.initial_state.clone(),
+            vec![tm.blank_symbol.clone()],
+            vec![turing_machine::Direction::Right],
+        );
+
+        tm.states = self.states.clone();
+        let mut final_state = "final".to_string();
+        while tm.states.contains(&final_state) {
+            final_state += "_"
+        }
+        tm.halt_state = final_state.clone();
+        tm.states.push(final_state.clone());
+        tm.states.push(initial_state);
+        for fs in self.final_states.iter() {
+            tm.add_transition(
+                fs.clone(),
+                vec![tm.blank_symbol.clone()],
+                final_state.clone(),
+                vec![tm.blank_symbol.clone()],
+                vec![turing_machine::Direction::Stay],
+            );
+        }
+
+        tm.input_alphabet = self.input_alphabet.clone();
+        tm.tape_alphabet = tm.input_alphabet.clone();
+        tm.tape_alphabet.push(tm.blank_symbol.clone());
+
+        for t in self.transitions.iter() {
+            if t.symbol.is_empty() {
+                for symbol in tm.tape_alphabet.clone() {
+                    tm.add_transition(
+                        t.state.clone(),
+                        vec![symbol.clone()],
+                        t.new_state.clone(),
+                        vec![symbol],
+                        vec![turing_machine::Direction::Stay],
+                    );
+                }
+            } else {
+                tm.add_transition(
+                    t.state.clone(),
+                    vec![t.symbol.clone()],
+                    t.new_state.clone(),
+                    vec![tm.blank_symbol.clone()],
+                    vec![turing_machine::Direction::Right],
+                );
+            }
+        }
+        tm
+    }
+
+    /// Reads back a finite automaton from its Turing machine form.
+    ///
+    /// # Arguments
+    ///
+    /// * `tm` - A single-tape Turing machine built like the ones produced by `to_tm` or by the
+    ///   regular expression construction
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FiniteAutomaton)` - The automaton recognizing the same language
+    /// * `Err(String)` - An error message if the machine does not have the shape of a finite automaton
+    ///
+    /// # Notes
+    ///
+    /// - The initial state of the machine must only skip the leading blank, moving right
+    /// - A move on a blank without moving into the accept or halt state marks a final state
+    /// - A move to the right on an input symbol is read as a transition on that symbol
+    /// - A move without moving which leaves the tape unchanged is read as an ε-move
+    pub fn from_tm(tm: &turing_machine::TuringMachine) -> Result<FiniteAutomaton, String> {
+        if tm.tape_count != 1 {
+            return Err("only single-tape machines can be read as finite automata".to_string());
+        }
+        let blank = &tm.blank_symbol;
+        let starts: Vec<&turing_machine::Transition> = tm
+            .transitions
+            .iter()
+            .filter(|t| t.state == tm.initial_state)
+            .collect();
+        if starts.len() != 1
+            || starts[0].symbols[0] != *blank
+            || starts[0].directions[0] != turing_machine::Direction::Right
+        {
+            return Err(format!(
+                "the initial state '{}' must only skip the leading blank",
+                tm.initial_state
+            ));
+        }
+        let is_accepting = |s: &String| {
+            (!tm.accept_state.is_empty() && *s == tm.accept_state)
+                || (!tm.halt_state.is_empty() && *s == tm.halt_state)
+        };
+
+        let mut fa = FiniteAutomaton::new();
+        fa.initial_state = starts[0].new_state.clone();
+        fa.input_alphabet = tm.input_alphabet.clone();
+        for t in tm.transitions.iter() {
+            if t.state == tm.initial_state {
+                continue;
+            }
+            let symbol = &t.symbols[0];
+            let direction = &t.directions[0];
+            if is_accepting(&t.new_state)
+                && symbol == blank
+                && *direction == turing_machine::Direction::Stay
+            {
+                if !fa.final_states.contains(&t.state) {
+                    fa.final_states.push(t.state.clone());
+                }
+            } else if *direction == turing_machine::Direction::Right && symbol != blank {
+                fa.add_transition(t.state.clone(), symbol.clone(), t.new_state.clone());
+            } else if *direction == turing_machine::Direction::Stay && t.symbols == t.new_symbols {
+                let has_blank_move = tm.transitions.iter().any(|e| {
+                    e.state == t.state
+                        && e.new_state == t.new_state
+                        && e.symbols[0] == *blank
+                        && e.new_symbols[0] == *blank
+                        && e.directions[0] == turing_machine::Direction::Stay
+                });
+                if !has_blank_move {
+                    return Err(format!(
+                        "move from '{}' to '{}' on '{}' cannot be read as an ε-move",
+                        t.state, t.new_state, symbol
+                    ));
+                }
+                fa.add_transition(t.state.clone(), String::new(), t.new_state.clone());
+            } else {
+                return Err(format!(
+                    "move from '{}' to '{}' on '{}' cannot be read as a finite automaton move",
+                    t.state, t.new_state, symbol
+                ));
+            }
+        }
+
+        let used: Vec<&String> = fa
+            .transitions
+            .iter()
+            .flat_map(|t| [&t.state, &t.new_state])
+            .chain(fa.final_states.iter())
+            .chain(std::iter::once(&fa.initial_state))
+            .collect();
+        fa.states = tm
+            .states
+            .iter()
+            .filter(|s| used.contains(s) || (**s != tm.initial_state && !is_accepting(s)))
+            .cloned()
+            .collect();
+        for s in used {
+            if !fa.states.contains(s) {
+                fa.states.push(s.clone());
+            }
+        }
+        // states such as "state 0" cannot be written in a fsm file
+        for state in fa.states.clone() {
+            if state.contains(' ') {
+                let mut new_name = state.replace(' ', "_");
+                while fa.states.contains(&new_name) {
+                    new_name += "_";
+                }
+                fa.rename_state(&state, &new_name);
+            }
+        }
+        Ok(fa)
+    }
+
+    /// Renames a state of the automaton everywhere it appears.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_name` - The current name of the state
+    /// * `new_name` - The new name of the state
+    pub fn rename_state(&mut self, old_name: &str, new_name: &str) {
+        let rename = |s: &mut String| {
+            if s == old_name {
+                *s = new_name.to_string();
+            }
+        };
+        rename(&mut self.initial_state);
+        self.final_states.iter_mut().for_each(rename);
+        self.states.iter_mut().for_each(rename);
+        for t in self.transitions.iter_mut() {
+            rename(&mut t.state);
+            rename(&mut t.new_state);
+        }
+    }
+
+    /// Converts the automaton into an equivalent deterministic automaton.
+    ///
+    /// The conversion uses the subset construction: each state of the resulting automaton is the
+    /// ε-closure of a set of states of the original one, named after its members (e.g. `{1,2}`).
+    ///
+    /// # Returns
+    ///
+    /// A deterministic `FiniteAutomaton` recognizing the same language, over the same alphabet.
+    ///
+    /// # Notes
+    ///
+    /// - Only the sets reachable from the initial one are built
+    /// - The empty set is not built: the resulting automaton may be partial, and a missing
+    ///   transition rejects the input as before
+    /// - A set is final if it contains at least one final state of the original automaton
+    pub fn determinize(&self) -> FiniteAutomaton {
+        let mut dfa = FiniteAutomaton::new();
+        dfa.input_alphabet = self.input_alphabet.clone();
+        let initial = self.epsilon_closure(std::slice::from_ref(&self.initial_state));
+        dfa.initial_state = state_set_name(&initial);
+
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(initial);
+        while let Some(set) = queue.pop_front() {
+            let name = state_set_name(&set);
+            if dfa.states.contains(&name) {
+                continue;
+            }
+            dfa.states.push(name.clone());
+            if set.iter().any(|s| self.is_final(s)) {
+                dfa.final_states.push(name.clone());
+            }
+            for symbol in self.input_alphabet.iter() {
+                let next = self.step(&set, symbol);
+                if next.is_empty() {
+                    continue;
+                }
+                dfa.add_transition(name.clone(), symbol.clone(), state_set_name(&next));
+                queue.push_back(next);
+            }
+        }
+        dfa
+    }
+
+    /// Removes the states which cannot be reached from the initial state.
+    ///
+    /// # Returns
+    ///
+    /// A `FiniteAutomaton` with only the reachable states and the transitions between them.
+    /// The reachable states missing from the list of states (e.g. the targets of transitions
+    /// of a file which does not declare them) are added to it.
+    pub fn remove_unreachable(&self) -> FiniteAutomaton {
+        let mut reachable = vec![self.initial_state.clone()];
+        let mut stack = vec![self.initial_state.clone()];
+        while let Some(state) = stack.pop() {
+            for t in self.transitions.iter() {
+                if t.state == state && !reachable.contains(&t.new_state) {
+                    reachable.push(t.new_state.clone());
+                    stack.push(t.new_state.clone());
+                }
+            }
+        }
+        let mut fa = self.clone();
+        fa.states.retain(|s| reachable.contains(s));
+        for state in reachable.iter() {
+            if !fa.states.contains(state) {
+                fa.states.push(state.clone());
+            }
+        }
+        fa.final_states.retain(|s| reachable.contains(s));
+        fa.transitions.retain(|t| reachable.contains(&t.state));
+        fa
+    }
+
+    /// Completes a deterministic automaton, so that every state has a transition on every symbol.
+    ///
+    /// # Returns
+    ///
+    /// A `FiniteAutomaton` where every missing transition goes to a fresh non-final sink state,
+    /// named `sink`, which loops on every symbol. If no transition is missing, the automaton is
+    /// returned unchanged.
+    ///
+    /// # Notes
+    ///
+    /// The automaton is expected to be deterministic, see `determinize`.
+    pub fn complete(&self) -> FiniteAutomaton {
+        let mut fa = self.clone();
+        let mut sink = "sink".to_string();
+        while fa.states.contains(&sink) {
+            sink += "_";
+        }
+        let mut sink_used = false;
+        for state in self.states.iter() {
+            for symbol in self.input_alphabet.iter() {
+                if !self
+                    .transitions
+                    .iter()
+                    .any(|t| t.state == *state && t.symbol == *symbol)
+                {
+                    fa.add_transition(state.clone(), symbol.clone(), sink.clone());
+                    sink_used = true;
+                }
+            }
+        }
+        if sink_used {
+            fa.states.push(sink.clone());
+            for symbol in self.input_alphabet.iter() {
+                fa.add_transition(sink.clone(), symbol.clone(), sink.clone());
+            }
+        }
+        fa
+    }
+
+    /// Computes the minimal deterministic automaton equivalent to this one.
+    ///
+    /// The automaton is first determinized (if needed), trimmed of its unreachable states and
+    /// completed with a sink state (if needed). Then the states are partitioned with Hopcroft's
+    /// partition refinement: two states end up in the same block exactly when no word leads one
+    /// of them to acceptance and the other to rejection, i.e. the blocks are the Myhill–Nerode
+    /// equivalence classes of the language.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// - The minimal complete DFA, whose states are the equivalence classes, named after their
+    ///   members (e.g. `[1,3]`)
+    /// - The mapping from each state of the (determinized, trimmed and completed) automaton to
+    ///   the name of its equivalence class
+    ///
+    /// # Notes
+    ///
+    /// - For a non-deterministic automaton the mapping refers to the states built by `determinize`
+    /// - Unreachable states do not belong to any class and are not part of the mapping
+    pub fn minimize(
+        &self,
+    ) -> (
+        FiniteAutomaton,
+        std::collections::HashMap<String, String>,
+    ) {
+        let dfa = if self.is_deterministic() {
+            self.clone()
+        } else {
+            self.determinize()
+        };
+        let dfa = dfa.remove_unreachable().complete();
+        let n = dfa.states.len();
+        let index: std::collections::HashMap<&String, usize> =
+            dfa.states.iter().enumerate().map(|(i, s)| (s, i)).collect();
+        // inverse[a][q] = states p with a transition p --a--> q
+        let mut inverse = vec![vec![Vec::new(); n]; dfa.input_alphabet.len()];
+        for t in dfa.transitions.iter() {
+            if let Some(a) = dfa.input_alphabet.iter().position(|s| *s == t.symbol) {
+                inverse[a][index[&t.new_state]].push(index[&t.state]);
+            }
+        }
+
+        let (finals, non_finals): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|i| dfa.is_final(&dfa.states[*i]));
+        let mut partition: Vec<Vec<usize>> = [finals, non_finals]
+            .into_iter()
+            .filter(|b| !b.is_empty())
+            .collect();
+        let mut waiting: Vec<Vec<usize>> = partition.clone();
+        while let Some(splitter) = waiting.pop() {
+            for inverse_a in inverse.iter() {
+                let mut predecessors: Vec<usize> = splitter
+                    .iter()
+                    .flat_map(|q| inverse_a[*q].iter().cloned())
+                    .collect();
+                predecessors.sort();
+                predecessors.dedup();
+                let mut new_partition = Vec::new();
+                for block in partition.into_iter() {
+                    let (inside, outside): (Vec<usize>, Vec<usize>) = block
+                        .iter()
+                        .partition(|q| predecessors.binary_search(q).is_ok());
+                    if inside.is_empty() || outside.is_empty() {
+                        new_partition.push(block);
+                        continue;
+                    }
+                    if let Some(pos) = waiting.iter().position(|w| *w == block) {
+                        waiting.remove(pos);
+                        waiting.push(inside.clone());
+                        waiting.push(outside.clone());
+                    } else if inside.len() <= outside.len() {
+                        waiting.push(inside.clone());
+                    } else {
+                        waiting.push(outside.clone());
+                    }
+                    new_partition.push(inside);
+                    new_partition.push(outside);
+                }
+                partition = new_partition;
+            }
+        }
+        partition.sort_by_key(|b| b[0]);
+
+        let mut class_of = vec![String::new(); n];
+        for block in partition.iter() {
+            let members: Vec<String> = block.iter().map(|i| dfa.states[*i].clone()).collect();
+            let name = format!("[{}]", members.join(","));
+            for i in block.iter() {
+                class_of[*i] = name.clone();
+            }
+        }
+        let mut minimal = FiniteAutomaton::new();
+        minimal.input_alphabet = dfa.input_alphabet.clone();
+        minimal.initial_state = class_of[index[&dfa.initial_state]].clone();
+        for block in partition.iter() {
+            let name = class_of[block[0]].clone();
+            minimal.states.push(name.clone());
+            if dfa.is_final(&dfa.states[block[0]]) {
+                minimal.final_states.push(name);
+            }
+        }
+        for t in dfa.transitions.iter() {
+            minimal.add_transition(
+                class_of[index[&t.state]].clone(),
+                t.symbol.clone(),
+                class_of[index[&t.new_state]].clone(),
+            );
+        }
+        let mapping = dfa
+            .states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.clone(), class_of[i].clone()))
+            .collect();
+        (minimal, mapping)
+    }
+
+    /// Builds the transition map of a deterministic automaton, from state and symbol to the
+    /// reached state.
+    ///
+    /// # Returns
+    ///
+    /// A `HashMap` indexed by (state, symbol) pairs. ε-moves are ignored.
+    pub fn make_transition_map(&self) -> std::collections::HashMap<(String, String), String> {
+        self.transitions
+            .iter()
+            .filter(|t| !t.symbol.is_empty())
+            .map(|t| ((t.state.clone(), t.symbol.clone()), t.new_state.clone()))
+            .collect()
+    }
+
+    /// Decides whether two automata recognize the same language, and finds a word telling them
+    /// apart if they do not.
+    ///
+    /// Both automata are determinized and completed over the union of their alphabets, then
+    /// the product automaton is explored breadth-first from the pair of initial states, until a
+    /// pair with exactly one final state is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The automaton to compare with
+    ///
+    /// # Returns
+    ///
+    /// * `None` - if the two automata recognize the same language
+    /// * `Some(word)` - the shortest word accepted by exactly one of the two automata
+    pub fn distinguishing_word(&self, other: &FiniteAutomaton) -> Option<Vec<String>> {
+        let alphabet = self.alphabet_union(other);
+        let (a, b) = (self.complete_dfa(&alphabet), other.complete_dfa(&alphabet));
+        let (delta_a, delta_b) = (a.make_transition_map(), b.make_transition_map());
+
+        // each node is a pair of states, with the index of its parent and the symbol read
+        let mut nodes: Vec<(String, String, usize, String)> =
+            vec![(a.initial_state.clone(), b.initial_state.clone(), 0, String::new())];
+        let mut visited = std::collections::HashSet::new();
+        visited.insert((a.initial_state.clone(), b.initial_state.clone()));
+        let mut current = 0;
+        while current < nodes.len() {
+            let (pa, pb) = (nodes[current].0.clone(), nodes[current].1.clone());
+            if a.is_final(&pa) != b.is_final(&pb) {
+                let mut word = Vec::new();
+                let mut index = current;
+                while index != 0 {
+                    word.push(nodes[index].3.clone());
+                    index = nodes[index].2;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            for symbol in alphabet.iter() {
+                let key_a = (pa.clone(), symbol.clone());
+                let key_b = (pb.clone(), symbol.clone());
+                if let (Some(qa), Some(qb)) = (delta_a.get(&key_a), delta_b.get(&key_b)) {
+                    if visited.insert((qa.clone(), qb.clone())) {
+                        nodes.push((qa.clone(), qb.clone(), current, symbol.clone()));
+                    }
+                }
+            }
+            current += 1;
+        }
+        None
+    }
+
+    /// Merges the alphabet of the automaton with the one of another automaton.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other automaton
+    ///
+    /// # Returns
+    ///
+    /// The symbols of this automaton followed by the new symbols of the other one.
+    pub fn alphabet_union(&self, other: &FiniteAutomaton) -> Vec<String> {
+        let mut alphabet = self.input_alphabet.clone();
+        for symbol in other.input_alphabet.iter() {
+            if !alphabet.contains(symbol) {
+                alphabet.push(symbol.clone());
+            }
+        }
+        alphabet
+    }
+
+    /// Builds a complete deterministic automaton equivalent to this one over a larger alphabet.
+    ///
+    /// # Arguments
+    ///
+    /// * `alphabet` - The alphabet of the result, which should contain the one of the automaton
+    ///
+    /// # Returns
+    ///
+    /// The determinized automaton, completed with a sink state over the given alphabet. The new
+    /// symbols only lead to the sink state.
+    pub fn complete_dfa(&self, alphabet: &[String]) -> FiniteAutomaton {
+        let mut fa = self.clone();
+        fa.input_alphabet = alphabet.to_vec();
+        fa.determinize().complete()
+    }
+
+    /// Combines two automata with the product construction.
+    ///
+    /// Both automata are determinized and completed over the union of their alphabets; the
+    /// states of the result are the reachable pairs of states, named `(p,q)`, and the moves
+    /// are made by both automata at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The second automaton
+    /// * `operation` - The boolean operation deciding which pairs are final
+    ///
+    /// # Returns
+    ///
+    /// A deterministic `FiniteAutomaton` recognizing the union, intersection or difference of
+    /// the two languages.
+    pub fn product(&self, other: &FiniteAutomaton, operation: ProductOperation) -> FiniteAutomaton {
+        let alphabet = self.alphabet_union(other);
+        let (a, b) = (self.complete_dfa(&alphabet), other.complete_dfa(&alphabet));
+        let (delta_a, delta_b) = (a.make_transition_map(), b.make_transition_map());
+        let pair_name = |p: &String, q: &String| format!("({},{})", p, q);
+
+        let mut fa = FiniteAutomaton::new();
+        fa.input_alphabet = alphabet.clone();
+        fa.initial_state = pair_name(&a.initial_state, &b.initial_state);
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back((a.initial_state.clone(), b.initial_state.clone()));
+        while let Some((p, q)) = queue.pop_front() {
+            let name = pair_name(&p, &q);
+            if fa.states.contains(&name) {
+                continue;
+            }
+            fa.states.push(name.clone());
+            let is_final = match operation {
+                ProductOperation::Union => a.is_final(&p) || b.is_final(&q),
+                ProductOperation::Intersection => a.is_final(&p) && b.is_final(&q),
+                ProductOperation::Difference => a.is_final(&p) && !b.is_final(&q),
+            };
+            if is_final {
+                fa.final_states.push(name.clone());
+            }
+            for symbol in alphabet.iter() {
+                let key_a = (p.clone(), symbol.clone());
+                let key_b = (q.clone(), symbol.clone());
+                if let (Some(next_p), Some(next_q)) = (delta_a.get(&key_a), delta_b.get(&key_b)) {
+                    fa.add_transition(name.clone(), symbol.clone(), pair_name(next_p, next_q));
+                    queue.push_back((next_p.clone(), next_q.clone()));
+                }
+            }
+        }
+        fa
+    }
+
+    /// Builds an automaton recognizing the complement of the language of this one.
+    ///
+    /// # Returns
+    ///
+    /// A complete deterministic `FiniteAutomaton`, obtained by determinizing the automaton,
+    /// completing it with a sink state and swapping final and non-final states. The complement
+    /// is taken with respect to the words over the alphabet of the automaton.
+    pub fn complement(&self) -> FiniteAutomaton {
+        let mut fa = self.complete_dfa(&self.input_alphabet);
+        fa.final_states = fa
+            .states
+            .iter()
+            .filter(|s| !fa.is_final(s))
+            .cloned()
+            .collect();
+        fa
+    }
+
+    /// Finds every match of the automaton inside an input.
+    ///
+    /// The input is scanned from left to right: at each position the automaton is run to find
+    /// the match starting there, and the search resumes at the end of the match. An empty match
+    /// right after the end of the previous match is skipped, and after an empty match the search
+    /// resumes one symbol later.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols to search
+    /// * `semantics` - The rules choosing among the matches starting at the same position
+    ///
+    /// # Returns
+    ///
+    /// The spans `(start, end)` of the matches, as indices of the first symbol and of the symbol
+    /// after the last one.
+    pub fn find_matches(&self, input: &[String], semantics: MatchSemantics) -> Vec<(usize, usize)> {
+        let mut matches: Vec<(usize, usize)> = Vec::new();
+        let mut start = 0;
+        while start <= input.len() {
+            let end = match semantics {
+                MatchSemantics::LeftmostLongest => self.longest_match_at(input, start),
+                MatchSemantics::LeftmostFirst => self.first_match_at(input, start),
+            };
+            match end {
+                Some(end) if end == start && matches.last().is_some_and(|m| m.1 == start) => {
+                    start += 1;
+                }
+                Some(end) => {
+                    matches.push((start, end));
+                    start = if end > start { end } else { start + 1 };
+                }
+                None => start += 1,
+            }
+        }
+        matches
+    }
+
+    /// Finds the end of the longest match starting at a position, by tracking the set of active
+    /// states until it becomes empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols to search
+    /// * `start` - The position of the first symbol of the match
+    ///
+    /// # Returns
+    ///
+    /// The position after the last symbol of the longest match, or `None` if no match starts at `start`.
+    fn longest_match_at(&self, input: &[String], start: usize) -> Option<usize> {
+        let mut current = self.epsilon_closure(std::slice::from_ref(&self.initial_state));
+        let mut end = None;
+        let mut position = start;
+        loop {
+            if current.iter().any(|s| self.is_final(s)) {
+                end = Some(position);
+            }
+            if position == input.len() || current.is_empty() {
+                return end;
+            }
+            current = self.step(&current, &input[position]);
+            position += 1;
+        }
+    }
+
+    /// Finds the end of the match starting at a position which is preferred by the order of the
+    /// transitions.
+    ///
+    /// The active states are kept in a list ordered by priority, built by following the moves
+    /// of each state in the order in which they are declared. When a final state is reached, the
+    /// match is recorded and the states of lower priority are dropped, while the ones of higher
+    /// priority keep running and may later replace the match. Leaving a final state through one of
+    /// its moves is preferred over stopping in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols to search
+    /// * `start` - The position of the first symbol of the match
+    ///
+    /// # Returns
+    ///
+    /// The position after the last symbol of the preferred match, or `None` if no match starts at `start`.
+    fn first_match_at(&self, input: &[String], start: usize) -> Option<usize> {
+        let mut current = Vec::new();
+        self.add_by_priority(&self.initial_state, &mut current);
+        let mut end = None;
+        let mut position = start;
+        while !current.is_empty() {
+            let mut next = Vec::new();
+            for state in current.iter() {
+                if position < input.len() {
+                    for t in self.transitions.iter() {
+                        if t.state == *state && t.symbol == input[position] {
+                            self.add_by_priority(&t.new_state, &mut next);
+                        }
+                    }
+                }
+                if self.is_final(state) {
+                    end = Some(position);
+                    break;
+                }
+            }
+            if position == input.len() {
+                break;
+            }
+            current = next;
+            position += 1;
+        }
+        end
+    }
+
+    /// Adds a state and the states reachable from it through ε-moves to a list ordered by
+    /// priority, following the ε-moves depth-first in the order in which they are declared.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to add
+    /// * `list` - The list of states, where states already present keep their priority
+    fn add_by_priority(&self, state: &String, list: &mut Vec<String>) {
+        if list.contains(state) {
+            return;
+        }
+        list.push(state.clone());
+        for t in self.transitions.iter() {
+            if t.state == *state && t.symbol.is_empty() {
+                self.add_by_priority(&t.new_state, list);
+            }
+        }
+    }
+}
+
+/// Builds a readable name for a set of states, such as `{q0,q1}`.
+///
+/// # Arguments
+///
+/// * `states` - The states of the set, in the order they should be printed
+///
+/// # Returns
+///
+/// The name of the set. The empty set is named `{}`.
+pub fn state_set_name(states: &[String]) -> String {
+    format!("{{{}}}", states.join(","))
+}
+
+/// A builder of finite automata, constructing them in code instead of reading them from files.
+///
+/// The states declared with `states` come first, in their order, followed by the other states
+/// of the transitions and the final states, in the order in which they appear. The input
+/// alphabet is completed with the symbols of the transitions.
+///
+/// # Fields
+///
+/// * `automaton` - The automaton built so far
+#[derive(Clone)]
+pub struct FiniteAutomatonBuilder {
+    automaton: FiniteAutomaton,
+}
+
+impl Default for FiniteAutomatonBuilder {
+    fn default() -> Self {
+        FiniteAutomatonBuilder::new()
+    }
+}
+
+impl FiniteAutomatonBuilder {
+    /// Creates a builder for an automaton without states.
+    pub fn new() -> Self {
+        FiniteAutomatonBuilder {
+            automaton: FiniteAutomaton::new(),
+        }
+    }
+
+    /// Sets the initial state.
+    pub fn initial_state(mut self, state: &str) -> Self {
+        self.automaton.initial_state = state.to_string();
+        self
+    }
+
+    /// Adds accepting states.
+    pub fn final_states(mut self, states: &[&str]) -> Self {
+        for state in states {
+            if !self.automaton.final_states.iter().any(|s| s == state) {
+                self.automaton.final_states.push(state.to_string());
+            }
+        }
+        self
+    }
+
+    /// Declares states, fixing their order.
+    pub fn states(mut self, states: &[&str]) -> Self {
+        for state in states {
+            if !self.automaton.states.iter().any(|s| s == state) {
+                self.automaton.states.push(state.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds symbols to the input alphabet.
+    pub fn input_alphabet(mut self, symbols: &[&str]) -> Self {
+        for symbol in symbols {
+            if !self.automaton.input_alphabet.iter().any(|s| s == symbol) {
+                self.automaton.input_alphabet.push(symbol.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds a move consuming a symbol.
+    pub fn transition(mut self, state: &str, symbol: &str, new_state: &str) -> Self {
+        self.automaton
+            .add_transition(state.to_string(), symbol.to_string(), new_state.to_string());
+        self
+    }
+
+    /// Adds an ε-move.
+    pub fn epsilon(mut self, state: &str, new_state: &str) -> Self {
+        self.automaton
+            .add_transition(state.to_string(), String::new(), new_state.to_string());
+        self
+    }
+
+    /// Builds the automaton.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FiniteAutomaton)` - The automaton, with its states and input alphabet completed
+    /// * `Err(String)` - An error message if the initial state is missing
+    pub fn build(self) -> Result<FiniteAutomaton, String> {
+        let mut fa = self.automaton;
+        if fa.initial_state.is_empty() {
+            return Err("the initial state is not set".to_string());
+        }
+        let mut states = vec![fa.initial_state.clone()];
+        let mut symbols = Vec::new();
+        for t in fa.transitions.iter() {
+            states.push(t.state.clone());
+            states.push(t.new_state.clone());
+            if !t.symbol.is_empty() {
+                symbols.push(t.symbol.clone());
+            }
+        }
+        states.extend(fa.final_states.iter().cloned());
+        for state in states {
+            if !fa.states.contains(&state) {
+                fa.states.push(state);
+            }
+        }
+        for symbol in symbols {
+            if !fa.input_alphabet.contains(&symbol) {
+                fa.input_alphabet.push(symbol);
+            }
+        }
+        Ok(fa)
+    }
+}
+
+impl std::fmt::Display for FiniteAutomaton {
+    /// Formats the automaton as a `fsm` file, which can be read back by the file handler.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "fsm")?;
+        writeln!(f, "{}", self.initial_state)?;
+        writeln!(f, "{}", self.final_states.join(" "))?;
+        writeln!(f, "{}", self.states.join(" "))?;
+        write!(f, "{}", self.input_alphabet.join(" "))?;
+        for t in self.transitions.iter() {
+            if t.symbol.is_empty() {
+                write!(f, "\n{} {}", t.state, t.new_state)?;
+            } else {
+                write!(f, "\n{} {} {}", t.state, t.symbol, t.new_state)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex;
+
+    fn bab() -> FiniteAutomaton {
+        let mut fa = FiniteAutomaton::new();
+        fa.initial_state = "1".to_string();
+        fa.final_states = vec!["1".to_string(), "2".to_string()];
+        fa.states = vec!["1", "2", "3", "4"].into_iter().map(String::from).collect();
+        fa.input_alphabet = vec!["a".to_string(), "b".to_string()];
+        for (p, a, q) in [("1", "a", "4"), ("1", "b", "2"), ("2", "b", "2"), ("2", "a", "3"), ("3", "b", "2")] {
+            fa.add_transition(p.to_string(), a.to_string(), q.to_string());
+        }
+        fa
+    }
+
+    fn word(s: &str) -> Vec<String> {
+        s.chars().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_simulate_dfa() {
+        let fa = bab();
+        assert!(fa.is_deterministic());
+        assert_eq!(fa.simulate(word("babbab"), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(fa.simulate(word(""), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(fa.simulate(word("ba"), 100).unwrap().outcome, computer::Outcome::Reject);
+        assert_eq!(fa.simulate(word("aab"), 100).unwrap().outcome, computer::Outcome::Reject);
+        assert_eq!(fa.simulate(word("babbab"), 3).unwrap().outcome, computer::Outcome::Timeout);
+    }
+
+    #[test]
+    fn test_simulate_epsilon_nfa() {
+        let mut fa = FiniteAutomaton::new();
+        fa.initial_state = "0".to_string();
+        fa.final_states = vec!["2".to_string()];
+        fa.states = vec!["0", "1", "2"].into_iter().map(String::from).collect();
+        fa.input_alphabet = vec!["a".to_string(), "b".to_string()];
+        fa.add_transition("0".to_string(), "a".to_string(), "0".to_string());
+        fa.add_transition("0".to_string(), "".to_string(), "1".to_string());
+        fa.add_transition("1".to_string(), "b".to_string(), "2".to_string());
+        assert!(!fa.is_deterministic());
+        assert_eq!(fa.epsilon_closure(&["0".to_string()]), vec!["0", "1"]);
+        let computer::SimulationResult {
+            outcome: state,
+            steps,
+            computation,
+            ..
+        } = fa.simulate(word("aab"), 100).unwrap();
+        assert_eq!(state, computer::Outcome::Accept);
+        assert_eq!(steps, 3);
+        assert_eq!(computation[0].to_string(), "fsm;{0,1};aab");
+        assert_eq!(computation[3].to_string(), "fsm;{2};");
+        assert_eq!(fa.simulate(word("aa"), 100).unwrap().outcome, computer::Outcome::Reject);
+    }
+
+    #[test]
+    fn test_tm_round_trip() {
+        let mut fa = bab();
+        fa.add_transition("4".to_string(), "".to_string(), "1".to_string());
+        let tm = fa.to_tm();
+        assert_eq!(tm.initial_state, "1_init");
+        assert_eq!(tm.halt_state, "final");
+        assert_eq!(FiniteAutomaton::from_tm(&tm).unwrap(), fa);
+    }
+
+    #[test]
+    fn test_from_regex_tm() {
+        let tm = regex::regex_to_fsa(&regex::build_regex_tree("a(b|c)*").unwrap()).unwrap();
+        let fa = FiniteAutomaton::from_tm(&tm).unwrap();
+        assert_eq!(fa.simulate(word("abcb"), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(fa.simulate(word("a"), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(fa.simulate(word("ba"), 100).unwrap().outcome, computer::Outcome::Reject);
+        assert!(fa.states.iter().all(|s| !s.contains(' ')));
+        assert_eq!(fa.initial_state, "state_0");
+    }
+
+    #[test]
+    fn test_from_tm_invalid() {
+        let mut tm = bab().to_tm();
+        tm.add_transition(
+            "2".to_string(),
+            vec!["a".to_string()],
+            "3".to_string(),
+            vec!["b".to_string()],
+            vec![turing_machine::Direction::Left],
+        );
+        assert!(FiniteAutomaton::from_tm(&tm).is_err());
+        tm.tape_count = 2;
+        assert!(FiniteAutomaton::from_tm(&tm).is_err());
+    }
+
+    #[test]
+    fn test_determinize() {
+        let mut fa = FiniteAutomaton::new();
+        fa.initial_state = "0".to_string();
+        fa.final_states = vec!["3".to_string()];
+        fa.states = vec!["0", "1", "2", "3"].into_iter().map(String::from).collect();
+        fa.input_alphabet = vec!["a".to_string(), "b".to_string()];
+        // (a|b)*ab with an ε-move in front of the final a
+        fa.add_transition("0".to_string(), "a".to_string(), "0".to_string());
+        fa.add_transition("0".to_string(), "b".to_string(), "0".to_string());
+        fa.add_transition("0".to_string(), "".to_string(), "1".to_string());
+        fa.add_transition("1".to_string(), "a".to_string(), "2".to_string());
+        fa.add_transition("2".to_string(), "b".to_string(), "3".to_string());
+        let dfa = fa.determinize();
+        assert!(dfa.is_deterministic());
+        assert_eq!(dfa.initial_state, "{0,1}");
+        assert_eq!(dfa.states, vec!["{0,1}", "{0,1,2}", "{0,1,3}"]);
+        assert_eq!(dfa.final_states, vec!["{0,1,3}"]);
+        for w in ["ab", "aab", "babab", "", "aba", "b"] {
+            assert_eq!(
+                dfa.simulate(word(w), 100).unwrap().outcome,
+                fa.simulate(word(w), 100).unwrap().outcome
+            );
+        }
+    }
+
+    #[test]
+    fn test_determinize_partial() {
+        let dfa = bab().determinize();
+        assert_eq!(dfa.states, vec!["{1}", "{4}", "{2}", "{3}"]);
+        assert_eq!(dfa.transitions.len(), 5);
+    }
+
+    #[test]
+    fn test_complete() {
+        let fa = bab().complete();
+        assert_eq!(fa.states.last().unwrap(), "sink");
+        assert_eq!(fa.transitions.len(), 10);
+        assert_eq!(fa.simulate(word("bab"), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(fa.simulate(word("aab"), 100).unwrap().outcome, computer::Outcome::Reject);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let (min, mapping) = bab().minimize();
+        // the dead state 4 and the sink are the same class
+        assert_eq!(min.states, vec!["[1]", "[2]", "[3]", "[4,sink]"]);
+        assert_eq!(mapping.get("4").unwrap(), "[4,sink]");
+        assert_eq!(mapping.get("sink").unwrap(), "[4,sink]");
+        assert_eq!(min.initial_state, "[1]");
+        assert_eq!(min.final_states, vec!["[1]", "[2]"]);
+        for w in ["babbab", "", "ba", "aab", "bbabb"] {
+            assert_eq!(
+                min.simulate(word(w), 100).unwrap().outcome,
+                bab().simulate(word(w), 100).unwrap().outcome
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimize_undeclared_state() {
+        let mut fa = FiniteAutomaton::new();
+        fa.initial_state = "1".to_string();
+        fa.final_states = vec!["2".to_string()];
+        fa.states = vec!["1".to_string(), "2".to_string()];
+        fa.input_alphabet = vec!["a".to_string()];
+        fa.add_transition("1".to_string(), "a".to_string(), "3".to_string());
+        let (min, mapping) = fa.minimize();
+        assert!(mapping.contains_key("3"));
+        assert_eq!(min.simulate(word("a"), 100).unwrap().outcome, computer::Outcome::Reject);
+    }
+
+    #[test]
+    fn test_minimize_regex() {
+        let tm = regex::regex_to_fsa(&regex::build_regex_tree("(a|b)*abb").unwrap()).unwrap();
+        let fa = FiniteAutomaton::from_tm(&tm).unwrap();
+        let (min, mapping) = fa.minimize();
+        assert!(min.is_deterministic());
+        assert_eq!(min.states.len(), 4);
+        assert_eq!(min.final_states.len(), 1);
+        assert_eq!(mapping.len(), fa.determinize().states.len());
+        assert_eq!(min.simulate(word("babb"), 100).unwrap().outcome, computer::Outcome::Accept);
+        assert_eq!(min.simulate(word("abab"), 100).unwrap().outcome, computer::Outcome::Reject);
+    }
+
+    #[test]
+    fn test_distinguishing_word() {
+        let regex_fa = |r: &str| {
+            FiniteAutomaton::from_tm(
+                &regex::regex_to_fsa(&regex::build_regex_tree(r).unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(regex_fa("(a|b)*").distinguishing_word(&regex_fa("(a*b*)*")), None);
+        assert_eq!(bab().distinguishing_word(&bab().minimize().0), None);
+        assert_eq!(
+            regex_fa("a(b|c)*").distinguishing_word(&regex_fa("ab*")),
+            Some(word("ac"))
+        );
+        // the empty word is accepted by bab only
+        assert_eq!(bab().distinguishing_word(&regex_fa("b(ab)*")), Some(word("")));
+        // symbols missing from one of the alphabets are rejected by that automaton
+        assert_eq!(regex_fa("a*").distinguishing_word(&regex_fa("a*|c")), Some(word("c")));
+    }
+
+    #[test]
+    fn test_boolean_operations() {
+        let regex_fa = |r: &str| {
+            FiniteAutomaton::from_tm(
+                &regex::regex_to_fsa(&regex::build_regex_tree(r).unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+        let a_then_b = regex_fa("a*b*");
+        let even = regex_fa("((a|b)(a|b))*");
+        let union = a_then_b.product(&even, ProductOperation::Union);
+        let intersection = a_then_b.product(&even, ProductOperation::Intersection);
+        let difference = a_then_b.product(&even, ProductOperation::Difference);
+        assert!(union.is_deterministic());
+        assert_eq!(
+            union.initial_state,
+            format!(
+                "({},{})",
+                a_then_b.determinize().initial_state,
+                even.determinize().initial_state
+            )
+        );
+        for (w, u, i, d) in [
+            ("aab", true, false, true),
+            ("ab", true, true, false),
+            ("ba", true, false, false),
+            ("bab", false, false, false),
+            ("", true, true, false),
+        ] {
+            let outcome = |accepted| {
+                if accepted {
+                    computer::Outcome::Accept
+                } else {
+                    computer::Outcome::Reject
+                }
+            };
+            assert_eq!(union.simulate(word(w), 100).unwrap().outcome, outcome(u));
+            assert_eq!(intersection.simulate(word(w), 100).unwrap().outcome, outcome(i));
+            assert_eq!(difference.simulate(word(w), 100).unwrap().outcome, outcome(d));
+        }
+        assert_eq!(
+            intersection.distinguishing_word(&regex_fa("(aa)*(bb)*|a(aa)*b(bb)*")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_complement() {
+        let complement = bab().complement();
+        assert!(complement.is_deterministic());
+        assert_eq!(complement.final_states, vec!["{4}", "{3}", "sink"]);
+        for w in ["babbab", "", "ba", "aab", "bbabb"] {
+            assert_ne!(
+                complement.simulate(word(w), 100).unwrap().outcome,
+                bab().simulate(word(w), 100).unwrap().outcome
+            );
+        }
+        assert_eq!(complement.complement().distinguishing_word(&bab()), None);
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let regex_fa = |r: &str| {
+            FiniteAutomaton::from_tm(
+                &regex::regex_to_fsa(&regex::build_regex_tree(r).unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+        let longest = MatchSemantics::LeftmostLongest;
+        let first = MatchSemantics::LeftmostFirst;
+        let fa = regex_fa("a|ab");
+        assert_eq!(fa.find_matches(&word("xabab"), longest.clone()), vec![(1, 3), (3, 5)]);
+        assert_eq!(fa.find_matches(&word("xabab"), first.clone()), vec![(1, 2), (3, 4)]);
+        let fa = regex_fa("a*");
+        assert_eq!(fa.find_matches(&word("baa"), longest.clone()), vec![(0, 0), (1, 3)]);
+        assert_eq!(fa.find_matches(&word("baa"), first.clone()), vec![(0, 0), (1, 3)]);
+        let fa = regex_fa("a*?");
+        assert_eq!(fa.find_matches(&word("aab"), first.clone()), vec![(0, 2), (3, 3)]);
+        let fa = regex_fa("(a|ab)(c|bcd)");
+        assert_eq!(fa.find_matches(&word("abcd"), longest.clone()), vec![(0, 4)]);
+        assert_eq!(fa.find_matches(&word("abcd"), first.clone()), vec![(0, 4)]);
+        let dfa = regex::regex_to_dfa(&regex::build_regex_tree("a|ab").unwrap());
+        assert_eq!(dfa.find_matches(&word("abab"), longest.clone()), vec![(0, 2), (2, 4)]);
+        assert_eq!(bab().find_matches(&word("ab"), longest), vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut fa = bab();
+        fa.add_transition("4".to_string(), "".to_string(), "1".to_string());
+        let printed = fa.to_string();
+        assert!(printed.starts_with("fsm\n1\n1 2\n1 2 3 4\na b\n1 a 4\n"));
+        assert!(printed.ends_with("\n4 1"));
+    }
+
+    #[test]
+    fn test_builder() {
+        let fa = FiniteAutomaton::builder()
+            .initial_state("1")
+            .final_states(&["1", "2"])
+            .states(&["1", "2", "3", "4"])
+            .transition("1", "a", "4")
+            .transition("1", "b", "2")
+            .transition("2", "b", "2")
+            .transition("2", "a", "3")
+            .transition("3", "b", "2")
+            .build()
+            .unwrap();
+        assert_eq!(fa, bab());
+
+        let fa = FiniteAutomaton::builder()
+            .initial_state("0")
+            .final_states(&["2"])
+            .epsilon("0", "1")
+            .transition("1", "a", "2")
+            .build()
+            .unwrap();
+        assert_eq!(fa.states, vec!["0", "1", "2"]);
+        assert_eq!(fa.input_alphabet, vec!["a"]);
+        assert_eq!(fa.simulate(word("a"), 100).unwrap().outcome, computer::Outcome::Accept);
+
+        assert!(FiniteAutomaton::builder().build().is_err());
+    }
+}
@@ -0,0 +1,1224 @@
+//! # Grammar Module
+//!
+//! This module provides an implementation of context-free grammars, with membership testing
+//! through the CYK and Earley algorithms and the construction of parse trees.
+//!
+//! ## Main Structures
+//!
+//! - **Grammar**: Represents a context-free grammar, with its start symbol, nonterminals,
+//!   terminals and productions.
+//! - **Production**: Represents a single production `head -> body`. An empty body denotes an
+//!   ε-production.
+//! - **ParseTree**: Represents a parse tree of a word, whose leaves are terminals or `ε`.
+//!
+//! ## Key Features
+//!
+//! - **Parsing**: Read a grammar from the lines of a `cfg` file.
+//! - **Chomsky normal form**: Convert a grammar into an equivalent one in Chomsky normal form,
+//!   by adding a new start symbol and removing ε-productions, unit productions and useless
+//!   symbols.
+//! - **Greibach normal form**: Convert a grammar into an equivalent one in Greibach normal form,
+//!   by removing left recursion and expanding the leading nonterminals of the bodies.
+//! - **Step log**: Record the intermediate grammar produced by each phase of a conversion.
+//! - **CYK**: Decide membership for a grammar in Chomsky normal form with dynamic programming.
+//! - **Earley**: Decide membership for arbitrary grammars, ε-productions and left recursion
+//!   included.
+//! - **Printing**: The `Display` implementation produces a valid `cfg` file.
+//!
+//! ## File Format
+//!
+//! The `cfg` file format is the following (after the `cfg` header line): one or more lines
+//! `A -> α | β | ...`, where the symbols of each alternative are separated by spaces and `ε`
+//! denotes the empty word. A line starting with `|` adds alternatives to the previous head. The
+//! head of the first production is the start symbol, the heads are the nonterminals and every
+//! other symbol is a terminal.
+//!
+//! ```text
+//! cfg
+//! S -> a S b | ε
+//! ```
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::trace;
+
+/// A complete representation of a context-free grammar.
+///
+/// # Fields
+/// * `start_symbol` - The nonterminal every derivation starts from
+/// * `nonterminals` - The nonterminals, in order of declaration
+/// * `terminals` - The terminals, in order of appearance
+/// * `productions` - The productions, in order of declaration
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grammar {
+    pub start_symbol: String,
+    pub nonterminals: Vec<String>,
+    pub terminals: Vec<String>,
+    pub productions: Vec<Production>,
+}
+
+/// Represents a production of a grammar.
+///
+/// # Fields
+///
+/// * `head` - The nonterminal on the left-hand side.
+/// * `body` - The symbols on the right-hand side, empty for an ε-production.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Production {
+    pub head: String,
+    pub body: Vec<String>,
+}
+
+/// Represents a parse tree.
+///
+/// # Fields
+///
+/// * `symbol` - The symbol of the node: a nonterminal for inner nodes, a terminal or `ε` for leaves.
+/// * `children` - The subtrees, one for each symbol of the body of the production applied.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseTree {
+    pub symbol: String,
+    pub children: Vec<ParseTree>,
+}
+
+/// An item of the Earley algorithm: a production, the position of the dot in its body and the
+/// index of the set the item was predicted in.
+type EarleyItem = (usize, usize, usize);
+
+/// A phase of a normal form conversion: its name and the transformation it applies.
+type Phase = (&'static str, fn(&Grammar) -> Grammar);
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Grammar::new()
+    }
+}
+
+impl Grammar {
+    /// Creates a new empty `Grammar` instance.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Grammar` with an empty start symbol and no symbols or productions.
+    pub fn new() -> Self {
+        Grammar {
+            start_symbol: String::new(),
+            nonterminals: Vec::new(),
+            terminals: Vec::new(),
+            productions: Vec::new(),
+        }
+    }
+
+    /// Reads a grammar from the lines of a `cfg` file, without the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The lines holding the productions
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Grammar)` - The grammar described by the lines
+    /// * `Err(String)` - An error message if a line is not a production or there are no productions
+    pub fn from_lines(lines: &[String]) -> Result<Grammar, String> {
+        let mut grammar = Grammar::new();
+        let mut head = String::new();
+        for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let alternatives = if let Some(rest) = line.strip_prefix('|') {
+                if head.is_empty() {
+                    return Err(format!("alternative '{}' without a head", line));
+                }
+                rest
+            } else if let Some((h, rest)) = line.split_once("->") {
+                head = h.trim().to_string();
+                if head.is_empty() || head.contains(' ') {
+                    return Err(format!("invalid head in production '{}'", line));
+                }
+                rest
+            } else {
+                return Err(format!("invalid production '{}'", line));
+            };
+            for alternative in alternatives.split('|') {
+                let body = alternative
+                    .split_whitespace()
+                    .filter(|s| *s != "ε")
+                    .map(String::from)
+                    .collect();
+                grammar.add_production(head.clone(), body);
+            }
+        }
+        if grammar.productions.is_empty() {
+            return Err("a grammar must have at least one production".to_string());
+        }
+        grammar.start_symbol = grammar.productions[0].head.clone();
+        grammar.update_symbols();
+        Ok(grammar)
+    }
+
+    /// Adds a new production to the grammar.
+    ///
+    /// If the production already exists, it will not be added again. The symbols of the grammar
+    /// are not updated, see `update_symbols`.
+    ///
+    /// # Arguments
+    ///
+    /// * `head` - The nonterminal on the left-hand side
+    /// * `body` - The symbols on the right-hand side, empty for an ε-production
+    pub fn add_production(&mut self, head: String, body: Vec<String>) {
+        let production = Production { head, body };
+        if !self.productions.contains(&production) {
+            self.productions.push(production);
+        }
+    }
+
+    /// Recomputes the nonterminals and the terminals from the productions.
+    ///
+    /// The nonterminals are the start symbol and the heads of the productions; the terminals are
+    /// the other symbols of the bodies. Both keep their order of appearance.
+    pub fn update_symbols(&mut self) {
+        let mut nonterminals = vec![self.start_symbol.clone()];
+        for p in self.productions.iter() {
+            if !nonterminals.contains(&p.head) {
+                nonterminals.push(p.head.clone());
+            }
+        }
+        let mut terminals: Vec<String> = Vec::new();
+        for symbol in self.productions.iter().flat_map(|p| p.body.iter()) {
+            if !nonterminals.contains(symbol) && !terminals.contains(symbol) {
+                terminals.push(symbol.clone());
+            }
+        }
+        self.nonterminals = nonterminals;
+        self.terminals = terminals;
+    }
+
+    /// Checks if a symbol is a nonterminal of the grammar.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol to check
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the symbol is one of the nonterminals, `false` otherwise.
+    pub fn is_nonterminal(&self, symbol: &str) -> bool {
+        self.nonterminals.iter().any(|s| s == symbol)
+    }
+
+    /// Builds a name for a new nonterminal, which is not used yet by the grammar.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The name to start from
+    ///
+    /// # Returns
+    ///
+    /// `base` itself if it is free, otherwise `base` followed by as many `'` as needed.
+    pub fn fresh_nonterminal(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        while self.is_nonterminal(&name) || self.terminals.contains(&name) {
+            name.push('\'');
+        }
+        name
+    }
+
+    /// Computes the nonterminals deriving the empty word.
+    ///
+    /// # Returns
+    ///
+    /// The nullable nonterminals, in order of declaration.
+    pub fn nullable(&self) -> Vec<String> {
+        let mut nullable: Vec<String> = Vec::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in self.productions.iter() {
+                if !nullable.contains(&p.head) && p.body.iter().all(|s| nullable.contains(s)) {
+                    nullable.push(p.head.clone());
+                    changed = true;
+                }
+            }
+        }
+        self.nonterminals
+            .iter()
+            .filter(|s| nullable.contains(s))
+            .cloned()
+            .collect()
+    }
+
+    /// Adds a new start symbol deriving the old one, if the old one appears in a body.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose start symbol does not appear on the right-hand side of any production.
+    pub fn add_new_start(&self) -> Grammar {
+        if !self
+            .productions
+            .iter()
+            .any(|p| p.body.contains(&self.start_symbol))
+        {
+            return self.clone();
+        }
+        let mut grammar = self.clone();
+        let start = self.fresh_nonterminal(&format!("{}0", self.start_symbol));
+        grammar.productions.insert(
+            0,
+            Production {
+                head: start.clone(),
+                body: vec![self.start_symbol.clone()],
+            },
+        );
+        grammar.start_symbol = start;
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Removes the ε-productions, adding the productions obtained by dropping nullable symbols.
+    ///
+    /// # Returns
+    ///
+    /// A grammar without ε-productions, except `S -> ε` for the start symbol when the language
+    /// contains the empty word, generating the same language.
+    pub fn remove_epsilon_productions(&self) -> Grammar {
+        let nullable = self.nullable();
+        let mut grammar = self.clone();
+        grammar.productions.clear();
+        for p in self.productions.iter() {
+            let mut bodies: Vec<Vec<String>> = vec![Vec::new()];
+            for symbol in p.body.iter() {
+                let mut next = Vec::new();
+                for body in bodies {
+                    if nullable.contains(symbol) {
+                        next.push(body.clone());
+                    }
+                    let mut body = body;
+                    body.push(symbol.clone());
+                    next.push(body);
+                }
+                bodies = next;
+            }
+            for body in bodies {
+                if !body.is_empty() {
+                    grammar.add_production(p.head.clone(), body);
+                }
+            }
+        }
+        if nullable.contains(&self.start_symbol) {
+            grammar.add_production(self.start_symbol.clone(), Vec::new());
+        }
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Removes the unit productions `A -> B`, giving `A` the other productions of every
+    /// nonterminal it derives through unit productions.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar without unit productions.
+    pub fn remove_unit_productions(&self) -> Grammar {
+        let is_unit = |p: &Production| p.body.len() == 1 && self.is_nonterminal(&p.body[0]);
+        let mut grammar = self.clone();
+        grammar.productions.clear();
+        for head in self.nonterminals.iter() {
+            let mut reached = vec![head.clone()];
+            let mut i = 0;
+            while i < reached.len() {
+                for p in self.productions.iter() {
+                    if p.head == reached[i] && is_unit(p) && !reached.contains(&p.body[0]) {
+                        reached.push(p.body[0].clone());
+                    }
+                }
+                i += 1;
+            }
+            for p in self.productions.iter() {
+                if reached.contains(&p.head) && !is_unit(p) {
+                    grammar.add_production(head.clone(), p.body.clone());
+                }
+            }
+        }
+        grammar.sort_productions(self);
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Removes the useless symbols: first the nonterminals deriving no word, then the symbols
+    /// which cannot be reached from the start symbol.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose symbols all appear in the derivation of some word. When the
+    /// language is empty, the grammar has no productions.
+    pub fn remove_useless_symbols(&self) -> Grammar {
+        let mut generating: Vec<String> = Vec::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in self.productions.iter() {
+                if !generating.contains(&p.head)
+                    && p.body
+                        .iter()
+                        .all(|s| !self.is_nonterminal(s) || generating.contains(s))
+                {
+                    generating.push(p.head.clone());
+                    changed = true;
+                }
+            }
+        }
+        let productions: Vec<Production> = self
+            .productions
+            .iter()
+            .filter(|p| {
+                generating.contains(&p.head)
+                    && p.body
+                        .iter()
+                        .all(|s| !self.is_nonterminal(s) || generating.contains(s))
+            })
+            .cloned()
+            .collect();
+
+        let mut reachable = vec![self.start_symbol.clone()];
+        let mut i = 0;
+        while i < reachable.len() {
+            let head = reachable[i].clone();
+            for p in productions.iter().filter(|p| p.head == head) {
+                for s in p.body.iter() {
+                    if !reachable.contains(s) {
+                        reachable.push(s.clone());
+                    }
+                }
+            }
+            i += 1;
+        }
+        let mut grammar = self.clone();
+        grammar.productions = productions
+            .into_iter()
+            .filter(|p| reachable.contains(&p.head))
+            .collect();
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Replaces the terminals of the bodies with more than one symbol with new nonterminals
+    /// `T_a`, each having the single production `T_a -> a`.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose terminals only appear in bodies of one symbol.
+    pub fn replace_terminals(&self) -> Grammar {
+        let mut grammar = self.clone();
+        grammar.productions.clear();
+        let mut terminal_heads: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+        for p in self.productions.iter() {
+            if p.body.len() < 2 {
+                grammar.add_production(p.head.clone(), p.body.clone());
+                continue;
+            }
+            let mut body = Vec::new();
+            for symbol in p.body.iter() {
+                if self.is_nonterminal(symbol) {
+                    body.push(symbol.clone());
+                    continue;
+                }
+                let head = match terminal_heads.get(symbol) {
+                    Some(head) => head.clone(),
+                    None => {
+                        let head = grammar.fresh_nonterminal(&format!("T_{}", symbol));
+                        grammar.nonterminals.push(head.clone());
+                        terminal_heads.insert(symbol.clone(), head.clone());
+                        grammar.add_production(head.clone(), vec![symbol.clone()]);
+                        head
+                    }
+                };
+                body.push(head);
+            }
+            grammar.add_production(p.head.clone(), body);
+        }
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Splits the bodies longer than two symbols, `A -> X1 X2 ... Xn` becoming
+    /// `A -> X1 A_1`, `A_1 -> X2 A_2`, ..., `A_n-2 -> Xn-1 Xn`.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose bodies have at most two symbols.
+    pub fn split_long_bodies(&self) -> Grammar {
+        let mut grammar = self.clone();
+        grammar.productions.clear();
+        for p in self.productions.iter() {
+            let mut body = p.body.clone();
+            let mut head = p.head.clone();
+            while body.len() > 2 {
+                let rest = (1..)
+                    .map(|k| format!("{}_{}", p.head, k))
+                    .find(|name| grammar.fresh_nonterminal(name) == *name)
+                    .unwrap_or_default();
+                grammar.nonterminals.push(rest.clone());
+                grammar.add_production(head, vec![body.remove(0), rest.clone()]);
+                head = rest;
+            }
+            grammar.add_production(head, body);
+        }
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Removes left recursion with the standard ordering algorithm: for each nonterminal `Ai`,
+    /// the productions `Ai -> Aj γ` with `j < i` are expanded with the productions of `Aj`, then
+    /// the direct left recursion `Ai -> Ai α | β` is replaced by `Ai -> β | β Ai'` and
+    /// `Ai' -> α | α Ai'`.
+    ///
+    /// The grammar must have no ε-productions, except for a start symbol not appearing in any
+    /// body, and no cycles of unit productions, as it happens in Chomsky normal form.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar without left recursion.
+    pub fn remove_left_recursion(&self) -> Grammar {
+        let mut grammar = self.clone();
+        let order = self.nonterminals.clone();
+        for (i, head) in order.iter().enumerate() {
+            for earlier in order[..i].iter() {
+                let mut productions: Vec<Production> = Vec::new();
+                for p in grammar.productions.iter() {
+                    let expansions: Vec<Production> = if p.head == *head && p.body.first() == Some(earlier) {
+                        grammar
+                            .productions
+                            .iter()
+                            .filter(|q| q.head == *earlier)
+                            .map(|q| Production {
+                                head: head.clone(),
+                                body: q.body.iter().chain(p.body[1..].iter()).cloned().collect(),
+                            })
+                            .collect()
+                    } else {
+                        vec![p.clone()]
+                    };
+                    for expansion in expansions {
+                        if !productions.contains(&expansion) {
+                            productions.push(expansion);
+                        }
+                    }
+                }
+                grammar.productions = productions;
+            }
+
+            let (recursive, others): (Vec<Production>, Vec<Production>) = grammar
+                .productions
+                .iter()
+                .filter(|p| p.head == *head)
+                .cloned()
+                .partition(|p| p.body.first() == Some(head));
+            if recursive.is_empty() {
+                continue;
+            }
+            let tail = grammar.fresh_nonterminal(&format!("{}'", head));
+            grammar.nonterminals.push(tail.clone());
+            grammar.productions.retain(|p| p.head != *head);
+            for p in others {
+                grammar.add_production(head.clone(), p.body.clone());
+                grammar.add_production(head.clone(), [p.body, vec![tail.clone()]].concat());
+            }
+            for p in recursive {
+                let alpha = p.body[1..].to_vec();
+                grammar.add_production(tail.clone(), alpha.clone());
+                grammar.add_production(tail.clone(), [alpha, vec![tail.clone()]].concat());
+            }
+        }
+        grammar.sort_productions(&grammar.clone());
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Expands the bodies starting with a nonterminal with the productions of that nonterminal,
+    /// until every body starts with a terminal.
+    ///
+    /// The grammar must have no left recursion and no ε-productions, except for a start symbol
+    /// not appearing in any body.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose bodies all start with a terminal, except `S -> ε`.
+    pub fn substitute_leading_nonterminals(&self) -> Grammar {
+        let mut grammar = self.clone();
+        for head in self.nonterminals.iter().rev() {
+            while let Some(index) = grammar.productions.iter().position(|p| {
+                p.head == *head && p.body.first().is_some_and(|s| grammar.is_nonterminal(s))
+            }) {
+                let p = grammar.productions.remove(index);
+                let expansions: Vec<Production> = grammar
+                    .productions
+                    .iter()
+                    .filter(|q| q.head == p.body[0])
+                    .map(|q| Production {
+                        head: head.clone(),
+                        body: q.body.iter().chain(p.body[1..].iter()).cloned().collect(),
+                    })
+                    .collect();
+                let mut position = index;
+                for expansion in expansions {
+                    if !grammar.productions.contains(&expansion) {
+                        grammar.productions.insert(position, expansion);
+                        position += 1;
+                    }
+                }
+            }
+        }
+        grammar.update_symbols();
+        grammar
+    }
+
+    /// Converts the grammar into Chomsky normal form.
+    ///
+    /// # Returns
+    ///
+    /// An equivalent grammar whose productions are `A -> B C`, `A -> a`, or `S -> ε` for the start symbol.
+    pub fn to_cnf(&self) -> Grammar {
+        self.to_cnf_steps().0
+    }
+
+    /// Converts the grammar into Chomsky normal form, recording every phase.
+    ///
+    /// The phases are: adding a new start symbol, removing ε-productions, unit productions and
+    /// useless symbols, replacing the terminals of long bodies with new nonterminals `T_a`, and
+    /// splitting the bodies longer than two symbols with new nonterminals.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// - The grammar in Chomsky normal form
+    /// - The grammar before and after each phase, in the format "// phase" followed by the `cfg` file
+    pub fn to_cnf_steps(&self) -> (Grammar, Vec<String>) {
+        let mut steps = vec![format!("// grammar\n{}", self)];
+        let phases: [Phase; 6] = [
+            ("add a new start symbol", Grammar::add_new_start),
+            ("remove ε-productions", Grammar::remove_epsilon_productions),
+            ("remove unit productions", Grammar::remove_unit_productions),
+            ("remove useless symbols", Grammar::remove_useless_symbols),
+            ("replace terminals in long bodies", Grammar::replace_terminals),
+            ("split long bodies", Grammar::split_long_bodies),
+        ];
+        let mut grammar = self.clone();
+        for (name, phase) in phases {
+            grammar = phase(&grammar);
+            steps.push(format!("// {}\n{}", name, grammar));
+        }
+        (grammar, steps)
+    }
+
+    /// Converts the grammar into Greibach normal form, recording every phase.
+    ///
+    /// The grammar is converted into Chomsky normal form first; then left recursion is removed,
+    /// the leading nonterminals are expanded and the symbols left useless are removed.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// - The grammar in Greibach normal form, whose productions are `A -> a B1 ... Bk` with
+    ///   nonterminals `Bi`, or `S -> ε` for the start symbol
+    /// - The grammar before and after each phase, in the format "// phase" followed by the `cfg` file
+    pub fn to_gnf_steps(&self) -> (Grammar, Vec<String>) {
+        let (mut grammar, mut steps) = self.to_cnf_steps();
+        let phases: [Phase; 3] = [
+            ("remove left recursion", Grammar::remove_left_recursion),
+            (
+                "substitute leading nonterminals",
+                Grammar::substitute_leading_nonterminals,
+            ),
+            ("remove useless symbols", Grammar::remove_useless_symbols),
+        ];
+        for (name, phase) in phases {
+            grammar = phase(&grammar);
+            steps.push(format!("// {}\n{}", name, grammar));
+        }
+        (grammar, steps)
+    }
+
+    /// Checks whether the grammar is in Chomsky normal form.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if every production is `A -> B C` with `B` and `C` different from the start
+    /// symbol, `A -> a`, or `S -> ε` for the start symbol `S`, `false` otherwise.
+    pub fn is_cnf(&self) -> bool {
+        self.productions.iter().all(|p| match p.body.len() {
+            0 => p.head == self.start_symbol,
+            1 => !self.is_nonterminal(&p.body[0]),
+            2 => p
+                .body
+                .iter()
+                .all(|s| self.is_nonterminal(s) && *s != self.start_symbol),
+            _ => false,
+        })
+    }
+
+    /// Checks whether the grammar is in Greibach normal form.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if every production is `A -> a B1 ... Bk` with nonterminals `Bi` different
+    /// from the start symbol, or `S -> ε` for the start symbol `S`, `false` otherwise.
+    pub fn is_gnf(&self) -> bool {
+        self.productions.iter().all(|p| match p.body.split_first() {
+            None => p.head == self.start_symbol,
+            Some((first, rest)) => {
+                !self.is_nonterminal(first)
+                    && rest
+                        .iter()
+                        .all(|s| self.is_nonterminal(s) && *s != self.start_symbol)
+            }
+        })
+    }
+
+    /// Sorts the productions by head, following the order of the heads in another grammar.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - The grammar giving the order of the heads
+    fn sort_productions(&mut self, reference: &Grammar) {
+        let position = |head: &String| {
+            reference
+                .nonterminals
+                .iter()
+                .position(|s| s == head)
+                .unwrap_or(reference.nonterminals.len())
+        };
+        self.productions.sort_by_key(|p| position(&p.head));
+    }
+
+    /// Decides whether a word belongs to the language of the grammar with the CYK algorithm.
+    ///
+    /// The grammar is first converted into Chomsky normal form; then, for every span of the input
+    /// from the shortest to the longest, the nonterminals deriving it are computed from the
+    /// nonterminals deriving its two parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols of the word
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ParseTree)` - A parse tree of the word for the grammar in Chomsky normal form,
+    ///   if the word belongs to the language
+    /// * `None` - otherwise
+    pub fn cyk(&self, input: &[String]) -> Option<ParseTree> {
+        let cnf = self.to_cnf();
+        let leaf = |symbol: &str| ParseTree {
+            symbol: symbol.to_string(),
+            children: Vec::new(),
+        };
+        if input.is_empty() {
+            return cnf
+                .productions
+                .iter()
+                .any(|p| p.head == cnf.start_symbol && p.body.is_empty())
+                .then(|| ParseTree {
+                    symbol: cnf.start_symbol.clone(),
+                    children: vec![leaf("ε")],
+                });
+        }
+
+        // for each (start, length, nonterminal), the production used and the length of its first part
+        let mut table: std::collections::HashMap<(usize, usize, &str), (usize, usize)> =
+            std::collections::HashMap::new();
+        let n = input.len();
+        for length in 1..=n {
+            for (start, first) in input.iter().enumerate().take(n - length + 1) {
+                for (index, p) in cnf.productions.iter().enumerate() {
+                    if table.contains_key(&(start, length, p.head.as_str())) {
+                        continue;
+                    }
+                    let split = match p.body.len() {
+                        1 if length == 1 && p.body[0] == *first => Some(0),
+                        2 => (1..length).find(|&k| {
+                            table.contains_key(&(start, k, p.body[0].as_str()))
+                                && table.contains_key(&(start + k, length - k, p.body[1].as_str()))
+                        }),
+                        _ => None,
+                    };
+                    if let Some(split) = split {
+                        table.insert((start, length, p.head.as_str()), (index, split));
+                    }
+                }
+            }
+        }
+
+        fn build(
+            cnf: &Grammar,
+            table: &std::collections::HashMap<(usize, usize, &str), (usize, usize)>,
+            input: &[String],
+            (start, length, symbol): (usize, usize, &str),
+        ) -> ParseTree {
+            let (index, split) = table[&(start, length, symbol)];
+            let body = &cnf.productions[index].body;
+            let children = if split == 0 {
+                vec![ParseTree {
+                    symbol: input[start].clone(),
+                    children: Vec::new(),
+                }]
+            } else {
+                vec![
+                    build(cnf, table, input, (start, split, &body[0])),
+                    build(cnf, table, input, (start + split, length - split, &body[1])),
+                ]
+            };
+            ParseTree {
+                symbol: symbol.to_string(),
+                children,
+            }
+        }
+        table
+            .contains_key(&(0, n, cnf.start_symbol.as_str()))
+            .then(|| build(&cnf, &table, input, (0, n, &cnf.start_symbol)))
+    }
+
+    /// Decides whether a word belongs to the language of the grammar with the Earley algorithm.
+    ///
+    /// The algorithm builds one set of items for each position of the input by prediction,
+    /// scanning and completion; nullable nonterminals are skipped while predicting, so that
+    /// ε-productions are handled correctly. The parse tree is then rebuilt from the sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols of the word
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ParseTree)` - A parse tree of the word, if it belongs to the language
+    /// * `None` - otherwise
+    pub fn earley(&self, input: &[String]) -> Option<ParseTree> {
+        let sets = self.earley_sets(input);
+        let n = input.len();
+        let mut visiting = Vec::new();
+        self.earley_tree(&sets, input, &self.start_symbol, 0, n, &mut visiting)
+    }
+
+    /// Builds the sets of items of the Earley algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The symbols of the word
+    ///
+    /// # Returns
+    ///
+    /// One set of items for each position of the input, the end included.
+    fn earley_sets(&self, input: &[String]) -> Vec<Vec<EarleyItem>> {
+        let nullable = self.nullable();
+        let n = input.len();
+        let mut sets: Vec<Vec<EarleyItem>> = vec![Vec::new(); n + 1];
+        let add = |set: &mut Vec<EarleyItem>, item: EarleyItem| {
+            if !set.contains(&item) {
+                set.push(item);
+            }
+        };
+        for (index, p) in self.productions.iter().enumerate() {
+            if p.head == self.start_symbol {
+                add(&mut sets[0], (index, 0, 0));
+            }
+        }
+        for j in 0..=n {
+            let mut i = 0;
+            while i < sets[j].len() {
+                let (index, dot, origin) = sets[j][i];
+                let body = &self.productions[index].body;
+                if let Some(symbol) = body.get(dot) {
+                    if self.is_nonterminal(symbol) {
+                        for (next, p) in self.productions.iter().enumerate() {
+                            if p.head == *symbol {
+                                add(&mut sets[j], (next, 0, j));
+                            }
+                        }
+                        if nullable.contains(symbol) {
+                            add(&mut sets[j], (index, dot + 1, origin));
+                        }
+                    } else if j < n && input[j] == *symbol {
+                        add(&mut sets[j + 1], (index, dot + 1, origin));
+                    }
+                } else {
+                    let head = &self.productions[index].head;
+                    let waiting: Vec<EarleyItem> = sets[origin]
+                        .iter()
+                        .filter(|(w, d, _)| self.productions[*w].body.get(*d) == Some(head))
+                        .cloned()
+                        .collect();
+                    for (w, d, o) in waiting {
+                        add(&mut sets[j], (w, d + 1, o));
+                    }
+                }
+                i += 1;
+            }
+        }
+        sets
+    }
+
+    /// Rebuilds a parse tree of a nonterminal for a span of the input from the Earley sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `sets` - The sets of items built by `earley_sets`
+    /// * `input` - The symbols of the word
+    /// * `symbol` - The nonterminal at the root of the tree
+    /// * `start` - The position where the span starts
+    /// * `end` - The position where the span ends
+    /// * `visiting` - The nonterminals and spans being rebuilt, to avoid cycles of unit or
+    ///   ε-productions
+    ///
+    /// # Returns
+    ///
+    /// A parse tree of the span rooted in `symbol`, or `None` if there is none.
+    fn earley_tree(
+        &self,
+        sets: &[Vec<EarleyItem>],
+        input: &[String],
+        symbol: &str,
+        start: usize,
+        end: usize,
+        visiting: &mut Vec<(String, usize, usize)>,
+    ) -> Option<ParseTree> {
+        let key = (symbol.to_string(), start, end);
+        if visiting.contains(&key) {
+            return None;
+        }
+        visiting.push(key);
+        let mut tree = None;
+        for (index, p) in self.productions.iter().enumerate() {
+            if p.head != symbol || !sets[end].contains(&(index, p.body.len(), start)) {
+                continue;
+            }
+            if let Some(mut children) =
+                self.earley_children(sets, input, index, p.body.len(), start, end, visiting)
+            {
+                if children.is_empty() {
+                    children.push(ParseTree {
+                        symbol: "ε".to_string(),
+                        children: Vec::new(),
+                    });
+                }
+                tree = Some(ParseTree {
+                    symbol: symbol.to_string(),
+                    children,
+                });
+                break;
+            }
+        }
+        visiting.pop();
+        tree
+    }
+
+    /// Rebuilds the subtrees of the first symbols of a production body for a span of the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `sets` - The sets of items built by `earley_sets`
+    /// * `input` - The symbols of the word
+    /// * `index` - The index of the production
+    /// * `dot` - The number of symbols of the body to rebuild
+    /// * `start` - The position where the span starts
+    /// * `end` - The position where the span ends
+    /// * `visiting` - The nonterminals and spans being rebuilt
+    ///
+    /// # Returns
+    ///
+    /// The subtrees of the first `dot` symbols of the body, or `None` if they cannot span the input.
+    #[allow(clippy::too_many_arguments)]
+    fn earley_children(
+        &self,
+        sets: &[Vec<EarleyItem>],
+        input: &[String],
+        index: usize,
+        dot: usize,
+        start: usize,
+        end: usize,
+        visiting: &mut Vec<(String, usize, usize)>,
+    ) -> Option<Vec<ParseTree>> {
+        if dot == 0 {
+            return (start == end).then(Vec::new);
+        }
+        let symbol = &self.productions[index].body[dot - 1];
+        if !self.is_nonterminal(symbol) {
+            if end == start || input[end - 1] != *symbol || !sets[end - 1].contains(&(index, dot - 1, start)) {
+                return None;
+            }
+            let mut children = self.earley_children(sets, input, index, dot - 1, start, end - 1, visiting)?;
+            children.push(ParseTree {
+                symbol: symbol.clone(),
+                children: Vec::new(),
+            });
+            return Some(children);
+        }
+        for middle in (start..=end).rev() {
+            if !sets[middle].contains(&(index, dot - 1, start)) {
+                continue;
+            }
+            let Some(last) = self.earley_tree(sets, input, symbol, middle, end, visiting) else {
+                continue;
+            };
+            if let Some(mut children) =
+                self.earley_children(sets, input, index, dot - 1, start, middle, visiting)
+            {
+                children.push(last);
+                return Some(children);
+            }
+        }
+        None
+    }
+
+    /// Simulates the grammar on the given input, deciding membership with the Earley algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of strings representing the input symbols
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing either:
+    /// - Ok with a `SimulationResult` containing:
+    ///   - The outcome, `Accept` or `Reject`
+    ///   - The head, after the last symbol read
+    ///   - The input, unchanged
+    ///   - The number of steps, one for each symbol read
+    ///   - The parse tree for accepted words, one event for each node, from the root
+    /// - Err with an error message if simulation fails
+    pub fn simulate(&self, input: Vec<String>) -> Result<computer::SimulationResult, String> {
+        let steps = input.len();
+        match self.earley(&input) {
+            Some(tree) => {
+                let mut computation = Vec::new();
+                let mut nodes = vec![(0, &tree)];
+                while let Some((level, node)) = nodes.pop() {
+                    computation.push(trace::TraceEvent::Cfg {
+                        depth: 0,
+                        level,
+                        symbol: node.symbol.clone(),
+                    });
+                    nodes.extend(node.children.iter().rev().map(|child| (level + 1, child)));
+                }
+                Ok(computer::SimulationResult {
+                    outcome: computer::Outcome::Accept,
+                    head: steps,
+                    tape: input,
+                    steps,
+                    computation,
+                })
+            }
+            None => Ok(computer::SimulationResult {
+                outcome: computer::Outcome::Reject,
+                head: steps,
+                tape: input,
+                steps,
+                computation: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Grammar {
+    /// Formats the grammar as a `cfg` file, with one line for each head.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cfg")?;
+        for head in self.nonterminals.iter() {
+            let bodies: Vec<String> = self
+                .productions
+                .iter()
+                .filter(|p| p.head == *head)
+                .map(|p| {
+                    if p.body.is_empty() {
+                        "ε".to_string()
+                    } else {
+                        p.body.join(" ")
+                    }
+                })
+                .collect();
+            if !bodies.is_empty() {
+                write!(f, "\n{} -> {}", head, bodies.join(" | "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ParseTree {
+    /// Formats the tree with one node per line, the children indented by four spaces.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_node(
+            f: &mut std::fmt::Formatter<'_>,
+            node: &ParseTree,
+            indent: usize,
+        ) -> std::fmt::Result {
+            if indent > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}{}", " ".repeat(indent), node.symbol)?;
+            for child in node.children.iter() {
+                write_node(f, child, indent + 4)?;
+            }
+            Ok(())
+        }
+        write_node(f, self, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grammar(lines: &[&str]) -> Grammar {
+        Grammar::from_lines(&lines.iter().map(|l| l.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    fn word(s: &str) -> Vec<String> {
+        s.chars().map(|c| c.to_string()).collect()
+    }
+
+    fn anbn() -> Grammar {
+        grammar(&["S -> a S b | ε"])
+    }
+
+    fn expressions() -> Grammar {
+        grammar(&["E -> E + T | T", "T -> T * F | F", "F -> ( E )", "  | x"])
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let g = expressions();
+        assert_eq!(g.start_symbol, "E");
+        assert_eq!(g.nonterminals, vec!["E", "T", "F"]);
+        assert_eq!(g.terminals, vec!["+", "*", "(", ")", "x"]);
+        assert_eq!(g.productions.len(), 6);
+        assert_eq!(anbn().productions[1].body, Vec::<String>::new());
+        assert!(Grammar::from_lines(&["S a b".to_string()]).is_err());
+        assert!(Grammar::from_lines(&["| a".to_string()]).is_err());
+        assert!(Grammar::from_lines(&[]).is_err());
+    }
+
+    #[test]
+    fn test_to_cnf() {
+        for g in [anbn(), expressions(), grammar(&["S -> A B", "A -> a A | ε", "B -> b | A", "C -> c"])] {
+            let cnf = g.to_cnf();
+            assert!(cnf.is_cnf(), "{}", cnf);
+            assert!(!cnf.nonterminals.contains(&"C".to_string()));
+        }
+        let cnf = anbn().to_cnf();
+        assert_eq!(cnf.start_symbol, "S0");
+        assert!(cnf.productions.contains(&Production {
+            head: "S0".to_string(),
+            body: Vec::new()
+        }));
+    }
+
+    #[test]
+    fn test_remove_left_recursion() {
+        let g = expressions().remove_left_recursion();
+        assert!(g
+            .productions
+            .iter()
+            .all(|p| p.body.first() != Some(&p.head)));
+        assert!(g.nonterminals.contains(&"E'".to_string()));
+        assert!(g.productions.contains(&Production {
+            head: "E'".to_string(),
+            body: vec!["+".to_string(), "T".to_string(), "E'".to_string()]
+        }));
+    }
+
+    #[test]
+    fn test_to_gnf() {
+        for g in [anbn(), expressions(), grammar(&["S -> S S | ( S ) | ( )"])] {
+            let gnf = g.to_gnf_steps().0;
+            assert!(gnf.is_gnf(), "{}", gnf);
+            for w in ["", "ab", "aabb", "x+x*x", "(x)", "()(())", "(()"] {
+                assert_eq!(
+                    gnf.earley(&word(w)).is_some(),
+                    g.earley(&word(w)).is_some(),
+                    "{}",
+                    w
+                );
+            }
+        }
+        assert!(!expressions().is_gnf());
+    }
+
+    #[test]
+    fn test_steps() {
+        let (cnf, steps) = expressions().to_cnf_steps();
+        assert_eq!(steps.len(), 7);
+        assert!(steps[0].starts_with("// grammar\ncfg\nE -> E + T | T"));
+        assert!(steps[2].starts_with("// remove ε-productions\ncfg"));
+        assert!(steps[6].ends_with(&cnf.to_string()));
+        let (gnf, steps) = expressions().to_gnf_steps();
+        assert_eq!(steps.len(), 10);
+        assert!(steps[7].starts_with("// remove left recursion"));
+        assert_eq!(steps[9], format!("// remove useless symbols\n{}", gnf));
+        let lines: Vec<String> = steps[9].lines().skip(2).map(String::from).collect();
+        assert!(Grammar::from_lines(&lines).unwrap().is_gnf());
+    }
+
+    #[test]
+    fn test_cyk_and_earley() {
+        for (g, accepted, rejected) in [
+            (anbn(), vec!["", "ab", "aaabbb"], vec!["a", "ba", "aabbb"]),
+            (expressions(), vec!["x", "x+x*x", "(x+x)*x"], vec!["", "x+", "(x", "xx"]),
+        ] {
+            for w in accepted {
+                assert!(g.cyk(&word(w)).is_some(), "{}", w);
+                assert!(g.earley(&word(w)).is_some(), "{}", w);
+            }
+            for w in rejected {
+                assert!(g.cyk(&word(w)).is_none(), "{}", w);
+                assert!(g.earley(&word(w)).is_none(), "{}", w);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let tree = anbn().earley(&word("ab")).unwrap();
+        assert_eq!(tree.to_string(), "S\n    a\n    S\n        ε\n    b");
+        let tree = expressions().earley(&word("x+x")).unwrap();
+        assert_eq!(tree.children.len(), 3);
+        assert_eq!(tree.children[0].children[0].symbol, "T");
+        let tree = anbn().cyk(&word("ab")).unwrap();
+        assert_eq!(tree.to_string(), "S0\n    T_a\n        a\n    T_b\n        b");
+        let cyclic = grammar(&["S -> S | A", "A -> a | ε"]);
+        assert!(cyclic.earley(&word("a")).is_some());
+        assert!(cyclic.earley(&word("")).is_some());
+    }
+
+    #[test]
+    fn test_simulate() {
+        let computer::SimulationResult {
+            outcome: state,
+            tape,
+            steps,
+            computation,
+            ..
+        } = anbn().simulate(word("ab")).unwrap();
+        assert_eq!(state, computer::Outcome::Accept);
+        assert_eq!(tape, word("ab"));
+        assert_eq!(steps, 2);
+        assert_eq!(computation[0].to_string(), "cfg;S");
+        assert_eq!(anbn().simulate(word("b")).unwrap().outcome, computer::Outcome::Reject);
+    }
+
+    #[test]
+    fn test_display() {
+        let g = expressions();
+        assert_eq!(
+            g.to_string(),
+            "cfg\nE -> E + T | T\nT -> T * F | F\nF -> ( E ) | x"
+        );
+        let lines: Vec<String> = g.to_string().lines().skip(1).map(String::from).collect();
+        assert_eq!(Grammar::from_lines(&lines).unwrap(), g);
+    }
+}
@@ -45,6 +45,7 @@
 use std::ops::Deref;
 
 use crate::computer;
+use crate::trace;
 
 #[derive(Debug, Clone)]
 pub enum LambdaExpr {
@@ -269,9 +270,9 @@ impl Lambda {
     ///
     /// # Returns
     /// - `Ok(SimulationResult)` containing:
-    ///   - The final reduced expression as a string
-    ///   - Number of registers used (always 0 for lambda calculus)
-    ///   - Vector of memory operations (empty for lambda calculus)
+    ///   - `Halt` if the normal form was reached, `Timeout` otherwise
+    ///   - The head position (always 0 for lambda calculus)
+    ///   - The final reduced expression, as the only element of the tape
     ///   - Number of reduction steps performed
     ///   - Vector of intermediate expressions showing the reduction process
     /// - `Err(String)` if the simulation fails
@@ -279,14 +280,20 @@ impl Lambda {
         let mut computation = Vec::new();
         self.substitute_names();
         let mut result = self.clone();
-        computation.push(result.to_string());
+        computation.push(trace::TraceEvent::Lambda {
+            depth: 0,
+            expression: result.to_string(),
+        });
         let mut new_result = Lambda {
             expr: beta_reduction(&self.clone().expr),
             references: self.references.clone(),
             name: self.name.clone(),
             force_currying: self.force_currying,
         };
-        computation.push(new_result.to_string());
+        computation.push(trace::TraceEvent::Lambda {
+            depth: 0,
+            expression: new_result.to_string(),
+        });
         let mut steps = 1;
         while result != new_result.clone() && steps < max_steps {
             result = new_result.clone();
@@ -297,10 +304,24 @@ impl Lambda {
                 force_currying: self.force_currying,
             };
             steps += 1;
-            computation.push(new_result.to_string());
+            computation.push(trace::TraceEvent::Lambda {
+                depth: 0,
+                expression: new_result.to_string(),
+            });
         }
+        let outcome = if result == new_result {
+            computer::Outcome::Halt
+        } else {
+            computer::Outcome::Timeout
+        };
         new_result.force_currying = true;
-        Ok((new_result.to_string(), 0, Vec::new(), steps, computation))
+        Ok(computer::SimulationResult {
+            outcome,
+            head: 0,
+            tape: vec![new_result.to_string()],
+            steps,
+            computation,
+        })
     }
 
     /// Converts the lambda expression into a vector of tokens.
@@ -799,14 +820,22 @@ mod tests {
         // 1. If max_steps is large, it should terminate early (at 4 steps)
         let result1 = lambda.simulate(10);
         assert!(result1.is_ok());
-        let (_, _, _, steps1, computation1) = result1.unwrap();
+        let computer::SimulationResult {
+            steps: steps1,
+            computation: computation1,
+            ..
+        } = result1.unwrap();
         assert_eq!(steps1, 4);
         assert_eq!(computation1.len(), 5); // 1 initial + 4 steps
 
         // 2. If max_steps is smaller than required, it should terminate at max_steps
         let result2 = lambda.simulate(2);
         assert!(result2.is_ok());
-        let (_, _, _, steps2, computation2) = result2.unwrap();
+        let computer::SimulationResult {
+            steps: steps2,
+            computation: computation2,
+            ..
+        } = result2.unwrap();
         assert_eq!(steps2, 2);
         assert_eq!(computation2.len(), 3); // 1 initial + 2 steps
     }
@@ -0,0 +1,92 @@
+//! # Computing Simulator - Library
+//!
+//! This crate exposes the models of computation of the Computing Simulator, their parsers,
+//! converters and simulators, so that other tools can use them programmatically. The
+//! command-line interface is a thin binary on top of it.
+//!
+//! ## Modules
+//! - `busy_beaver`: Enumeration of busy beaver candidates in tree normal form.
+//! - `compiled_machine`: Fast simulation engine for deterministic Turing machines.
+//! - `computer`: Core computer simulation logic.
+//! - `debugger`: Step-by-step debugging with breakpoints, watchpoints and reverse stepping.
+//! - `file_handler`: Utilities for file input/output.
+//! - `finite_automaton`: Finite automata (DFA, NFA and ε-NFA) simulation.
+//! - `grammar`: Context-free grammars with CYK and Earley parsing.
+//! - `lambda`: Lambda calculus interpreter and related structures.
+//! - `loop_detector`: Proofs that deterministic computations never halt.
+//! - `macro_machine`: Accelerated simulation of long-running Turing machines over blocks of cells.
+//! - `pushdown_automaton`: Pushdown automata and their conversions to and from grammars.
+//! - `ram_machine`: RAM machine simulation.
+//! - `regex`: Regular expression utilities and simulation.
+//! - `trace`: Events recorded at every step of a computation, and their renderers.
+//! - `turing_machine`: Turing machine simulation.
+//! - `utils`: Miscellaneous utility functions.
+//!
+//! The command-line interface is kept internal: `cli` handles the user interaction, `options`
+//! parses the command-line options and `visualizer` draws running computations full screen. Only
+//! `main_cli`, which runs the interface, and `Options`, which the conversions of
+//! `computer::Computer` take, are exported.
+//!
+//! ## Usage
+//! Machines can be read from files with `file_handler::handle_file_reads`, or constructed in
+//! code with the builders of their modules, such as `turing_machine::TuringMachineBuilder`,
+//! `ram_machine::RamMachineBuilder`, `finite_automaton::FiniteAutomatonBuilder` and
+//! `pushdown_automaton::PushdownAutomatonBuilder`. A `computer::Server` then runs them:
+//!
+//! ```
+//! use computing_simulator::computer::{Computer, Outcome, Server};
+//! use computing_simulator::turing_machine::{Direction, TuringMachine};
+//!
+//! // replaces every 0 with a 1, then halts on the first blank
+//! let tm = TuringMachine::builder()
+//!     .initial_state("q0")
+//!     .halt_state("qh")
+//!     .input_alphabet(&["0", "1"])
+//!     .transition("q0", &["_"], "q1", &["_"], &[Direction::Right])
+//!     .transition("q1", &["0"], "q1", &["1"], &[Direction::Right])
+//!     .transition("q1", &["1"], "q1", &["1"], &[Direction::Right])
+//!     .transition("q1", &["_"], "qh", &["_"], &[Direction::Stay])
+//!     .build()
+//!     .unwrap();
+//!
+//! let mut computer = Computer::new();
+//! computer.set_turing(tm);
+//! let mut server = Server::new();
+//! server.add_computer("main".to_string(), computer);
+//! server.set_computation_order_at(0, "main".to_string());
+//!
+//! let result = server.execute("010", 1000).unwrap();
+//! assert_eq!(result.outcome, Outcome::Halt);
+//! assert_eq!(result.output(), "111");
+//! ```
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+pub mod busy_beaver;
+mod cli;
+pub mod compiled_machine;
+pub mod computer;
+pub mod debugger;
+pub mod file_handler;
+pub mod finite_automaton;
+pub mod grammar;
+pub mod lambda;
+pub mod loop_detector;
+pub mod macro_machine;
+mod options;
+pub mod pushdown_automaton;
+pub mod ram_machine;
+pub mod regex;
+pub mod trace;
+pub mod turing_machine;
+pub mod utils;
+mod visualizer;
+
+pub use cli::main_cli;
+pub use options::Options;
@@ -0,0 +1,286 @@
+//! # Loop Detector Module
+//!
+//! This module provides detectors proving that a deterministic computation never halts, so that
+//! a run can report why it diverges instead of running until the maximum number of steps.
+//!
+//! ## Main Structures
+//!
+//! - **CycleDetector**: Detects a configuration repeating itself, for any kind of machine whose
+//!   configurations can be compared.
+//! - **TranslationDetector**: Detects the translated cycles (Lin recurrences) of single-tape
+//!   Turing machines, and the machines writing the blank part of their tape forever.
+//!
+//! ## Key Features
+//!
+//! - **Repeated Configurations**: Configurations are compared with a reference one, which is
+//!   replaced after a number of steps doubling every time (Brent's algorithm), so that a cycle is
+//!   found within a few periods of its start with a single stored configuration.
+//! - **Translated Cycles**: A single-tape machine reaching the farthest cell of one side of its
+//!   tape twice in the same state, with the cells it visited in between matching once shifted,
+//!   repeats the same moves shifted forever.
+//! - **Tape Growth**: A single-tape machine on the blank part of its tape whose transitions on blank
+//!   symbols keep moving in the same direction, until one of their states repeats, moves away
+//!   forever.
+//!
+//! Every detector returns its proof as a message, such as "cycle of period 4 from step 1", with
+//! the steps counted as the moves made since the start of the computation.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::compiled_machine;
+use std::collections::VecDeque;
+
+/// Detects the repetition of a configuration of a deterministic machine.
+///
+/// # Fields
+/// * `reference` - The configuration compared with the next ones, with the step it was reached
+/// * `power` - The number of steps after which the reference is replaced
+///
+#[derive(Clone, Debug)]
+pub struct CycleDetector<C> {
+    reference: Option<(C, usize)>,
+    power: usize,
+}
+
+impl<C: Clone + PartialEq> Default for CycleDetector<C> {
+    fn default() -> Self {
+        CycleDetector::new()
+    }
+}
+
+impl<C: Clone + PartialEq> CycleDetector<C> {
+    /// Creates a new detector without a reference configuration.
+    pub fn new() -> CycleDetector<C> {
+        CycleDetector {
+            reference: None,
+            power: 1,
+        }
+    }
+
+    /// Observes the configuration reached after a number of steps, which must increase by one
+    /// at every call.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The proof that the computation diverges, if the configuration repeats
+    ///   the reference one
+    /// * `None` - Otherwise
+    pub fn observe(&mut self, step: usize, configuration: &C) -> Option<String> {
+        match &self.reference {
+            Some((reference, start)) if reference == configuration => {
+                return Some(format!(
+                    "cycle of period {} from step {}",
+                    step - start,
+                    start
+                ));
+            }
+            Some((_, start)) if step - start < self.power => return None,
+            Some(_) => self.power *= 2,
+            None => {}
+        }
+        self.reference = Some((configuration.clone(), step));
+        None
+    }
+}
+
+/// The farthest cells reached on one side of the tape by a single-tape machine.
+///
+/// # Fields
+/// * `direction` - 1 for the right side, -1 for the left side
+/// * `extreme` - The farthest position reached on this side
+/// * `reference` - The state, position, step, tape and origin of the reference record
+/// * `back` - The position nearest to the other side reached since the reference record
+/// * `count` - The records reached since the reference record
+/// * `power` - The number of records after which the reference is replaced
+///
+#[derive(Clone, Debug)]
+struct Records {
+    direction: i64,
+    extreme: i64,
+    reference: Option<(u32, i64, usize, VecDeque<u32>, i64)>,
+    back: i64,
+    count: usize,
+    power: usize,
+}
+
+/// Detects the translated cycles and the tape growth of a compiled single-tape Turing machine.
+///
+/// Positions are absolute: the cell of index `i` of a tape whose first `origin` cells were added
+/// to its front is at position `i - origin`.
+///
+/// # Fields
+/// * `machine` - The compiled machine, whose transitions are followed to prove tape growth
+/// * `tape_end` - The position of the last cell of the initial tape
+/// * `sides` - The records of the left and of the right side of the tape
+///
+#[derive(Clone, Debug)]
+pub struct TranslationDetector<'a> {
+    machine: &'a compiled_machine::CompiledMachine,
+    tape_end: i64,
+    sides: [Records; 2],
+}
+
+/// Returns the symbol at a position of a tape, blank outside of it.
+fn cell(tape: &VecDeque<u32>, origin: i64, position: i64, blank: u32) -> u32 {
+    usize::try_from(position + origin)
+        .ok()
+        .and_then(|index| tape.get(index).copied())
+        .unwrap_or(blank)
+}
+
+impl<'a> TranslationDetector<'a> {
+    /// Creates a new detector for a machine starting with its head at `head` on a tape of
+    /// `tape_len` cells.
+    pub fn new(
+        machine: &'a compiled_machine::CompiledMachine,
+        tape_len: usize,
+        head: usize,
+    ) -> TranslationDetector<'a> {
+        let records = |direction| Records {
+            direction,
+            extreme: head as i64,
+            reference: None,
+            back: head as i64,
+            count: 0,
+            power: 1,
+        };
+        TranslationDetector {
+            machine,
+            tape_end: tape_len as i64 - 1,
+            sides: [records(-1), records(1)],
+        }
+    }
+
+    /// Checks whether the transitions on blank symbols from a state keep moving in a direction
+    /// until one of their states repeats.
+    fn grows(&self, mut state: u32, direction: i64) -> bool {
+        let mut seen = vec![state];
+        loop {
+            if self.machine.final_states[state as usize] {
+                return false;
+            }
+            let entry = self.machine.entry(state, &[self.machine.blank_symbol]);
+            state = self.machine.next_states[entry];
+            if state == compiled_machine::NO_TRANSITION
+                || self.machine.moves[entry] as i64 != direction
+            {
+                return false;
+            }
+            if seen.contains(&state) {
+                return true;
+            }
+            seen.push(state);
+        }
+    }
+
+    /// Observes the configuration reached after a number of steps, which must increase by one
+    /// at every call.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The moves made since the start of the computation
+    /// * `state` - The current state
+    /// * `position` - The absolute position of the head
+    /// * `tape` - The cells of the tape
+    /// * `origin` - The number of cells added to the front of the tape
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The proof that the computation diverges, if any
+    /// * `None` - Otherwise
+    pub fn observe(
+        &mut self,
+        step: usize,
+        state: u32,
+        position: i64,
+        tape: &VecDeque<u32>,
+        origin: i64,
+    ) -> Option<String> {
+        let blank = self.machine.blank_symbol;
+        for side in 0..2 {
+            let records = &mut self.sides[side];
+            let direction = records.direction;
+            if (position - records.back) * direction < 0 {
+                records.back = position;
+            }
+            // only the records past the initial tape have blank cells beyond them
+            let beyond = if direction > 0 {
+                position >= self.tape_end
+            } else {
+                position <= 0
+            };
+            if (position - records.extreme) * direction <= 0 {
+                continue;
+            }
+            records.extreme = position;
+            if !beyond {
+                continue;
+            }
+            if cell(tape, origin, position, blank) == blank && self.grows(state, direction) {
+                return Some(format!("tape grows forever from step {}", step));
+            }
+            let records = &mut self.sides[side];
+            if let Some((
+                reference_state,
+                reference_position,
+                start,
+                reference_tape,
+                reference_origin,
+            )) = &records.reference
+            {
+                let shift = position - *reference_position;
+                let low = records.back.min(*reference_position);
+                let high = records.back.max(*reference_position);
+                if *reference_state == state
+                    && (low..=high).all(|p| {
+                        cell(reference_tape, *reference_origin, p, blank)
+                            == cell(tape, origin, p + shift, blank)
+                    })
+                {
+                    return Some(format!(
+                        "translated cycle of period {} and shift {} from step {}",
+                        step - start,
+                        shift,
+                        start
+                    ));
+                }
+            }
+            records.count += 1;
+            if records.reference.is_none() || records.count == records.power {
+                if records.reference.is_some() {
+                    records.power *= 2;
+                }
+                records.reference = Some((state, position, step, tape.clone(), origin));
+                records.back = position;
+                records.count = 0;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_detector() {
+        // 0, 1, 2, 3, 4, 5, 6, 3, 4, 5, 6, ...
+        let mut detector = CycleDetector::new();
+        let proof = (0..100)
+            .map(|step| if step < 3 { step } else { 3 + (step - 3) % 4 })
+            .enumerate()
+            .find_map(|(step, value)| detector.observe(step, &value))
+            .unwrap();
+        assert!(proof.starts_with("cycle of period 4 from step "));
+
+        let mut detector = CycleDetector::new();
+        assert!((0..1000).all(|step| detector.observe(step, &step).is_none()));
+    }
+}
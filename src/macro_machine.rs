@@ -0,0 +1,628 @@
+//! # Macro Machine Module
+//!
+//! This module provides an accelerated simulator for deterministic single-tape Turing machines,
+//! meant for machines running for far more steps than a step-by-step engine can reach, such as
+//! the candidates of busy beaver experiments.
+//!
+//! ## Main Structures
+//!
+//! - **MacroMachine**: A compiled machine whose tape is grouped into blocks of cells and stored
+//!   run-length encoded, together with a cache of the behavior of the machine inside each block.
+//!
+//! ## Key Features
+//!
+//! - **Block Transitions**: The machine is simulated one block at a time, from the cell where the
+//!   head enters a block to the one where it leaves it; the result is cached, so that a block seen
+//!   before in the same state costs a single lookup.
+//! - **Run Skipping**: When the machine crosses a block and enters the next one in the same state
+//!   and from the same side, and the next blocks are equal to the crossed one, the whole run is
+//!   crossed at once, however long it is.
+//! - **Exact Results**: The final state, head position, tape and step count are the ones of the
+//!   plain simulation, including when the maximum number of steps is reached in the middle of a
+//!   block or of a run.
+//!
+//! The computation history is not recorded, since the configurations skipped are never built.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::compiled_machine;
+use crate::computer;
+use crate::turing_machine;
+use std::collections::HashMap;
+
+/// The contents of a block of cells, as symbol numbers of the compiled machine.
+type Block = Vec<u32>;
+
+/// The side of a block from which the head leaves it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// The behavior of the machine from the cell where its head enters a block.
+///
+/// # Variants
+/// * `Exit` - The head leaves the block after `steps` moves, in `state`, leaving `block` behind
+/// * `Stop` - The machine reaches a final state or a configuration without transitions after
+///   `steps` moves, with its head at `position`
+/// * `Loop` - The machine never leaves the block nor stops
+///
+/// `low` and `high` are the first and last positions of the block visited by the head.
+#[derive(Clone, Debug)]
+enum BlockResult {
+    Exit {
+        side: Side,
+        state: u32,
+        block: Block,
+        steps: usize,
+        low: usize,
+        high: usize,
+    },
+    Stop {
+        state: u32,
+        position: usize,
+        block: Block,
+        steps: usize,
+        low: usize,
+        high: usize,
+    },
+    Loop,
+}
+
+/// A deterministic single-tape Turing machine simulated over blocks of cells.
+///
+/// # Fields
+/// * `machine` - The compiled machine whose transitions are applied inside the blocks
+/// * `block_size` - The number of cells of each block
+/// * `cache` - The behavior of the machine in each state, block and entry position met so far
+///
+#[derive(Clone, Debug)]
+pub struct MacroMachine {
+    pub machine: compiled_machine::CompiledMachine,
+    pub block_size: usize,
+    cache: HashMap<(u32, Block, usize), BlockResult>,
+}
+
+/// A half of the tape, run-length encoded, with the block nearest to the head on top.
+///
+/// Past its last run the half is made of infinitely many blank blocks.
+struct HalfTape {
+    runs: Vec<(Block, usize)>,
+}
+
+impl HalfTape {
+    /// Pushes `count` copies of a block next to the head.
+    fn push(&mut self, block: Block, count: usize) {
+        match self.runs.last_mut() {
+            Some((top, top_count)) if *top == block => *top_count += count,
+            _ => self.runs.push((block, count)),
+        }
+    }
+
+    /// Removes the block next to the head and returns it.
+    fn pop(&mut self, blank: &Block) -> Block {
+        match self.runs.last_mut() {
+            None => blank.clone(),
+            Some((block, 1)) => {
+                let block = block.clone();
+                self.runs.pop();
+                block
+            }
+            Some((block, count)) => {
+                *count -= 1;
+                block.clone()
+            }
+        }
+    }
+
+    /// Returns how many blocks next to the head are equal to `block`, `usize::MAX` for the
+    /// infinitely many blank blocks past the last run.
+    fn run_of(&self, block: &Block, blank: &Block) -> usize {
+        match self.runs.last() {
+            None if block == blank => usize::MAX,
+            Some((top, count)) if top == block => *count,
+            _ => 0,
+        }
+    }
+
+    /// Removes `count` blocks next to the head, which must be equal to each other.
+    fn skip(&mut self, count: usize) {
+        if let Some((_, top_count)) = self.runs.last_mut() {
+            *top_count -= count;
+            if *top_count == 0 {
+                self.runs.pop();
+            }
+        }
+    }
+}
+
+impl MacroMachine {
+    /// Builds the macro machine of a deterministic single-tape Turing machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `tm` - The machine to simulate, assumed to be deterministic
+    /// * `block_size` - The number of cells of each block, at least 1
+    /// * `input` - The symbols which will be on the tape
+    ///
+    /// # Returns
+    ///
+    /// * `Some(MacroMachine)` - The macro machine
+    /// * `None` - If the machine has more than one tape, the block size is 0 or the machine cannot
+    ///   be compiled
+    pub fn new(
+        tm: &turing_machine::TuringMachine,
+        block_size: usize,
+        input: &[String],
+    ) -> Option<MacroMachine> {
+        if tm.tape_count != 1 || block_size == 0 {
+            return None;
+        }
+        Some(MacroMachine {
+            machine: compiled_machine::CompiledMachine::new(tm, input)?,
+            block_size,
+            cache: HashMap::new(),
+        })
+    }
+
+    /// Applies the transition of a state reading the cell of a block at a position.
+    ///
+    /// # Returns
+    ///
+    /// The new state and the move of the head, or `None` if there is no transition.
+    fn step(&self, state: u32, block: &mut Block, position: usize) -> Option<(u32, i8)> {
+        let entry = self.machine.entry(state, &[block[position]]);
+        let next_state = self.machine.next_states[entry];
+        if next_state == compiled_machine::NO_TRANSITION {
+            return None;
+        }
+        block[position] = self.machine.writes[entry];
+        Some((next_state, self.machine.moves[entry]))
+    }
+
+    /// Computes the behavior of the machine from a state and a position of a block, until the
+    /// head leaves it, the machine stops or a configuration repeats.
+    fn run_block(&self, mut state: u32, mut block: Block, mut position: usize) -> BlockResult {
+        let mut seen = std::collections::HashSet::new();
+        let (mut low, mut high) = (position, position);
+        let mut steps = 0;
+        loop {
+            let stop = self.machine.final_states[state as usize];
+            let next = if stop {
+                None
+            } else {
+                self.step(state, &mut block, position)
+            };
+            let (next_state, direction) = match next {
+                Some(next) => next,
+                None => {
+                    return BlockResult::Stop {
+                        state,
+                        position,
+                        block,
+                        steps,
+                        low,
+                        high,
+                    }
+                }
+            };
+            state = next_state;
+            steps += 1;
+            let side = match direction {
+                -1 if position == 0 => Some(Side::Left),
+                1 if position == self.block_size - 1 => Some(Side::Right),
+                _ => None,
+            };
+            if let Some(side) = side {
+                return BlockResult::Exit {
+                    side,
+                    state,
+                    block,
+                    steps,
+                    low,
+                    high,
+                };
+            }
+            position = (position as isize + direction as isize) as usize;
+            low = low.min(position);
+            high = high.max(position);
+            if !seen.insert((state, position, block.clone())) {
+                return BlockResult::Loop;
+            }
+        }
+    }
+
+    /// Runs the machine inside a block for at most `budget` moves, which must not be enough to
+    /// leave it, skipping the repetitions of the cycle it may enter.
+    ///
+    /// # Returns
+    ///
+    /// The final state, block and position, the moves made and the positions visited.
+    fn run_budget(
+        &self,
+        mut state: u32,
+        mut block: Block,
+        mut position: usize,
+        budget: usize,
+    ) -> (u32, Block, usize, usize, usize, usize) {
+        let mut seen = HashMap::new();
+        let (mut low, mut high) = (position, position);
+        let mut moves = 0;
+        while moves < budget && !self.machine.final_states[state as usize] {
+            if let Some(first) = seen.insert((state, position, block.clone()), moves) {
+                let period = moves - first;
+                moves += (budget - moves) / period * period;
+                seen.clear();
+                if moves == budget {
+                    break;
+                }
+            }
+            let (next_state, direction) = match self.step(state, &mut block, position) {
+                Some(next) => next,
+                None => break,
+            };
+            state = next_state;
+            moves += 1;
+            if (direction == -1 && position == 0)
+                || (direction == 1 && position == self.block_size - 1)
+            {
+                break;
+            }
+            position = (position as isize + direction as isize) as usize;
+            low = low.min(position);
+            high = high.max(position);
+        }
+        (state, block, position, moves, low, high)
+    }
+
+    /// Returns the behavior of the machine in a block, from the cache if possible.
+    fn block_result(&mut self, state: u32, block: &Block, position: usize) -> BlockResult {
+        let key = (state, block.clone(), position);
+        if let Some(result) = self.cache.get(&key) {
+            return result.clone();
+        }
+        let result = self.run_block(state, block.clone(), position);
+        self.cache.insert(key, result.clone());
+        result
+    }
+
+    /// Simulates the machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `tape` - The initial content of the tape, whose symbols must have been interned
+    /// * `head` - The initial position of the head
+    /// * `max_steps` - Maximum number of steps the simulation should run before stopping
+    ///
+    /// # Returns
+    ///
+    /// A `SimulationResult` containing:
+    /// - The outcome of the computation
+    /// - Final head position
+    /// - Final tape contents, with the cells ever visited by the head
+    /// - Number of steps executed
+    /// - An empty computation history
+    ///
+    /// # Notes
+    ///
+    /// - Like the plain simulation, the steps count the configurations reached, the initial one
+    ///   included, so at most `max_steps - 1` moves are made
+    pub fn simulate(
+        &mut self,
+        tape: &[String],
+        head: usize,
+        max_steps: usize,
+    ) -> computer::SimulationResult {
+        let size = self.block_size;
+        let blank_symbol = self.machine.blank_symbol;
+        let blank = vec![blank_symbol; size];
+        let numbers: HashMap<&String, u32> = self
+            .machine
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(number, symbol)| (symbol, number as u32))
+            .collect();
+        let mut blocks: Vec<Block> = tape
+            .chunks(size)
+            .map(|chunk| {
+                let mut block: Block = chunk.iter().map(|s| numbers[s]).collect();
+                block.resize(size, blank_symbol);
+                block
+            })
+            .collect();
+        blocks.resize(blocks.len().max(head / size + 1), blank.clone());
+
+        // the tape is made of `left`, the current block at `index` and `right`
+        let mut index = (head / size) as i64;
+        let mut position = head % size;
+        let mut left = HalfTape { runs: Vec::new() };
+        let mut right = HalfTape { runs: Vec::new() };
+        for block in blocks.iter().skip(head / size + 1).rev() {
+            right.push(block.clone(), 1);
+        }
+        let mut block = blocks.swap_remove(head / size);
+        blocks.truncate(head / size);
+        for block in blocks {
+            left.push(block, 1);
+        }
+
+        let mut state = self.machine.initial_state;
+        let mut budget = max_steps.saturating_sub(1);
+        let mut moves = 0;
+        // the first and last cells visited, which the plain simulation adds to the tape
+        let (mut first, mut last) = (0, tape.len() as i64 - 1);
+        let mut visit = |index: i64, low: usize, high: usize| {
+            first = first.min(index * size as i64 + low as i64);
+            last = last.max(index * size as i64 + high as i64);
+        };
+        visit(index, position, position);
+        while !self.machine.final_states[state as usize] {
+            match self.block_result(state, &block, position) {
+                BlockResult::Exit {
+                    side,
+                    state: next_state,
+                    block: next_block,
+                    steps,
+                    low,
+                    high,
+                } if steps <= budget => {
+                    visit(index, low, high);
+                    // every further equal block would be crossed the same way
+                    let (ahead, behind) = match side {
+                        Side::Left => (&mut left, &mut right),
+                        Side::Right => (&mut right, &mut left),
+                    };
+                    let entry = if side == Side::Left { size - 1 } else { 0 };
+                    let mut count = 1;
+                    if next_state == state && entry == position {
+                        let run = ahead.run_of(&block, &blank).min((budget - steps) / steps);
+                        ahead.skip(run);
+                        count += run;
+                    }
+                    behind.push(next_block, count);
+                    budget -= steps * count;
+                    moves += steps * count;
+                    // the blocks skipped are crossed from one end to the other
+                    let distance = if side == Side::Left { -1 } else { 1 };
+                    if count > 1 {
+                        visit(index + distance * (count as i64 - 1), 0, size - 1);
+                    }
+                    index += distance * count as i64;
+                    block = ahead.pop(&blank);
+                    state = next_state;
+                    position = entry;
+                    visit(index, position, position);
+                }
+                BlockResult::Stop {
+                    state: next_state,
+                    position: next_position,
+                    block: next_block,
+                    steps,
+                    low,
+                    high,
+                } if steps <= budget => {
+                    visit(index, low, high);
+                    moves += steps;
+                    state = next_state;
+                    block = next_block;
+                    position = next_position;
+                    break;
+                }
+                _ => {
+                    let (next_state, next_block, next_position, used, low, high) =
+                        self.run_budget(state, block, position, budget);
+                    visit(index, low, high);
+                    moves += used;
+                    state = next_state;
+                    block = next_block;
+                    position = next_position;
+                    break;
+                }
+            }
+        }
+
+        // the cells of the blocks from `first` to `last`
+        let first_block = first.div_euclid(size as i64);
+        let last_block = last.div_euclid(size as i64);
+        let mut cells = Vec::new();
+        let mut before = Vec::new();
+        let mut remaining = index - first_block;
+        for (run, count) in left.runs.iter().rev() {
+            let taken = (*count as i64).min(remaining);
+            for _ in 0..taken {
+                before.push(run);
+            }
+            remaining -= taken;
+            if remaining == 0 {
+                break;
+            }
+        }
+        for _ in 0..remaining {
+            before.push(&blank);
+        }
+        for run in before.into_iter().rev() {
+            cells.extend(run.iter().copied());
+        }
+        cells.extend(block.iter().copied());
+        let mut remaining = last_block - index;
+        for (run, count) in right.runs.iter().rev() {
+            let taken = (*count as i64).min(remaining);
+            for _ in 0..taken {
+                cells.extend(run.iter().copied());
+            }
+            remaining -= taken;
+            if remaining == 0 {
+                break;
+            }
+        }
+        for _ in 0..remaining {
+            cells.extend(blank.iter().copied());
+        }
+        let offset = (first - first_block * size as i64) as usize;
+        let result_tape: Vec<String> = cells[offset..offset + (last - first + 1) as usize]
+            .iter()
+            .map(|symbol| self.machine.symbols[*symbol as usize].clone())
+            .collect();
+        let result_head = (index * size as i64 + position as i64 - first) as usize;
+
+        computer::SimulationResult {
+            outcome: self.machine.outcome(state, &[block[position]]),
+            head: result_head,
+            tape: result_tape,
+            steps: moves + 1,
+            computation: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a single-tape machine from lines "state read new_state write direction".
+    fn machine(initial_state: &str, lines: &[&str]) -> turing_machine::TuringMachine {
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = "_".to_string();
+        tm.initial_state = initial_state.to_string();
+        tm.halt_state = "H".to_string();
+        tm.states = vec!["H".to_string()];
+        for line in lines {
+            let parts: Vec<&str> = line.split(' ').collect();
+            for state in [parts[0], parts[2]] {
+                if !tm.states.contains(&state.to_string()) {
+                    tm.states.push(state.to_string());
+                }
+            }
+            let direction = match parts[4] {
+                "L" => turing_machine::Direction::Left,
+                "R" => turing_machine::Direction::Right,
+                _ => turing_machine::Direction::Stay,
+            };
+            tm.add_transition(
+                parts[0].to_string(),
+                vec![parts[1].to_string()],
+                parts[2].to_string(),
+                vec![parts[3].to_string()],
+                vec![direction],
+            );
+        }
+        tm
+    }
+
+    fn tape(content: &str) -> Vec<String> {
+        content.chars().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_same_results() {
+        let machines = [
+            // the 4-state busy beaver champion
+            machine(
+                "A",
+                &[
+                    "A _ B 1 R",
+                    "A 1 B 1 L",
+                    "B _ A 1 L",
+                    "B 1 C _ L",
+                    "C _ H 1 R",
+                    "C 1 D 1 L",
+                    "D _ D 1 R",
+                    "D 1 A _ R",
+                ],
+            ),
+            // counts in binary forever
+            machine(
+                "R",
+                &[
+                    "R 0 R 0 R",
+                    "R 1 R 1 R",
+                    "R _ C _ L",
+                    "C 1 C 0 L",
+                    "C 0 R 1 R",
+                    "C _ R 1 R",
+                ],
+            ),
+            // loops inside a block, then stops without a transition
+            machine("A", &["A _ B _ R", "B 1 A 1 L", "A 1 A 1 S"]),
+            machine("A", &["A _ A 1 R", "A 1 B 1 L", "B 1 B 1 L", "B _ H 0 S"]),
+        ];
+        let computer = computer::Computer::new();
+        let mut context = computer::Server::new();
+        context.simulation.trace = false;
+        for tm in machines.iter() {
+            for input in ["", "1", "0110", "11111111"] {
+                let tape = [tape("_"), tape(input)].concat();
+                let compiled = compiled_machine::CompiledMachine::new(tm, &tape).unwrap();
+                for max_steps in [1, 2, 3, 10, 107, 108, 1000, 20000] {
+                    let expected = compiled.simulate(&tape, 0, max_steps, false, false);
+                    if max_steps <= 1000 {
+                        // the generic simulation of the computation tree, without any engine
+                        let generic = tm
+                            .simulate_tree(tape.clone(), max_steps, &computer, &context, 0)
+                            .unwrap();
+                        assert_eq!(generic, expected, "{} {}", input, max_steps);
+                    }
+                    for block_size in 1..=5 {
+                        let mut macro_machine = MacroMachine::new(tm, block_size, &tape).unwrap();
+                        assert_eq!(
+                            macro_machine.simulate(&tape, 0, max_steps),
+                            expected,
+                            "{} {} {}",
+                            input,
+                            max_steps,
+                            block_size
+                        );
+                    }
+                }
+            }
+        }
+
+        let tm = machines[0].clone();
+        let computer::SimulationResult {
+            outcome: state,
+            tape: result,
+            steps,
+            ..
+        } = MacroMachine::new(&tm, 2, &tape("_"))
+            .unwrap()
+            .simulate(&tape("_"), 0, 1000);
+        assert_eq!(state, computer::Outcome::Halt);
+        assert_eq!(steps, 108);
+        assert_eq!(result.iter().filter(|s| *s == "1").count(), 13);
+    }
+
+    #[test]
+    fn test_run_skipping() {
+        // sweeps back and forth over the input
+        let tm = machine("B", &["A 1 A 1 R", "A _ B _ L", "B 1 B 1 L", "B _ A _ R"]);
+        let n = 100_000;
+        let tape = [tape("_"), vec!["1".to_string(); n]].concat();
+        let mut macro_machine = MacroMachine::new(&tm, 4, &tape).unwrap();
+        let max_steps = 100_000_001;
+        let computer::SimulationResult {
+            outcome,
+            head,
+            tape: result,
+            steps,
+            computation,
+        } = macro_machine.simulate(&tape, 0, max_steps);
+        // every sweep takes 2n + 2 moves
+        let r = (max_steps - 1) % (2 * n + 2);
+        assert_eq!(steps, max_steps);
+        assert_eq!(outcome, computer::Outcome::Timeout);
+        assert_eq!(head, if r <= n + 1 { r } else { 2 * n + 2 - r });
+        assert_eq!(result.len(), n + 2);
+        assert!(computation.is_empty());
+        assert!(macro_machine.cache.len() < 100);
+
+        assert!(MacroMachine::new(&tm, 0, &tape).is_none());
+    }
+}
@@ -1,25 +1,15 @@
 //! # Computing Simulator - Main Entry Point
 //!
 //! This is the main entry point for the Computing Simulator project.
-//! It imports all the core modules and launches the command-line interface (CLI).
-//!
-//! ## Modules
-//! - `cli`: Handles the command-line interface and user interaction.
-//! - `computer`: Core computer simulation logic.
-//! - `file_handler`: Utilities for file input/output.
-//! - `lambda`: Lambda calculus interpreter and related structures.
-//! - `options`: Command-line options and configuration parsing.
-//! - `ram_machine`: RAM machine simulation.
-//! - `regex`: Regular expression utilities and simulation.
-//! - `turing_machine`: Turing machine simulation.
-//! - `utils`: Miscellaneous utility functions.
+//! It is a thin binary on top of the `computing_simulator` library, whose modules hold the
+//! models of computation, and it launches the command-line interface (CLI).
 //!
 //! ## Usage
 //! Run the binary to start the CLI for the Computing Simulator.
 //!
 //! ## Testing
-//! This file includes integration tests to ensure that all modules are properly linked
-//! and that the CLI can be invoked without panicking.
+//! This file includes integration tests to ensure that the modules of the library are
+//! properly linked and that the CLI can be invoked without panicking.
 //!
 //! ## Author
 //!
@@ -29,15 +19,7 @@
 //!
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
-mod cli;
-mod computer;
-mod file_handler;
-mod lambda;
-mod options;
-mod ram_machine;
-mod regex;
-mod turing_machine;
-mod utils;
+use computing_simulator::main_cli;
 
 /// The main function serves as the entry point for the application.
 /// It initializes the command-line interface (CLI) for the Computing Simulator.
@@ -52,11 +34,12 @@ mod utils;
 /// This will start the CLI, allowing users to input commands and options.
 ///
 fn main() {
-    cli::main_cli();
+    main_cli();
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use computing_simulator::{computer, lambda, ram_machine, turing_machine};
 
     #[test]
     fn test_main_cli_integration() {
@@ -64,7 +47,7 @@ mod tests {
             let stdout = std::io::stdout();
             let _handle = stdout.lock();
 
-            cli::main_cli();
+            main_cli();
         })
         .is_ok());
     }
@@ -72,7 +55,7 @@ mod tests {
     #[test]
     fn test_module_imports() {
         assert!(std::panic::catch_unwind(|| {
-            let _cli = cli::main_cli;
+            let _cli = main_cli;
             let _computer = computer::Computer::new();
             let _lambda = lambda::Lambda {
                 expr: lambda::LambdaExpr::Var("a".to_string()),
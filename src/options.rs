@@ -27,8 +27,52 @@
 //! - `--status`: Print status information.
 //! - `--print-encoding`: Print the encoding used.
 //! - `--verbose=<i32>`: Set verbosity level (default: 1).
+//! - `--determinize`: Print the deterministic automaton equivalent to the input automaton.
+//! - `--minimize`: Print the minimal deterministic automaton equivalent to the input automaton.
+//! - `--equiv=<String>`: Check whether the input automaton recognizes the same language as the
+//!   one in the given file. `--equiv a.fsm b.reg` compares the two given files.
+//! - `--union=<String>`, `--intersection=<String>`, `--difference=<String>`: Print the automaton
+//!   recognizing the union, intersection or difference of the language of the input automaton
+//!   and the one of the given file. `--union a.fsm b.reg` combines the two given files.
+//! - `--complement`: Print the automaton recognizing the complement of the input language.
+//! - `--to-regex`: Print a regular expression matching the language of the input automaton.
+//! - `--regex-dfa`: Build the automaton of `regex` files as a DFA with Brzozowski derivatives,
+//!   instead of the ε-NFA of the Thompson construction.
+//! - `--search=<String>`: Print every match of the input automaton inside the input, with
+//!   `longest` (leftmost-longest, the default of `--search`) or `first` (leftmost-first) semantics.
+//! - `--grep=<String>`: Print the lines of the given text file containing a match of the input
+//!   automaton, with the semantics of `--search`.
+//!   The `first` semantics cannot be combined with `--regex-dfa`, since determinization loses the
+//!   order of the alternatives.
+//! - `--parse=<String>`: Decide whether the input belongs to the language of the input grammar with
+//!   the `earley` (the default of `--parse`) or `cyk` algorithm, printing a parse tree if it does.
+//! - `--cnf`: Print the input grammar in Chomsky normal form, with every intermediate grammar
+//!   when the verbosity level is at least 2.
+//! - `--gnf`: Print the input grammar in Greibach normal form, like `--cnf`.
+//! - `--to-pda`: Print a pushdown automaton accepting the language of the input grammar.
+//! - `--to-cfg`: Print a grammar generating the language of the input pushdown automaton.
+//! - `--strategy=<String>`: Explore the configurations of non-deterministic Turing machines with
+//!   `bfs` (breadth-first, the default), `dfs` (depth-first, expanding at most the maximum number
+//!   of steps configurations), `iddfs` (iterative deepening, within the same budget) or `random`
+//!   (a single random branch, `random:<u64>` to choose the seed).
+//! - `--block-size=<usize>`: Simulate deterministic single-tape Turing machines over blocks of the
+//!   given number of cells, skipping runs of equal blocks.
+//! - `--detect-loops`: Stop the simulation of deterministic Turing machines and RAM machines as
+//!   soon as they are proved to never halt, printing the proof; Turing machines simulated over
+//!   blocks of cells, non-deterministic or calling subroutines are rejected.
+//! - `--busy-beaver=<usize>[,<usize>]`: Enumerate the Turing machines with the given number of
+//!   states and symbols (2 by default) in tree normal form, printing the busy beaver champions;
+//!   the file given, if any, is the table of the machines run, from which the enumeration resumes.
+//! - `--debug`: Debug Turing machines, RAM machines and lambda expressions on the input step by
+//!   step, reading debugger commands from the standard input.
+//! - `--visualize`: Show the computation of Turing machines, RAM machines and lambda expressions
+//!   on the input full screen, with run, pause and speed controls.
+//! - `--trace-format=<String>`: Print the computation history, at verbosity 2, as `text` (the
+//!   default), `jsonl` (one JSON object per step) or `csv`.
 //!
 //! Any unrecognized argument is treated as a file name, with optional surrounding quotes removed.
+//! When more than one file name is given the last one is used, except with the options taking two
+//! files, which report an error when the second file is missing. Only one of them can be given.
 //!
 //! ## Testing
 //!
@@ -65,6 +109,34 @@
 /// - `status`: Prints status information if `true`.
 /// - `print_encoding`: Prints the encoding used if `true`.
 /// - `verbose`: Sets the verbosity level (default: 1).
+/// - `determinize`: Prints the deterministic automaton equivalent to the input automaton if `true`.
+/// - `minimize`: Prints the minimal deterministic automaton and its equivalence classes if `true`.
+/// - `equiv`: If not empty, the file whose language is compared with the one of `file`.
+/// - `union`: If not empty, the file whose language is joined with the one of `file`.
+/// - `intersection`: If not empty, the file whose language is intersected with the one of `file`.
+/// - `difference`: If not empty, the file whose language is removed from the one of `file`.
+/// - `error`: If not empty, the error found in the arguments, reported instead of running.
+/// - `complement`: Prints the automaton recognizing the complement of the input language if `true`.
+/// - `to_regex`: Prints a regular expression matching the language of the input automaton if `true`.
+/// - `regex_dfa`: Builds `regex` files directly as DFAs if `true`.
+/// - `search`: If not empty, the semantics (`longest` or `first`) used to search the input.
+/// - `grep`: If not empty, the text file whose lines are searched.
+/// - `parse`: If not empty, the algorithm (`earley` or `cyk`) used to parse the input.
+/// - `cnf`: Prints the input grammar in Chomsky normal form if `true`.
+/// - `gnf`: Prints the input grammar in Greibach normal form if `true`.
+/// - `to_pda`: Prints a pushdown automaton equivalent to the input grammar if `true`.
+/// - `to_cfg`: Prints a grammar equivalent to the input pushdown automaton if `true`.
+/// - `strategy`: If not empty, the strategy (`bfs`, `dfs`, `iddfs`, `random` or `random:<seed>`)
+///   used to simulate non-deterministic Turing machines.
+/// - `block_size`: If greater than 0, the number of cells of the blocks used to accelerate the
+///   simulation of deterministic single-tape Turing machines (default: 0).
+/// - `detect_loops`: Stops the simulations proved to diverge if `true`.
+/// - `busy_beaver`: If not empty, the number of states and symbols of the busy beavers to
+///   enumerate.
+/// - `debug`: Starts the step-by-step debugger if `true`.
+/// - `visualize`: Shows the computation full screen if `true`.
+/// - `trace_format`: If not empty, the format (`text`, `jsonl` or `csv`) of the computation
+///   history.
 #[derive(Clone, Default)]
 pub struct Options {
     pub convert_to_tm: bool,
@@ -81,6 +153,30 @@ pub struct Options {
     pub status: bool,
     pub print_encoding: bool,
     pub verbose: i32,
+    pub determinize: bool,
+    pub minimize: bool,
+    pub equiv: String,
+    pub union: String,
+    pub intersection: String,
+    pub difference: String,
+    pub error: String,
+    pub complement: bool,
+    pub to_regex: bool,
+    pub regex_dfa: bool,
+    pub search: String,
+    pub grep: String,
+    pub parse: String,
+    pub cnf: bool,
+    pub gnf: bool,
+    pub to_pda: bool,
+    pub to_cfg: bool,
+    pub strategy: String,
+    pub block_size: usize,
+    pub detect_loops: bool,
+    pub busy_beaver: String,
+    pub debug: bool,
+    pub visualize: bool,
+    pub trace_format: String,
 }
 
 /// Parses command-line arguments and returns an `Options` struct populated with the corresponding values.
@@ -110,6 +206,29 @@ pub struct Options {
 /// - `--status`
 /// - `--print-encoding`
 /// - `--verbose=<i32>`
+/// - `--determinize`
+/// - `--minimize`
+/// - `--equiv=<String>` or `--equiv <file> <file>`
+/// - `--union=<String>` or `--union <file> <file>`
+/// - `--intersection=<String>` or `--intersection <file> <file>`
+/// - `--difference=<String>` or `--difference <file> <file>`
+/// - `--complement`
+/// - `--to-regex`
+/// - `--regex-dfa`
+/// - `--search=<String>` or `--search`
+/// - `--grep=<String>`
+/// - `--parse=<String>` or `--parse`
+/// - `--cnf`
+/// - `--gnf`
+/// - `--to-pda`
+/// - `--to-cfg`
+/// - `--strategy=<String>`
+/// - `--block-size=<usize>`
+/// - `--detect-loops`
+/// - `--busy-beaver=<String>`
+/// - `--debug`
+/// - `--visualize`
+/// - `--trace-format=<String>`
 ///
 /// # Note
 ///
@@ -129,6 +248,35 @@ pub fn get_options() -> Options {
     let mut status = false;
     let mut print_encoding = false;
     let mut verbose = 1;
+    let mut determinize = false;
+    let mut minimize = false;
+    let mut equiv = String::new();
+    let mut union = String::new();
+    let mut intersection = String::new();
+    let mut difference = String::new();
+    let mut error = String::new();
+    let mut complement = false;
+    let mut to_regex = false;
+    let mut regex_dfa = false;
+    let mut search = String::new();
+    let mut grep = String::new();
+    let mut parse = String::new();
+    let mut cnf = false;
+    let mut gnf = false;
+    let mut to_pda = false;
+    let mut to_cfg = false;
+    let mut strategy = String::new();
+    let mut block_size = 0;
+    let mut detect_loops = false;
+    let mut busy_beaver = String::new();
+    let mut debug = false;
+    let mut visualize = false;
+    let mut trace_format = String::new();
+    // the option given without a value, reading its file after the main one
+    let mut two_files_flag = String::new();
+    // the options taking two files given, of which only one is allowed
+    let mut two_files_options = 0;
+    let mut files = Vec::new();
 
     #[cfg(test)]
     let args = tests::ARGS
@@ -153,6 +301,45 @@ pub fn get_options() -> Options {
             if let Ok(value) = arg.strip_prefix("--max-steps=").unwrap_or("1000").parse() {
                 max_steps = value;
             }
+        } else if let Some((name, value)) = arg.split_once('=').filter(|(name, _)| {
+            ["--equiv", "--union", "--intersection", "--difference"].contains(name)
+        }) {
+            two_files_options += 1;
+            let mut value = value.to_string();
+            if value.starts_with('"') && value.ends_with('"') {
+                value = value[1..value.len() - 1].to_string();
+            }
+            if value.is_empty() {
+                error = format!("{} needs a second file", name);
+            }
+            match name {
+                "--equiv" => equiv = value,
+                "--union" => union = value,
+                "--intersection" => intersection = value,
+                _ => difference = value,
+            }
+        } else if arg.starts_with("--search=") {
+            search = arg.strip_prefix("--search=").unwrap_or("longest").to_string();
+        } else if arg.starts_with("--grep=") {
+            grep = arg.strip_prefix("--grep=").unwrap_or("").to_string();
+            if grep.starts_with('"') && grep.ends_with('"') {
+                grep = grep[1..grep.len() - 1].to_string();
+            }
+        } else if arg.starts_with("--parse=") {
+            parse = arg.strip_prefix("--parse=").unwrap_or("earley").to_string();
+        } else if arg.starts_with("--trace-format=") {
+            trace_format = arg
+                .strip_prefix("--trace-format=")
+                .unwrap_or("text")
+                .to_string();
+        } else if arg.starts_with("--strategy=") {
+            strategy = arg.strip_prefix("--strategy=").unwrap_or("bfs").to_string();
+        } else if arg.starts_with("--block-size=") {
+            if let Ok(value) = arg.strip_prefix("--block-size=").unwrap_or("0").parse() {
+                block_size = value;
+            }
+        } else if arg.starts_with("--busy-beaver=") {
+            busy_beaver = arg.strip_prefix("--busy-beaver=").unwrap_or("").to_string();
         } else if arg.starts_with("--verbose=") {
             if let Ok(value) = arg.strip_prefix("--verbose=").unwrap_or("1").parse() {
                 verbose = value;
@@ -168,15 +355,66 @@ pub fn get_options() -> Options {
                 "--version" => version = true,
                 "--status" => status = true,
                 "--print-encoding" => print_encoding = true,
+                "--determinize" => determinize = true,
+                "--minimize" => minimize = true,
+                "--complement" => complement = true,
+                "--to-regex" => to_regex = true,
+                "--regex-dfa" => regex_dfa = true,
+                "--search" => search = "longest".to_string(),
+                "--parse" => parse = "earley".to_string(),
+                "--cnf" => cnf = true,
+                "--gnf" => gnf = true,
+                "--to-pda" => to_pda = true,
+                "--to-cfg" => to_cfg = true,
+                "--detect-loops" => detect_loops = true,
+                "--debug" => debug = true,
+                "--visualize" => visualize = true,
+                "--equiv" | "--union" | "--intersection" | "--difference" => {
+                    two_files_options += 1;
+                    two_files_flag = arg.clone()
+                }
                 _ => {
                     file = arg.clone();
                     if file.starts_with('"') && file.ends_with('"') {
                         file = file[1..file.len() - 1].to_string();
                     }
+                    files.push(file.clone());
                 }
             }
         }
     }
+    if two_files_options > 1 {
+        error = "only one of --equiv, --union, --intersection and --difference can be given"
+            .to_string();
+    } else if !two_files_flag.is_empty() {
+        if files.len() < 2 {
+            error = format!("{} needs a second file", two_files_flag);
+        } else {
+            file = files[0].clone();
+            let second = files[1].clone();
+            match two_files_flag.as_str() {
+                "--equiv" => equiv = second,
+                "--union" => union = second,
+                "--intersection" => intersection = second,
+                _ => difference = second,
+            }
+        }
+    }
+    if !parse.is_empty() && parse != "earley" && parse != "cyk" {
+        error = format!(
+            "Invalid parsing algorithm: '{}'. Expected 'earley' or 'cyk'",
+            parse
+        );
+    }
+    if !search.is_empty() && search != "longest" && search != "first" {
+        error = format!(
+            "Invalid search semantics: '{}'. Expected 'longest' or 'first'",
+            search
+        );
+    } else if search == "first" && regex_dfa {
+        error = "--search=first needs the order of the alternatives, which --regex-dfa loses"
+            .to_string();
+    }
 
     Options {
         print_computer,
@@ -193,6 +431,30 @@ pub fn get_options() -> Options {
         status,
         print_encoding,
         verbose,
+        determinize,
+        minimize,
+        equiv,
+        union,
+        intersection,
+        difference,
+        error,
+        complement,
+        to_regex,
+        regex_dfa,
+        search,
+        grep,
+        parse,
+        cnf,
+        gnf,
+        to_pda,
+        to_cfg,
+        strategy,
+        block_size,
+        detect_loops,
+        busy_beaver,
+        debug,
+        visualize,
+        trace_format,
     }
 }
 
@@ -289,6 +551,9 @@ mod tests {
                 "--version".to_string(),
                 "--status".to_string(),
                 "--print-encoding".to_string(),
+                "--determinize".to_string(),
+                "--minimize".to_string(),
+                "--regex-dfa".to_string(),
             ];
         });
 
@@ -302,6 +567,308 @@ mod tests {
         assert!(options.version);
         assert!(options.status);
         assert!(options.print_encoding);
+        assert!(options.determinize);
+        assert!(options.minimize);
+        assert!(options.regex_dfa);
+    }
+
+    #[test]
+    fn test_equiv_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--equiv".to_string(),
+                "a.fsm".to_string(),
+                "b.reg".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.file, "a.fsm");
+        assert_eq!(options.equiv, "b.reg");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "a.fsm".to_string(),
+                "--equiv=\"b.reg\"".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.file, "a.fsm");
+        assert_eq!(options.equiv, "b.reg");
+        assert!(options.error.is_empty());
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--equiv".to_string(),
+                "a.fsm".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert!(options.equiv.is_empty());
+        assert_eq!(options.error, "--equiv needs a second file");
+
+        // the union would silently replace the comparison
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--equiv=a.fsm".to_string(),
+                "--union".to_string(),
+                "b.fsm".to_string(),
+                "c.fsm".to_string(),
+            ];
+        });
+        assert_eq!(
+            get_options().error,
+            "only one of --equiv, --union, --intersection and --difference can be given"
+        );
+    }
+
+    #[test]
+    fn test_boolean_operation_options() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--difference".to_string(),
+                "a.fsm".to_string(),
+                "b.reg".to_string(),
+                "--complement".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.file, "a.fsm");
+        assert_eq!(options.difference, "b.reg");
+        assert!(options.complement);
+        assert!(!options.to_regex);
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "a.fsm".to_string(),
+                "--union=\"b.reg\"".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.file, "a.fsm");
+        assert_eq!(options.union, "b.reg");
+        assert!(options.equiv.is_empty());
+        assert!(options.error.is_empty());
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "a.fsm".to_string(),
+                "--union=\"b.reg\"".to_string(),
+                "--intersection=c.fsm".to_string(),
+            ];
+        });
+        assert!(!get_options().error.is_empty());
+
+        for flag in ["--union", "--intersection", "--difference"] {
+            ARGS.with(|args| {
+                *args.borrow_mut() = vec![
+                    "program".to_string(),
+                    flag.to_string(),
+                    "a.fsm".to_string(),
+                ];
+            });
+            assert_eq!(get_options().error, format!("{} needs a second file", flag));
+
+            ARGS.with(|args| {
+                *args.borrow_mut() = vec![
+                    "program".to_string(),
+                    "a.fsm".to_string(),
+                    format!("{}=", flag),
+                ];
+            });
+            assert_eq!(get_options().error, format!("{} needs a second file", flag));
+        }
+
+        // the value left empty does not count as the second file given without a value
+        for args_given in [
+            vec!["--union=", "--equiv", "a.fsm"],
+            vec!["--equiv", "--union="],
+        ] {
+            ARGS.with(|args| {
+                *args.borrow_mut() = ["program"]
+                    .iter()
+                    .chain(args_given.iter())
+                    .map(|arg| arg.to_string())
+                    .collect();
+            });
+            assert!(!get_options().error.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_search_options() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "a.reg".to_string(),
+                "--search".to_string(),
+                "--grep=\"text file.txt\"".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.search, "longest");
+        assert_eq!(options.grep, "text file.txt");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--search=first".to_string()];
+        });
+        assert_eq!(get_options().search, "first");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--search=first".to_string(),
+                "--regex-dfa".to_string(),
+            ];
+        });
+        assert!(!get_options().error.is_empty());
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--search=frist".to_string()];
+        });
+        assert_eq!(
+            get_options().error,
+            "Invalid search semantics: 'frist'. Expected 'longest' or 'first'"
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--parse".to_string()];
+        });
+        assert_eq!(get_options().parse, "earley");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--parse=cyk".to_string()];
+        });
+        assert_eq!(get_options().parse, "cyk");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--parse=cky".to_string()];
+        });
+        assert_eq!(
+            get_options().error,
+            "Invalid parsing algorithm: 'cky'. Expected 'earley' or 'cyk'"
+        );
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--cnf".to_string(),
+                "--gnf".to_string(),
+                "--to-pda".to_string(),
+                "--to-cfg".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert!(options.cnf);
+        assert!(options.gnf);
+        assert!(options.to_pda);
+        assert!(options.to_cfg);
+    }
+
+    #[test]
+    fn test_strategy_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--strategy=random:7".to_string()];
+        });
+        assert_eq!(get_options().strategy, "random:7");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string()];
+        });
+        assert!(get_options().strategy.is_empty());
+    }
+
+    #[test]
+    fn test_block_size_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--block-size=3".to_string()];
+        });
+        assert_eq!(get_options().block_size, 3);
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--block-size=x".to_string()];
+        });
+        assert_eq!(get_options().block_size, 0);
+        assert!(!get_options().detect_loops);
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--detect-loops".to_string()];
+        });
+        assert!(get_options().detect_loops);
+    }
+
+    #[test]
+    fn test_busy_beaver_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--busy-beaver=4,2".to_string(),
+                "table.tsv".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert_eq!(options.busy_beaver, "4,2");
+        assert_eq!(options.file, "table.tsv");
+    }
+
+    #[test]
+    fn test_debug_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "test.tm".to_string()];
+        });
+        assert!(!get_options().debug);
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "--debug".to_string(),
+                "test.tm".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert!(options.debug);
+        assert_eq!(options.file, "test.tm");
+    }
+
+    #[test]
+    fn test_trace_format_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "--trace-format=jsonl".to_string()];
+        });
+        assert_eq!(get_options().trace_format, "jsonl");
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string()];
+        });
+        assert!(get_options().trace_format.is_empty());
+    }
+
+    #[test]
+    fn test_visualize_option() {
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec!["program".to_string(), "test.ram".to_string()];
+        });
+        assert!(!get_options().visualize);
+
+        ARGS.with(|args| {
+            *args.borrow_mut() = vec![
+                "program".to_string(),
+                "test.ram".to_string(),
+                "--visualize".to_string(),
+            ];
+        });
+        let options = get_options();
+        assert!(options.visualize);
+        assert_eq!(options.file, "test.ram");
     }
 
     #[test]
@@ -0,0 +1,1292 @@
+//! # Pushdown Automaton Module
+//!
+//! This module provides a first-class implementation of pushdown automata. Until now `pda` files
+//! were lowered straight into a 2-tape Turing machine whose second tape played the stack; this
+//! module keeps them as automata, so that they can push strings of any length and show their
+//! stack while they are simulated.
+//!
+//! ## Main Structures
+//!
+//! - **PushdownAutomaton**: Represents a (possibly non-deterministic) pushdown automaton,
+//!   including its states, alphabets, initial and final states, transitions and acceptance mode.
+//! - **Transition**: Represents a single move of the automaton, replacing the top of the stack
+//!   with a string of symbols.
+//! - **Acceptance**: Represents the acceptance mode, by final state or by empty stack.
+//! - **PushdownAutomatonBuilder**: Constructs an automaton in code, completing its states and
+//!   alphabets from the transitions.
+//!
+//! ## Key Features
+//!
+//! - **Parsing**: Read an automaton from the lines of a `pda` file.
+//! - **Determinism**: Check the conditions of deterministic pushdown automata, taking ε-moves
+//!   into account, and find the transitions breaking them.
+//! - **Simulation**: Follow the only computation path of deterministic automata, and explore the
+//!   configurations of non-deterministic ones breadth-first, recording the stack of each
+//!   configuration of the computation.
+//! - **PDA to CFG**: Build a grammar generating the accepted words with the triple construction,
+//!   whose nonterminals `[p,X,q]` derive the words read while going from `p` to `q` and popping `X`.
+//! - **CFG to PDA**: Build the single-state automaton which expands the nonterminal on the top of
+//!   its stack and matches the terminals against the input.
+//! - **Conversion**: Conversion to the 2-tape Turing machine form used by the rest of the
+//!   simulator (`to_tm`), so that automata can still be encoded and converted to the other
+//!   computational models.
+//! - **Printing**: The `Display` implementation produces a valid `pda` file.
+//!
+//! ## File Format
+//!
+//! The `pda` file format is the following (after the header line, `pda` or `pda final-state` for
+//! acceptance by final state and `pda empty-stack` for acceptance by empty stack):
+//!
+//! 1. initial state
+//! 2. final states, separated by spaces
+//! 3. states, separated by spaces
+//! 4. input alphabet, separated by spaces
+//! 5. stack alphabet, separated by spaces
+//! 6. blank symbol
+//! 7. one transition per line: `state input top new_state new_top1 ... new_topk`, replacing the
+//!    top of the stack with `new_top1 ... new_topk` (`new_topk` on the top). A single blank
+//!    `new_top1` pops the top of the stack, and a blank `input` denotes an ε-move.
+//!
+//! The stack starts with a single blank symbol, and a word is accepted when the automaton reads
+//! it and reaches a final state or empties its stack, depending on the acceptance mode. With the
+//! plain `pda` header the stack is bottomless, as when automata were lowered to Turing machines:
+//! unlimited blanks lie below it, so popping the last symbol uncovers another blank.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::grammar;
+use crate::trace;
+use crate::turing_machine;
+
+/// A complete representation of a pushdown automaton, possibly non-deterministic and with ε-moves.
+///
+/// # Fields
+/// * `initial_state` - The starting state of the automaton
+/// * `final_states` - The accepting states of the automaton
+/// * `states` - Collection of all states in the automaton
+/// * `input_alphabet` - Set of symbols allowed in the input
+/// * `stack_alphabet` - Set of symbols allowed on the stack
+/// * `blank_symbol` - The symbol initially on the stack, also denoting ε-moves
+/// * `transitions` - The transition relation as a collection of moves
+/// * `acceptance` - Whether the words are accepted by final state or by empty stack
+/// * `bottomless` - Whether unlimited blank symbols lie below the stack, so that it is never
+///   exhausted (`pda` files without acceptance mode)
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PushdownAutomaton {
+    pub initial_state: String,
+    pub final_states: Vec<String>,
+    pub states: Vec<String>,
+    pub input_alphabet: Vec<String>,
+    pub stack_alphabet: Vec<String>,
+    pub blank_symbol: String,
+    pub transitions: Vec<Transition>,
+    pub acceptance: Acceptance,
+    pub bottomless: bool,
+}
+
+/// Represents the condition under which a pushdown automaton accepts its input, once read.
+///
+/// # Variants
+///
+/// * `FinalState` - The automaton is in a final state
+/// * `EmptyStack` - The stack of the automaton is empty
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Acceptance {
+    FinalState,
+    EmptyStack,
+}
+
+/// Represents a move of a pushdown automaton.
+///
+/// # Fields
+///
+/// * `state` - The state the move starts from.
+/// * `symbol` - The input symbol read, or `None` for an ε-move.
+/// * `top` - The symbol on the top of the stack.
+/// * `new_state` - The state the move leads to.
+/// * `push` - The symbols replacing the top of the stack, from the bottom to the new top; empty to pop.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub state: String,
+    pub symbol: Option<String>,
+    pub top: String,
+    pub new_state: String,
+    pub push: Vec<String>,
+}
+
+/// A configuration explored by the simulation, with the index of the configuration it comes from.
+struct Configuration {
+    state: String,
+    position: usize,
+    stack: Vec<String>,
+    parent: Option<usize>,
+}
+
+impl Default for PushdownAutomaton {
+    fn default() -> Self {
+        PushdownAutomaton::new()
+    }
+}
+
+impl PushdownAutomaton {
+    /// Creates a new empty `PushdownAutomaton` instance.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `PushdownAutomaton` with no states nor transitions, `_` as blank symbol and
+    /// acceptance by final state.
+    pub fn new() -> Self {
+        PushdownAutomaton {
+            initial_state: String::new(),
+            final_states: Vec::new(),
+            states: Vec::new(),
+            input_alphabet: Vec::new(),
+            stack_alphabet: Vec::new(),
+            blank_symbol: "_".to_string(),
+            transitions: Vec::new(),
+            acceptance: Acceptance::FinalState,
+            bottomless: false,
+        }
+    }
+
+    /// Returns a builder constructing a pushdown automaton in code.
+    pub fn builder() -> PushdownAutomatonBuilder {
+        PushdownAutomatonBuilder::new()
+    }
+
+    /// Reads an automaton from the lines of a `pda` file, without the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The lines holding the definition of the automaton
+    /// * `acceptance` - The acceptance mode chosen by the header
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PushdownAutomaton)` - The automaton described by the lines
+    /// * `Err(String)` - An error message if the definition is not valid
+    pub fn from_lines(
+        lines: &[String],
+        acceptance: Acceptance,
+    ) -> Result<PushdownAutomaton, String> {
+        if lines.len() < 6 {
+            return Err("Pushdown Automaton definition must have at least 6 lines".to_string());
+        }
+        let split =
+            |line: &String| -> Vec<String> { line.split_whitespace().map(String::from).collect() };
+        let mut pda = PushdownAutomaton {
+            initial_state: lines[0].trim().to_string(),
+            final_states: split(&lines[1]),
+            states: split(&lines[2]),
+            input_alphabet: split(&lines[3]),
+            stack_alphabet: split(&lines[4]),
+            blank_symbol: lines[5].trim().to_string(),
+            transitions: Vec::new(),
+            acceptance,
+            bottomless: false,
+        };
+        for line in lines.iter().skip(6).filter(|l| !l.trim().is_empty()) {
+            let data = split(line);
+            if data.len() < 5 {
+                return Err(format!("Error: Transition format not valid: {}", line));
+            }
+            let push = if data.len() == 5 && data[4] == pda.blank_symbol {
+                Vec::new()
+            } else {
+                data[4..].to_vec()
+            };
+            pda.transitions.push(Transition {
+                state: data[0].clone(),
+                symbol: (data[1] != pda.blank_symbol).then(|| data[1].clone()),
+                top: data[2].clone(),
+                new_state: data[3].clone(),
+                push,
+            });
+        }
+        Ok(pda)
+    }
+
+    /// Builds the automaton accepting the language of a grammar.
+    ///
+    /// The automaton has a single state and accepts by empty stack: it first replaces the blank
+    /// symbol with the start symbol, then replaces the nonterminal on the top of the stack with
+    /// the body of one of its productions (pushed so that its first symbol is on the top), and
+    /// pops the terminal on the top of the stack when it matches the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `g` - The grammar to convert
+    ///
+    /// # Returns
+    ///
+    /// An automaton accepting the words generated by the grammar.
+    pub fn from_cfg(g: &grammar::Grammar) -> PushdownAutomaton {
+        let mut pda = PushdownAutomaton::new();
+        while g.is_nonterminal(&pda.blank_symbol) || g.terminals.contains(&pda.blank_symbol) {
+            pda.blank_symbol.push('_');
+        }
+        pda.acceptance = Acceptance::EmptyStack;
+        pda.initial_state = "loop".to_string();
+        pda.states = vec!["loop".to_string()];
+        pda.input_alphabet = g.terminals.clone();
+        pda.stack_alphabet = g
+            .nonterminals
+            .iter()
+            .chain(g.terminals.iter())
+            .cloned()
+            .collect();
+        pda.stack_alphabet.push(pda.blank_symbol.clone());
+
+        let blank = pda.blank_symbol.clone();
+        pda.add_transition("loop", None, &blank, "loop", vec![g.start_symbol.clone()]);
+        for p in g.productions.iter() {
+            let body = p.body.iter().rev().cloned().collect();
+            pda.add_transition("loop", None, &p.head, "loop", body);
+        }
+        for terminal in g.terminals.iter() {
+            pda.add_transition("loop", Some(terminal.clone()), terminal, "loop", Vec::new());
+        }
+        pda
+    }
+
+    /// Adds a new transition to the automaton.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state the move starts from
+    /// * `symbol` - The input symbol read, or `None` for an ε-move
+    /// * `top` - The symbol on the top of the stack
+    /// * `new_state` - The state the move leads to
+    /// * `push` - The symbols replacing the top of the stack, from the bottom to the new top
+    pub fn add_transition(
+        &mut self,
+        state: &str,
+        symbol: Option<String>,
+        top: &str,
+        new_state: &str,
+        push: Vec<String>,
+    ) {
+        self.transitions.push(Transition {
+            state: state.to_string(),
+            symbol,
+            top: top.to_string(),
+            new_state: new_state.to_string(),
+            push,
+        });
+    }
+
+    /// Checks whether a configuration accepts the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the configuration
+    /// * `stack` - The stack of the configuration
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the state is final or the stack is empty, depending on the acceptance
+    /// mode, `false` otherwise. The input is assumed to be read.
+    fn is_accepting(&self, state: &String, stack: &[String]) -> bool {
+        match self.acceptance {
+            Acceptance::FinalState => self.final_states.contains(state),
+            Acceptance::EmptyStack => stack.is_empty(),
+        }
+    }
+
+    /// Returns the symbol on the top of a stack, which is the blank symbol for an exhausted
+    /// bottomless stack.
+    fn top<'a>(&'a self, stack: &'a [String]) -> Option<&'a String> {
+        stack
+            .last()
+            .or_else(|| self.bottomless.then_some(&self.blank_symbol))
+    }
+
+    /// Finds the pairs of transitions which make the automaton non-deterministic.
+    ///
+    /// Two transitions conflict when they start from the same state with the same symbol on the
+    /// top of the stack, and either they read the same input symbol or one of them is an ε-move,
+    /// which could be taken instead of reading the input.
+    ///
+    /// # Returns
+    ///
+    /// The pairs of indices of the conflicting transitions, the smaller index first.
+    pub fn determinism_conflicts(&self) -> Vec<(usize, usize)> {
+        let mut conflicts = Vec::new();
+        for (i, t) in self.transitions.iter().enumerate() {
+            for (j, u) in self.transitions.iter().enumerate().skip(i + 1) {
+                if t.state == u.state
+                    && t.top == u.top
+                    && (t.symbol.is_none() || u.symbol.is_none() || t.symbol == u.symbol)
+                {
+                    conflicts.push((i, j));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Checks whether the automaton is a deterministic pushdown automaton.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if at most one move can be taken from every configuration, `false` otherwise.
+    /// See `determinism_conflicts` for the transitions breaking the determinism.
+    pub fn is_deterministic(&self) -> bool {
+        self.determinism_conflicts().is_empty()
+    }
+
+    /// Formats a transition as a line of a `pda` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The transition to format
+    ///
+    /// # Returns
+    ///
+    /// A string with the transition, using the blank symbol for ε-moves and pops.
+    pub fn transition_line(&self, t: &Transition) -> String {
+        let push = if t.push.is_empty() {
+            self.blank_symbol.clone()
+        } else {
+            t.push.join(" ")
+        };
+        format!(
+            "{} {} {} {} {}",
+            t.state,
+            t.symbol.as_ref().unwrap_or(&self.blank_symbol),
+            t.top,
+            t.new_state,
+            push
+        )
+    }
+
+    /// Simulates the automaton on the given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of strings representing the input symbols
+    /// * `max_steps` - Maximum number of moves (deterministic automata) or of configurations
+    ///   to expand (non-deterministic automata)
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing either:
+    /// - Ok with a `SimulationResult` containing:
+    ///   - The outcome: `Accept`, `Reject` or `Timeout`
+    ///   - The number of symbols read
+    ///   - The input, unchanged (an automaton never writes)
+    ///   - Number of moves made or configurations expanded
+    ///   - Vector of computation history, one event for each configuration with its state, the
+    ///     input left and its stack, whose top is on the right
+    /// - Err with an error message if simulation fails
+    ///
+    /// # Notes
+    ///
+    /// - Deterministic automata follow their single computation path, see `simulate_deterministic`
+    /// - Non-deterministic automata explore their configurations breadth-first, see
+    ///   `simulate_nondeterministic`
+    pub fn simulate(
+        &self,
+        input: Vec<String>,
+        max_steps: usize,
+    ) -> Result<computer::SimulationResult, String> {
+        if self.is_deterministic() {
+            self.simulate_deterministic(input, max_steps)
+        } else {
+            self.simulate_nondeterministic(input, max_steps)
+        }
+    }
+
+    /// Simulates a deterministic automaton on the given input, following its only computation path.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of strings representing the input symbols
+    /// * `max_steps` - Maximum number of moves to make
+    ///
+    /// # Returns
+    ///
+    /// The same `SimulationResult` as `simulate`.
+    ///
+    /// # Notes
+    ///
+    /// - The moves are looked up in a table indexed by state, input symbol and top of the stack,
+    ///   so each move takes constant time, besides recording the computation
+    /// - The automaton is assumed to be deterministic: ε-moves are preferred over moves reading
+    ///   the input, and only the first matching transition is used
+    fn simulate_deterministic(
+        &self,
+        input: Vec<String>,
+        max_steps: usize,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut moves = std::collections::HashMap::new();
+        for t in self.transitions.iter() {
+            moves
+                .entry((t.state.as_str(), t.symbol.as_ref(), t.top.as_str()))
+                .or_insert(t);
+        }
+        let mut state = self.initial_state.clone();
+        let mut position = 0;
+        let mut stack = vec![self.blank_symbol.clone()];
+        let mut steps = 0;
+        let mut computation = Vec::new();
+        let result = loop {
+            computation.push(trace::TraceEvent::Pda {
+                depth: 0,
+                state: state.clone(),
+                input: input[position..].join(""),
+                stack: stack.clone(),
+            });
+            if position == input.len() && self.is_accepting(&state, &stack) {
+                break computer::Outcome::Accept;
+            }
+            let Some(top) = self.top(&stack).cloned() else {
+                break computer::Outcome::Reject;
+            };
+            let t = match moves.get(&(state.as_str(), None, top.as_str())) {
+                Some(t) => t,
+                None => match moves.get(&(state.as_str(), input.get(position), top.as_str())) {
+                    Some(t) => t,
+                    None => break computer::Outcome::Reject,
+                },
+            };
+            if steps >= max_steps {
+                break computer::Outcome::Timeout;
+            }
+            steps += 1;
+            stack.pop();
+            stack.extend(t.push.iter().cloned());
+            position += usize::from(t.symbol.is_some());
+            state = t.new_state.clone();
+        };
+        Ok(computer::SimulationResult {
+            outcome: result,
+            head: position,
+            tape: input,
+            steps,
+            computation,
+        })
+    }
+
+    /// Simulates a non-deterministic automaton on the given input, exploring its configurations.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of strings representing the input symbols
+    /// * `max_steps` - Maximum number of configurations to expand
+    ///
+    /// # Returns
+    ///
+    /// The same `SimulationResult` as `simulate`.
+    ///
+    /// # Notes
+    ///
+    /// - The configurations are explored breadth-first and each of them is expanded once, so
+    ///   ε-moves looping back to the same configuration end, while ε-moves growing the stack
+    ///   forever end with a timeout
+    /// - When the input is accepted the computation leads to the accepting configuration,
+    ///   otherwise to the last configuration explored
+    fn simulate_nondeterministic(
+        &self,
+        input: Vec<String>,
+        max_steps: usize,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut arena = vec![Configuration {
+            state: self.initial_state.clone(),
+            position: 0,
+            stack: vec![self.blank_symbol.clone()],
+            parent: None,
+        }];
+        let mut visited = std::collections::HashSet::new();
+        visited.insert((self.initial_state.clone(), 0, arena[0].stack.clone()));
+        let mut queue = std::collections::VecDeque::from([0]);
+        let mut steps = 0;
+        let result = loop {
+            let Some(index) = queue.pop_front() else {
+                break (computer::Outcome::Reject, arena.len() - 1);
+            };
+            let configuration = &arena[index];
+            if configuration.position == input.len()
+                && self.is_accepting(&configuration.state, &configuration.stack)
+            {
+                break (computer::Outcome::Accept, index);
+            }
+            if steps >= max_steps {
+                break (computer::Outcome::Timeout, index);
+            }
+            steps += 1;
+            for t in self.transitions.iter() {
+                let configuration = &arena[index];
+                if t.state != configuration.state
+                    || self.top(&configuration.stack) != Some(&t.top)
+                    || t.symbol.is_some() && t.symbol.as_ref() != input.get(configuration.position)
+                {
+                    continue;
+                }
+                let mut stack = configuration.stack.clone();
+                stack.pop();
+                stack.extend(t.push.iter().cloned());
+                let position = configuration.position + usize::from(t.symbol.is_some());
+                if visited.insert((t.new_state.clone(), position, stack.clone())) {
+                    arena.push(Configuration {
+                        state: t.new_state.clone(),
+                        position,
+                        stack,
+                        parent: Some(index),
+                    });
+                    queue.push_back(arena.len() - 1);
+                }
+            }
+        };
+
+        let (outcome, mut index) = result;
+        let mut computation = Vec::new();
+        loop {
+            let configuration = &arena[index];
+            computation.push(trace::TraceEvent::Pda {
+                depth: 0,
+                state: configuration.state.clone(),
+                input: input[configuration.position..].join(""),
+                stack: configuration.stack.clone(),
+            });
+            match configuration.parent {
+                Some(parent) => index = parent,
+                None => break,
+            }
+        }
+        computation.reverse();
+        let read = arena[result.1].position;
+        Ok(computer::SimulationResult {
+            outcome,
+            head: read,
+            tape: input,
+            steps,
+            computation,
+        })
+    }
+
+    /// Converts the automaton into an equivalent 2-tape Turing machine, whose second tape holds the stack.
+    ///
+    /// # Returns
+    ///
+    /// A `TuringMachine` which:
+    /// - starts in a fresh initial state that skips the leading blank and writes a fresh bottom
+    ///   symbol, standing for the blank symbol initially on the stack
+    /// - consumes a symbol moving right for each move reading the input, overwriting it with a blank
+    /// - pops the stack by writing a blank and moving left, and pushes a string one symbol at a
+    ///   time through auxiliary states; the blank cells below the bottom symbol stand for the
+    ///   blank symbols of a bottomless stack
+    /// - enters a fresh halting state when the input is over and the automaton accepts
+    pub fn to_tm(&self) -> turing_machine::TuringMachine {
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.tape_count = 2;
+        tm.blank_symbol = self.blank_symbol.clone();
+        let blank = self.blank_symbol.clone();
+        let mut bottom = blank.clone() + "0";
+        while self.stack_alphabet.contains(&bottom) || self.input_alphabet.contains(&bottom) {
+            bottom += "0";
+        }
+        // the blank symbol on the stack of the automaton is the bottom symbol of the machine
+        let on_tape = |symbol: &String| {
+            if *symbol == blank {
+                bottom.clone()
+            } else {
+                symbol.clone()
+            }
+        };
+        use turing_machine::Direction::{Left, Right, Stay};
+
+        let mut initial_state = self.initial_state.clone() + "_init";
+        while self.states.contains(&initial_state) {
+            initial_state += "_"
+        }
+        tm.initial_state = initial_state.clone();
+        tm.add_transition(
+            initial_state.clone(),
+            vec![blank.clone(), blank.clone()],
+            self.initial_state.clone(),
+            vec![blank.clone(), bottom.clone()],
+            vec![Right, Stay],
+        );
+        tm.states = self.states.clone();
+        tm.states.push(initial_state);
+
+        let mut final_state = "final".to_string();
+        while tm.states.contains(&final_state) {
+            final_state += "_"
+        }
+        tm.halt_state = final_state.clone();
+        tm.states.push(final_state.clone());
+        let accepting: Vec<(&String, String)> = match self.acceptance {
+            Acceptance::FinalState => self
+                .final_states
+                .iter()
+                .flat_map(|s| {
+                    self.stack_alphabet
+                        .iter()
+                        .map(&on_tape)
+                        .chain([bottom.clone(), blank.clone()])
+                        .map(move |top| (s, top))
+                })
+                .collect(),
+            Acceptance::EmptyStack => self.states.iter().map(|s| (s, blank.clone())).collect(),
+        };
+        for (state, top) in accepting {
+            tm.add_transition(
+                state.clone(),
+                vec![blank.clone(), top.clone()],
+                final_state.clone(),
+                vec![blank.clone(), top],
+                vec![Stay, Stay],
+            );
+        }
+
+        tm.input_alphabet = self.input_alphabet.clone();
+        tm.tape_alphabet = self.input_alphabet.clone();
+        for symbol in self
+            .stack_alphabet
+            .iter()
+            .map(&on_tape)
+            .chain([bottom.clone()])
+        {
+            if !tm.tape_alphabet.contains(&symbol) {
+                tm.tape_alphabet.push(symbol);
+            }
+        }
+
+        for (index, t) in self.transitions.iter().enumerate() {
+            // the input symbols under the head: the symbol read, or any symbol for ε-moves
+            let reads: Vec<String> = match &t.symbol {
+                Some(symbol) => vec![symbol.clone()],
+                None => self
+                    .input_alphabet
+                    .iter()
+                    .cloned()
+                    .chain([blank.clone()])
+                    .collect(),
+            };
+            let push: Vec<String> = t.push.iter().map(&on_tape).collect();
+            let tops = if self.bottomless && t.top == blank {
+                vec![bottom.clone(), blank.clone()]
+            } else {
+                vec![on_tape(&t.top)]
+            };
+            for (read, top) in reads
+                .iter()
+                .flat_map(|read| tops.iter().map(move |top| (read.clone(), top.clone())))
+            {
+                let written = if t.symbol.is_some() {
+                    blank.clone()
+                } else {
+                    read.clone()
+                };
+                let input_direction = if t.symbol.is_some() { Right } else { Stay };
+                if push.is_empty() {
+                    tm.add_transition(
+                        t.state.clone(),
+                        vec![read, top],
+                        t.new_state.clone(),
+                        vec![written, blank.clone()],
+                        vec![input_direction, Left],
+                    );
+                    continue;
+                }
+                let mut state = t.state.clone();
+                let mut symbols = vec![read, top];
+                for (i, symbol) in push.iter().enumerate() {
+                    let last = i == push.len() - 1;
+                    let new_state = if last {
+                        t.new_state.clone()
+                    } else {
+                        format!("{}_aux_{}_{}", t.new_state, index, i)
+                    };
+                    if !last && !tm.states.contains(&new_state) {
+                        tm.states.push(new_state.clone());
+                    }
+                    tm.add_transition(
+                        state,
+                        symbols,
+                        new_state.clone(),
+                        vec![written.clone(), symbol.clone()],
+                        vec![
+                            if last { input_direction.clone() } else { Stay },
+                            if last { Stay } else { Right },
+                        ],
+                    );
+                    state = new_state;
+                    symbols = vec![written.clone(), blank.clone()];
+                }
+            }
+        }
+        tm
+    }
+
+    /// Builds a grammar generating the words accepted by the automaton, with the triple construction.
+    ///
+    /// The automaton is first made to accept by empty stack: a new bottom symbol `⊥` is placed
+    /// under the initial blank symbol, and a new state drains the stack from the accepting
+    /// configurations; the moves of a bottomless automaton reading a blank symbol can also read
+    /// `⊥`, keeping it below the symbols they push. Then, for every move from `p` to `q` reading `a` and replacing `X` with
+    /// `Y1 ... Yk` (`Y1` on the top), and for every choice of states `r1, ..., rk`, the production
+    /// `[p,X,rk] -> a [q,Y1,r1] [r1,Y2,r2] ... [rk-1,Yk,rk]` is added. The useless symbols are
+    /// removed at the end.
+    ///
+    /// # Returns
+    ///
+    /// A grammar whose start symbol `S`, followed by underscores if it is an input symbol,
+    /// derives the words accepted by the automaton.
+    pub fn to_cfg(&self) -> grammar::Grammar {
+        let fresh = |base: &str| {
+            let mut name = base.to_string();
+            while self.states.contains(&name) || self.stack_alphabet.contains(&name) {
+                name.push('_');
+            }
+            name
+        };
+        let bottom = fresh("⊥");
+        let drain = fresh("drain");
+        let mut states = self.states.clone();
+        states.push(drain.clone());
+        let mut stack_alphabet = self.stack_alphabet.clone();
+        for symbol in [&self.blank_symbol, &bottom] {
+            if !stack_alphabet.contains(symbol) {
+                stack_alphabet.push(symbol.clone());
+            }
+        }
+
+        // the automaton accepting by empty stack
+        let mut moves = self.clone();
+        let draining: Vec<&String> = match self.acceptance {
+            Acceptance::FinalState => self.final_states.iter().chain([&drain]).collect(),
+            Acceptance::EmptyStack => vec![&drain],
+        };
+        for state in draining {
+            for top in stack_alphabet.iter() {
+                moves.add_transition(state, None, top, &drain, Vec::new());
+            }
+        }
+        if self.bottomless {
+            for t in self.transitions.iter().filter(|t| t.top == self.blank_symbol) {
+                let push = [vec![bottom.clone()], t.push.clone()].concat();
+                moves.add_transition(&t.state, t.symbol.clone(), &bottom, &t.new_state, push);
+            }
+        }
+        if self.acceptance == Acceptance::EmptyStack {
+            for state in self.states.iter() {
+                moves.add_transition(state, None, &bottom, &drain, Vec::new());
+            }
+        }
+
+        let triple = |p: &str, x: &str, q: &str| format!("[{},{},{}]", p, x, q);
+        let mut g = grammar::Grammar::new();
+        // the triples are enclosed in brackets, so only the input symbols can clash with `S`
+        g.start_symbol = "S".to_string();
+        while self.input_alphabet.contains(&g.start_symbol) {
+            g.start_symbol.push('_');
+        }
+        for r in states.iter() {
+            g.add_production(
+                g.start_symbol.clone(),
+                vec![
+                    triple(&self.initial_state, &self.blank_symbol, r),
+                    triple(r, &bottom, &drain),
+                ],
+            );
+        }
+        for t in moves.transitions {
+            // every sequence of states r1, ..., rk, starting from the new state
+            let mut sequences: Vec<Vec<String>> = vec![vec![t.new_state.clone()]];
+            for _ in t.push.iter() {
+                sequences = sequences
+                    .into_iter()
+                    .flat_map(|sequence| {
+                        states
+                            .iter()
+                            .map(move |r| [sequence.clone(), vec![r.clone()]].concat())
+                    })
+                    .collect();
+            }
+            for sequence in sequences {
+                let mut body: Vec<String> = t.symbol.iter().cloned().collect();
+                for (i, y) in t.push.iter().rev().enumerate() {
+                    body.push(triple(&sequence[i], y, &sequence[i + 1]));
+                }
+                g.add_production(triple(&t.state, &t.top, &sequence[t.push.len()]), body);
+            }
+        }
+        // the triples without productions are nonterminals deriving nothing, not terminals
+        g.nonterminals = vec![g.start_symbol.clone()];
+        for p in states.iter() {
+            for x in stack_alphabet.iter() {
+                for q in states.iter() {
+                    g.nonterminals.push(triple(p, x, q));
+                }
+            }
+        }
+        g.remove_useless_symbols()
+    }
+}
+
+/// A builder of pushdown automata, constructing them in code instead of reading them from files.
+///
+/// The states are collected from the initial state, the transitions and the final states, in
+/// the order in which they appear. The input and stack alphabets are completed with the symbols
+/// of the transitions, the stack alphabet with the blank symbol too.
+///
+/// # Fields
+///
+/// * `automaton` - The automaton built so far
+#[derive(Clone)]
+pub struct PushdownAutomatonBuilder {
+    automaton: PushdownAutomaton,
+}
+
+impl Default for PushdownAutomatonBuilder {
+    fn default() -> Self {
+        PushdownAutomatonBuilder::new()
+    }
+}
+
+impl PushdownAutomatonBuilder {
+    /// Creates a builder for an automaton accepting by final state, with `_` as blank symbol.
+    pub fn new() -> Self {
+        PushdownAutomatonBuilder {
+            automaton: PushdownAutomaton::new(),
+        }
+    }
+
+    /// Sets the initial state.
+    pub fn initial_state(mut self, state: &str) -> Self {
+        self.automaton.initial_state = state.to_string();
+        self
+    }
+
+    /// Adds accepting states.
+    pub fn final_states(mut self, states: &[&str]) -> Self {
+        for state in states {
+            if !self.automaton.final_states.iter().any(|s| s == state) {
+                self.automaton.final_states.push(state.to_string());
+            }
+        }
+        self
+    }
+
+    /// Sets the blank symbol, initially on the stack.
+    pub fn blank_symbol(mut self, symbol: &str) -> Self {
+        self.automaton.blank_symbol = symbol.to_string();
+        self
+    }
+
+    /// Sets the acceptance mode.
+    pub fn acceptance(mut self, acceptance: Acceptance) -> Self {
+        self.automaton.acceptance = acceptance;
+        self
+    }
+
+    /// Places unlimited blank symbols below the stack.
+    pub fn bottomless(mut self) -> Self {
+        self.automaton.bottomless = true;
+        self
+    }
+
+    /// Adds symbols to the input alphabet.
+    pub fn input_alphabet(mut self, symbols: &[&str]) -> Self {
+        for symbol in symbols {
+            if !self.automaton.input_alphabet.iter().any(|s| s == symbol) {
+                self.automaton.input_alphabet.push(symbol.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds symbols to the stack alphabet.
+    pub fn stack_alphabet(mut self, symbols: &[&str]) -> Self {
+        for symbol in symbols {
+            if !self.automaton.stack_alphabet.iter().any(|s| s == symbol) {
+                self.automaton.stack_alphabet.push(symbol.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds a move.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state the move starts from
+    /// * `symbol` - The input symbol read, or `None` for an ε-move
+    /// * `top` - The symbol on the top of the stack
+    /// * `new_state` - The state the move leads to
+    /// * `push` - The symbols replacing the top of the stack, from the bottom to the new top
+    pub fn transition(
+        mut self,
+        state: &str,
+        symbol: Option<&str>,
+        top: &str,
+        new_state: &str,
+        push: &[&str],
+    ) -> Self {
+        self.automaton.add_transition(
+            state,
+            symbol.map(String::from),
+            top,
+            new_state,
+            push.iter().map(|s| s.to_string()).collect(),
+        );
+        self
+    }
+
+    /// Builds the automaton.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PushdownAutomaton)` - The automaton, with its states and alphabets completed
+    /// * `Err(String)` - An error message if the initial state is missing
+    pub fn build(self) -> Result<PushdownAutomaton, String> {
+        let mut pda = self.automaton;
+        if pda.initial_state.is_empty() {
+            return Err("the initial state is not set".to_string());
+        }
+        let mut states = vec![pda.initial_state.clone()];
+        let mut input = Vec::new();
+        let mut stack = vec![pda.blank_symbol.clone()];
+        for t in pda.transitions.iter() {
+            states.push(t.state.clone());
+            states.push(t.new_state.clone());
+            input.extend(t.symbol.iter().cloned());
+            stack.push(t.top.clone());
+            stack.extend(t.push.iter().cloned());
+        }
+        states.extend(pda.final_states.iter().cloned());
+        for state in states {
+            if !pda.states.contains(&state) {
+                pda.states.push(state);
+            }
+        }
+        for symbol in input {
+            if !pda.input_alphabet.contains(&symbol) {
+                pda.input_alphabet.push(symbol);
+            }
+        }
+        for symbol in stack {
+            if !pda.stack_alphabet.contains(&symbol) {
+                pda.stack_alphabet.push(symbol);
+            }
+        }
+        Ok(pda)
+    }
+}
+
+impl std::fmt::Display for PushdownAutomaton {
+    /// Formats the automaton as a `pda` file.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.acceptance {
+            Acceptance::FinalState if self.bottomless => writeln!(f, "pda")?,
+            Acceptance::FinalState => writeln!(f, "pda final-state")?,
+            Acceptance::EmptyStack => writeln!(f, "pda empty-stack")?,
+        }
+        writeln!(f, "{}", self.initial_state)?;
+        writeln!(f, "{}", self.final_states.join(" "))?;
+        writeln!(f, "{}", self.states.join(" "))?;
+        writeln!(f, "{}", self.input_alphabet.join(" "))?;
+        writeln!(f, "{}", self.stack_alphabet.join(" "))?;
+        write!(f, "{}", self.blank_symbol)?;
+        for t in self.transitions.iter() {
+            write!(f, "\n{}", self.transition_line(t))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    fn word(w: &str) -> Vec<String> {
+        w.chars().map(|c| c.to_string()).collect()
+    }
+
+    fn example() -> PushdownAutomaton {
+        let text = std::fs::read_to_string("examples/0n1m2m3n.pda").unwrap();
+        let all: Vec<String> = text
+            .lines()
+            .filter(|l| !l.starts_with("//"))
+            .map(String::from)
+            .collect();
+        let mut pda = PushdownAutomaton::from_lines(&all[1..], Acceptance::FinalState).unwrap();
+        pda.bottomless = all[0] == "pda";
+        pda
+    }
+
+    fn in_example_language(w: &str) -> bool {
+        let counts: Vec<usize> = "0123"
+            .chars()
+            .map(|d| w.chars().filter(|c| *c == d).count())
+            .collect();
+        let sorted = w
+            .chars()
+            .collect::<Vec<char>>()
+            .windows(2)
+            .all(|p| p[0] <= p[1]);
+        sorted && counts[0] == counts[3] && counts[1] == counts[2] && counts[0] > 0 && counts[1] > 0
+    }
+
+    const EXAMPLE_WORDS: [&str; 9] = [
+        "0123",
+        "00112233",
+        "01122233",
+        "0011122233",
+        "",
+        "012",
+        "0011223",
+        "0213",
+        "1023",
+    ];
+
+    #[test]
+    fn test_from_lines() {
+        let pda = example();
+        assert_eq!(pda.initial_state, "0");
+        assert_eq!(pda.final_states, vec!["5"]);
+        assert_eq!(pda.transitions.len(), 11);
+        assert_eq!(pda.transitions[0].symbol, None);
+        assert_eq!(pda.transitions[0].push, vec!["B", "B"]);
+        assert_eq!(pda.transitions[1].push, Vec::<String>::new());
+        let text = pda.to_string();
+        assert!(text.starts_with("pda\n0\n5"));
+        let read =
+            |text: &str, acceptance| PushdownAutomaton::from_lines(&lines(text)[1..], acceptance);
+        let back = read(&text, Acceptance::FinalState).unwrap();
+        assert_eq!(PushdownAutomaton { bottomless: true, ..back.clone() }, pda);
+        assert!(back.to_string().starts_with("pda final-state\n0\n5"));
+        assert!(read("pda\n0\n1\n0 1\na\nA\n_", Acceptance::FinalState).is_ok());
+        assert!(read("pda\n0\n1\n0 1\na\nA", Acceptance::FinalState).is_err());
+        assert!(read("pda\n0\n1\n0 1\na\nA\n_\n0 a A", Acceptance::FinalState).is_err());
+        let pda = read(
+            "pda\n0\n\n0\na\nA\n_\n0 a _ 0 _ A A A",
+            Acceptance::EmptyStack,
+        )
+        .unwrap();
+        assert_eq!(pda.transitions[0].push.len(), 4);
+        assert!(pda.to_string().starts_with("pda empty-stack\n"));
+    }
+
+    #[test]
+    fn test_simulate() {
+        let pda = example();
+        for w in EXAMPLE_WORDS {
+            let computer::SimulationResult {
+                outcome: state,
+                head: read,
+                tape,
+                ..
+            } = pda.simulate(word(w), 1000).unwrap();
+            assert_eq!(state == computer::Outcome::Accept, in_example_language(w), "{}", w);
+            assert_eq!(tape, word(w));
+            if state == computer::Outcome::Accept {
+                assert_eq!(read, w.len());
+            }
+        }
+        let computation = pda.simulate(word("0123"), 1000).unwrap().computation;
+        assert_eq!(computation.len(), 8);
+        assert_eq!(computation[0].to_string(), "pda;0;0123;B");
+        assert_eq!(computation[2].to_string(), "pda;1;0123;B");
+        assert_eq!(computation[3].to_string(), "pda;1;123;0");
+        assert_eq!(computation[4].to_string(), "pda;2;23;0 1");
+        assert_eq!(computation.last().unwrap().to_string(), "pda;5;;");
+        assert_eq!(pda.simulate(word("00112233"), 3).unwrap().outcome, computer::Outcome::Timeout);
+
+        // without blanks below the stack, the last pop leaves nothing to read
+        let bounded = PushdownAutomaton { bottomless: false, ..pda };
+        assert_eq!(
+            bounded.simulate(word("0123"), 1000).unwrap().outcome,
+            computer::Outcome::Reject
+        );
+    }
+
+    #[test]
+    fn test_empty_stack() {
+        let text =
+            "pda empty-stack\np\n\np\na b\nA\n_\np _ _ p _\np a _ p _ A\np a A p A A\np b A p _";
+        let pda = PushdownAutomaton::from_lines(&lines(text)[1..], Acceptance::EmptyStack).unwrap();
+        for (w, expected) in [
+            ("", true),
+            ("ab", true),
+            ("aabb", true),
+            ("aab", false),
+            ("abb", false),
+        ] {
+            assert_eq!(
+                pda.simulate(word(w), 1000).unwrap().outcome == computer::Outcome::Accept,
+                expected,
+                "{}",
+                w
+            );
+        }
+        let mut final_state = pda.clone();
+        final_state.acceptance = Acceptance::FinalState;
+        assert_eq!(
+            final_state.simulate(word(""), 1000).unwrap().outcome,
+            computer::Outcome::Reject
+        );
+    }
+
+    #[test]
+    fn test_determinism() {
+        let pda = example();
+        assert!(pda.is_deterministic());
+        for w in EXAMPLE_WORDS {
+            let deterministic = pda.simulate_deterministic(word(w), 1000).unwrap();
+            let nondeterministic = pda.simulate_nondeterministic(word(w), 1000).unwrap();
+            assert_eq!(deterministic.outcome, nondeterministic.outcome, "{}", w);
+            if deterministic.outcome == computer::Outcome::Accept {
+                assert_eq!(deterministic.computation, nondeterministic.computation, "{}", w);
+            }
+        }
+        let text =
+            "pda\np\nq\np q\na\nA\n_\np a _ p _ A\np a A p A A\np _ A q A\nq a A q _\nq a _ q _";
+        let pda = PushdownAutomaton::from_lines(&lines(text)[1..], Acceptance::FinalState).unwrap();
+        assert!(!pda.is_deterministic());
+        assert_eq!(pda.determinism_conflicts(), vec![(1, 2)]);
+        assert_eq!(pda.transition_line(&pda.transitions[2]), "p _ A q A");
+        assert_eq!(pda.transition_line(&pda.transitions[3]), "q a A q _");
+    }
+
+    #[test]
+    fn test_to_tm() {
+        let pda = example();
+        let tm = pda.to_tm();
+        assert_eq!(tm.tape_count, 2);
+        let server = computer::Server::new();
+        let mut c = computer::Computer::new();
+        c.set_turing(tm);
+        for w in ["0123", "00112233", "012", "0213"] {
+            let state = c.simulate(w, 1000, &server, 0).unwrap().outcome;
+            assert_eq!(state == computer::Outcome::Halt, in_example_language(w), "{}", w);
+        }
+        let tm = PushdownAutomaton::from_cfg(
+            &grammar::Grammar::from_lines(&lines("S -> a b c")).unwrap(),
+        )
+        .to_tm();
+        assert!(tm.states.iter().any(|s| s.contains("_aux_")));
+    }
+
+    #[test]
+    fn test_from_cfg() {
+        let g = grammar::Grammar::from_lines(&lines("E -> T + E | T\nT -> ( E ) | x")).unwrap();
+        let pda = PushdownAutomaton::from_cfg(&g);
+        assert_eq!(pda.states, vec!["loop"]);
+        assert_eq!(pda.acceptance, Acceptance::EmptyStack);
+        for (w, expected) in [
+            ("x", true),
+            ("x+(x+x)", true),
+            ("", false),
+            ("x+", false),
+            ("(x", false),
+        ] {
+            let accepted = pda.simulate(word(w), 1000).unwrap().outcome
+                == computer::Outcome::Accept;
+            assert_eq!(accepted, expected, "{}", w);
+        }
+        let text = pda.to_string();
+        let back =
+            PushdownAutomaton::from_lines(&lines(&text)[1..], Acceptance::EmptyStack).unwrap();
+        assert_eq!(back, pda);
+    }
+
+    #[test]
+    fn test_to_cfg() {
+        let g = example().to_cfg();
+        assert_eq!(g.start_symbol, "S");
+        assert!(g
+            .productions
+            .iter()
+            .flat_map(|p| p.body.iter())
+            .all(|s| g.is_nonterminal(s) || s.len() == 1));
+        for w in EXAMPLE_WORDS {
+            assert_eq!(
+                g.earley(&word(w)).is_some(),
+                in_example_language(w),
+                "{}",
+                w
+            );
+        }
+
+        // the start symbol must not be taken for the input symbol S
+        let pda = PushdownAutomaton::builder()
+            .initial_state("q")
+            .acceptance(Acceptance::EmptyStack)
+            .transition("q", Some("S"), "_", "q", &[])
+            .build()
+            .unwrap();
+        let g = pda.to_cfg();
+        assert_eq!(g.start_symbol, "S_");
+        assert!(g.earley(&word("S")).is_some());
+        assert!(g.earley(&word("SS")).is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let g = grammar::Grammar::from_lines(&lines("S -> a S b | ε")).unwrap();
+        let text = PushdownAutomaton::from_cfg(&g).to_cfg().to_string();
+        let g = grammar::Grammar::from_lines(&lines(&text)[1..]).unwrap();
+        for (w, expected) in [("", true), ("aabb", true), ("aab", false), ("ba", false)] {
+            assert_eq!(g.earley(&word(w)).is_some(), expected, "{}", w);
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        let pda = PushdownAutomaton::builder()
+            .initial_state("0")
+            .final_states(&["5"])
+            .blank_symbol("B")
+            .bottomless()
+            .input_alphabet(&["0", "1", "2", "3"])
+            .stack_alphabet(&["0", "1", "2", "3", "B"])
+            .transition("0", None, "B", "0a", &["B", "B"])
+            .transition("0a", None, "B", "1", &[])
+            .transition("1", Some("0"), "B", "1", &["0"])
+            .transition("1", Some("0"), "0", "1", &["0", "0"])
+            .transition("1", Some("1"), "0", "2", &["0", "1"])
+            .transition("2", Some("1"), "1", "2", &["1", "1"])
+            .transition("2", Some("2"), "1", "3", &[])
+            .transition("3", Some("2"), "1", "3", &[])
+            .transition("3", Some("3"), "0", "4", &[])
+            .transition("4", Some("3"), "0", "4", &[])
+            .transition("4", None, "B", "5", &[])
+            .build()
+            .unwrap();
+        assert_eq!(pda, example());
+
+        let pda = PushdownAutomaton::builder()
+            .initial_state("q")
+            .acceptance(Acceptance::EmptyStack)
+            .transition("q", Some("a"), "_", "q", &[])
+            .build()
+            .unwrap();
+        assert_eq!(pda.states, vec!["q"]);
+        assert_eq!(pda.input_alphabet, vec!["a"]);
+        assert_eq!(pda.stack_alphabet, vec!["_"]);
+        assert_eq!(
+            pda.simulate(word("a"), 100).unwrap().outcome,
+            computer::Outcome::Accept
+        );
+
+        assert!(PushdownAutomaton::builder().build().is_err());
+    }
+}
@@ -41,6 +41,10 @@
 //! * labels_map: Mapping of symbolic labels to numeric values
 //! * translation_map: Mapping for optional output symbol translations
 //!
+//! ## RamMachineBuilder
+//! Constructs a program in code, with instructions named as in `ram` files and labels for the
+//! addresses of instructions and data cells.
+//!
 //! ## Instruction
 //! Structure representing a single RAM machine instruction:
 //! * opcode: The operation code (4-bit binary string)
@@ -62,6 +66,8 @@
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
 use crate::computer;
+use crate::loop_detector;
+use crate::trace;
 use crate::utils;
 
 /// A Random Access Machine (RAM) implementation representing a computational model.
@@ -96,6 +102,28 @@ pub struct RamMachine {
     pub translation_map: std::collections::HashMap<String, String>,
 }
 
+/// The configuration of a RAM machine during its execution.
+///
+/// # Fields
+///
+/// * `pc` - The program counter, in binary
+/// * `acc` - The accumulator, in binary
+/// * `mov` - The move register, in binary
+/// * `input` - The input, extended with zeros when the input head moves past its start
+/// * `input_head` - The position of the input head
+/// * `memory` - The memory, with the program loaded from address 0
+/// * `out` - The output written so far
+#[derive(Clone, Debug, PartialEq)]
+pub struct RamConfiguration {
+    pub pc: String,
+    pub acc: String,
+    pub mov: String,
+    pub input: String,
+    pub input_head: usize,
+    pub memory: std::collections::HashMap<String, String>,
+    pub out: String,
+}
+
 /// A structure representing a single instruction in the RAM machine.
 ///
 /// # Fields
@@ -117,6 +145,11 @@ pub struct Instruction {
 }
 
 impl RamMachine {
+    /// Returns a builder constructing a RAM machine in code.
+    pub fn builder() -> RamMachineBuilder {
+        RamMachineBuilder::new()
+    }
+
     /// Checks if a given instruction string is a valid RAM machine instruction.
     ///
     /// # Arguments
@@ -170,45 +203,50 @@ impl RamMachine {
         Ok(opcode.to_string())
     }
 
-    /// Simulates the execution of the RAM machine.
+    /// Converts a 4-bit opcode to the name of its RAM machine instruction.
     ///
     /// # Arguments
     ///
-    /// * `self` - The RAM machine instance
-    /// * `input` - Input string to process
-    /// * `max_steps` - Maximum number of simulation steps
-    /// * `this_computer_object` - Reference to the current computer object
-    /// * `context` - Server context for handling subroutine calls
+    /// * `opcode` - A string slice containing the opcode to convert
     ///
     /// # Returns
     ///
-    /// Returns a Result containing a tuple with:
-    /// * Final state ("halt" or "reject")
-    /// * Final position (always 0 for RAM machines)
-    /// * Output vector
-    /// * Number of steps executed
-    /// * Computation history vector
+    /// Returns a String containing the instruction name.
     ///
-    /// # Errors
+    pub fn ram_opcode_lookup(opcode: &str) -> Result<String, String> {
+        let instruction = match opcode {
+            "0000" => "R",
+            "0001" => "MIR",
+            "0010" => "MIL",
+            "0011" => "W",
+            "0100" => "L",
+            "0101" => "A",
+            "0110" => "S",
+            "0111" => "INIT",
+            "1000" => "ST",
+            "1001" => "JUMP",
+            "1010" => "CJUMP",
+            "1011" => "H",
+            "1100" => "CALL",
+            "1101" => "MOV",
+            "1110" => "LD",
+            "1111" => "STD",
+            _ => return Err(format!("Unknown RAM opcode: '{}'", opcode)),
+        };
+        Ok(instruction.to_string())
+    }
+
+    /// Builds the initial configuration of the RAM machine, with its program loaded in memory.
     ///
-    /// Returns an error string if:
-    /// * Invalid memory access occurs
-    /// * Binary conversion fails
-    /// * Subroutine calls fail
-    pub fn simulate(
-        &self,
-        mut input: String,
-        max_steps: usize,
-        this_computer_object: &computer::Computer,
-        context: &computer::Server,
-    ) -> Result<computer::SimulationResult, String> {
-        let mut ir: String;
-        let mut out: String = "".to_string();
-        let mut pc: String = "0".to_string();
-        let mut acc: String = "0".to_string();
-        let mut ar: String;
-        let mut mov: String = "0".to_string();
-        let mut input_head = 0;
+    /// # Arguments
+    ///
+    /// * `input` - Input string to process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RamConfiguration)` - The configuration before the first step
+    /// * `Err(String)` - An error message if an instruction uses an unknown label
+    pub fn initial_configuration(&self, input: String) -> Result<RamConfiguration, String> {
         let mut memory: std::collections::HashMap<String, String> =
             std::collections::HashMap::new();
         for (index, instr) in self.instructions.iter().enumerate() {
@@ -232,195 +270,325 @@ impl RamMachine {
                 memory.insert(utils::int2bin(index as i32, 0), "0".to_string());
             }
         }
+        Ok(RamConfiguration {
+            pc: "0".to_string(),
+            acc: "0".to_string(),
+            mov: "0".to_string(),
+            input,
+            input_head: 0,
+            memory,
+            out: "".to_string(),
+        })
+    }
+
+    /// Simulates the execution of the RAM machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The RAM machine instance
+    /// * `input` - Input string to process
+    /// * `max_steps` - Maximum number of simulation steps
+    /// * `this_computer_object` - Reference to the current computer object
+    /// * `context` - Server context for handling subroutine calls
+    ///
+    /// # Returns
+    ///
+    /// Returns a Result containing a `SimulationResult` with:
+    /// * The outcome (`Halt`, `Reject`, `Timeout` or, if the context detects loops, `Diverges`
+    ///   with the proof that the computation never halts)
+    /// * Final position (always 0 for RAM machines)
+    /// * Output vector
+    /// * Number of steps executed
+    /// * Computation history vector
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if:
+    /// * Invalid memory access occurs
+    /// * Binary conversion fails
+    /// * Subroutine calls fail
+    pub fn simulate(
+        &self,
+        input: String,
+        max_steps: usize,
+        this_computer_object: &computer::Computer,
+        context: &computer::Server,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut configuration = self.initial_configuration(input)?;
         let mut computation = Vec::new();
         let mut steps = 0;
-        let mut halted = false;
+        let mut cycles = loop_detector::CycleDetector::new();
         while steps < max_steps {
+            if context.simulation.detect_loops {
+                // the output is left out, since it does not affect the next steps
+                let without_output = RamConfiguration {
+                    out: String::new(),
+                    ..configuration.clone()
+                };
+                if let Some(proof) = cycles.observe(steps, &without_output) {
+                    return Ok(computer::SimulationResult {
+                        outcome: computer::Outcome::Diverges(proof),
+                        head: 0,
+                        tape: vec![configuration.out],
+                        steps,
+                        computation,
+                    });
+                }
+            }
             steps += 1;
-            let mem_val = memory
-                .get(&pc)
-                .ok_or_else(|| format!("key not found: {}", pc))?;
-            if mem_val.len() < 4 {
-                return Err(format!(
-                    "Invalid memory value at address {}: '{}' is shorter than 4 bits",
-                    pc, mem_val
-                ));
+            if let Some(outcome) = self.step(
+                &mut configuration,
+                max_steps.saturating_sub(steps),
+                this_computer_object,
+                context,
+                &mut computation,
+            )? {
+                return Ok(computer::SimulationResult {
+                    outcome,
+                    head: 0,
+                    tape: vec![configuration.out],
+                    steps,
+                    computation,
+                });
             }
-            ir = mem_val[0..4].to_string();
-            ar = mem_val[4..].to_string();
-            pc = utils::int2bin(utils::bin2int(pc)? + 1, 0);
-            computation
-                .push(format!("ram;{};{};{}", ir, ar, acc));
-            match ir.as_str() {
-                "0000" => {
-                    // R: Read [operands] bit from input
-                    let end = input_head + (utils::bin2int(ar)? as usize);
-                    if input.len() < end {
-                        let start = std::cmp::min(input_head, input.len());
-                        let slice = input[start..input.len()].to_string();
-                        let value = if slice.is_empty() {
-                            0
-                        } else {
-                            utils::bin2int(slice)?
-                        };
-                        acc = format!(
-                            "{:0>width$b}",
-                            value,
-                            width = end - input_head
-                        )
-                    } else {
-                        acc = input[input_head..end].to_string();
-                    }
-                }
-                "0001" => {
-                    // MIR: move input head [operands] bits to the right
-                    input_head += utils::bin2int(ar)? as usize;
-                }
-                "0010" => {
-                    // MIL: move input head [operands] bits to the left
-                    let to_sub = utils::bin2int(ar)? as usize;
-                    if input_head >= to_sub {
-                        input_head -= to_sub;
+        }
+        Ok(computer::SimulationResult {
+            outcome: computer::Outcome::Timeout,
+            head: 0,
+            tape: vec![configuration.out],
+            steps,
+            computation,
+        })
+    }
+
+    /// Executes the instruction at the program counter of a configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `configuration` - The configuration to update
+    /// * `max_steps` - Maximum number of simulation steps of the subroutines called
+    /// * `this_computer_object` - Reference to the current computer object
+    /// * `context` - Server context for handling subroutine calls
+    /// * `computation` - Computation history, to which the instruction is added
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - If the machine goes on
+    /// * `Ok(Some(Outcome))` - The outcome (`Halt` or `Reject`) if the machine stops
+    /// * `Err(String)` - An error message if the instruction cannot be executed
+    pub fn step(
+        &self,
+        configuration: &mut RamConfiguration,
+        max_steps: usize,
+        this_computer_object: &computer::Computer,
+        context: &computer::Server,
+        computation: &mut Vec<trace::TraceEvent>,
+    ) -> Result<Option<computer::Outcome>, String> {
+        let RamConfiguration {
+            pc,
+            acc,
+            mov,
+            input,
+            input_head,
+            memory,
+            out,
+        } = configuration;
+        let mem_val = memory
+            .get(pc.as_str())
+            .ok_or_else(|| format!("key not found: {}", pc))?;
+        if mem_val.len() < 4 {
+            return Err(format!(
+                "Invalid memory value at address {}: '{}' is shorter than 4 bits",
+                pc, mem_val
+            ));
+        }
+        let ir = mem_val[0..4].to_string();
+        let ar = mem_val[4..].to_string();
+        let address = utils::bin2int(pc.clone())?;
+        *pc = utils::int2bin(address + 1, 0);
+        if context.simulation.trace {
+            let mut cells = std::collections::BTreeMap::new();
+            for (cell, value) in memory.iter() {
+                cells.insert(utils::bin2int(cell.clone())? as usize, value.clone());
+            }
+            computation.push(trace::TraceEvent::Ram {
+                depth: 0,
+                pc: address as usize,
+                ir: ir.clone(),
+                ar: ar.clone(),
+                acc: acc.clone(),
+                mov: mov.clone(),
+                input_head: *input_head,
+                memory: cells,
+                output: out.clone(),
+            });
+        }
+        match ir.as_str() {
+            "0000" => {
+                // R: Read [operands] bit from input
+                let end = *input_head + (utils::bin2int(ar)? as usize);
+                if input.len() < end {
+                    let start = std::cmp::min(*input_head, input.len());
+                    let slice = input[start..input.len()].to_string();
+                    let value = if slice.is_empty() {
+                        0
                     } else {
-                        let zeros = "0".repeat(to_sub - input_head);
-                        input = zeros + &input;
-                        input_head = 0;
-                    }
-                }
-                "0011" => {
-                    // W: Write ACC to output
-                    out.push_str(&acc);
-                }
-                "0100" => {
-                    // L: Load AR to ACC
-                    if !memory.contains_key(&ar) {
-                        memory.insert(ar.clone(), "0".to_string());
-                    }
-                    acc = memory
-                        .get(&ar)
-                        .ok_or(format!("key not found: {}", ar))?
-                        .clone();
-                }
-                "0101" => {
-                    // A: Add AR to ACC
-                    if !memory.contains_key(&ar) {
-                        memory.insert(ar.clone(), "0".to_string());
-                    }
-                    acc = utils::int2bin(
-                        utils::bin2int(acc)?
-                            + utils::bin2int(
-                                memory
-                                    .get(&ar)
-                                    .ok_or(format!("key not found: {}", ar))?
-                                    .clone(),
-                            )?,
-                        0,
-                    );
-                }
-                "0110" => {
-                    // S: Subtract AR from ACC
-                    if !memory.contains_key(&ar) {
-                        memory.insert(ar.clone(), "0".to_string());
-                    }
-                    acc = utils::int2bin(
-                        utils::bin2int(acc)?
-                            - (utils::bin2int(
-                                memory
-                                    .get(&ar)
-                                    .ok_or(format!("key not found: {}", ar))?
-                                    .clone(),
-                            )?),
-                        0,
-                    );
+                        utils::bin2int(slice)?
+                    };
+                    *acc = format!(
+                        "{:0>width$b}",
+                        value,
+                        width = end - *input_head
+                    )
+                } else {
+                    *acc = input[*input_head..end].to_string();
                 }
-                "0111" => {
-                    // INIT: Initialize ACC to [operands]
-                    acc = ar.clone();
+            }
+            "0001" => {
+                // MIR: move input head [operands] bits to the right
+                *input_head += utils::bin2int(ar)? as usize;
+            }
+            "0010" => {
+                // MIL: move input head [operands] bits to the left
+                let to_sub = utils::bin2int(ar)? as usize;
+                if *input_head >= to_sub {
+                    *input_head -= to_sub;
+                } else {
+                    let zeros = "0".repeat(to_sub - *input_head);
+                    *input = zeros + input;
+                    *input_head = 0;
                 }
-                "1000" => {
-                    // ST: Store ACC to AR
-                    memory.insert(ar.clone(), acc.clone());
+            }
+            "0011" => {
+                // W: Write ACC to output
+                out.push_str(acc);
+            }
+            "0100" => {
+                // L: Load AR to ACC
+                if !memory.contains_key(&ar) {
+                    memory.insert(ar.clone(), "0".to_string());
                 }
-                "1001" => {
-                    // JUMP: Jump to AR
-                    pc = ar.clone();
+                *acc = memory
+                    .get(&ar)
+                    .ok_or(format!("key not found: {}", ar))?
+                    .clone();
+            }
+            "0101" => {
+                // A: Add AR to ACC
+                if !memory.contains_key(&ar) {
+                    memory.insert(ar.clone(), "0".to_string());
                 }
-                "1010" => {
-                    // CJUMP: Conditional jump to AR if ACC is 0000
-                    if !acc.contains("1") {
-                        pc = ar.clone();
-                    }
+                *acc = utils::int2bin(
+                    utils::bin2int(acc.clone())?
+                        + utils::bin2int(
+                            memory
+                                .get(&ar)
+                                .ok_or(format!("key not found: {}", ar))?
+                                .clone(),
+                        )?,
+                    0,
+                );
+            }
+            "0110" => {
+                // S: Subtract AR from ACC
+                if !memory.contains_key(&ar) {
+                    memory.insert(ar.clone(), "0".to_string());
                 }
-                "1011" => {
-                    // HALT: Halt
-                    halted = true;
-                    break;
+                *acc = utils::int2bin(
+                    utils::bin2int(acc.clone())?
+                        - (utils::bin2int(
+                            memory
+                                .get(&ar)
+                                .ok_or(format!("key not found: {}", ar))?
+                                .clone(),
+                        )?),
+                    0,
+                );
+            }
+            "0111" => {
+                // INIT: Initialize ACC to [operands]
+                *acc = ar.clone();
+            }
+            "1000" => {
+                // ST: Store ACC to AR
+                memory.insert(ar.clone(), acc.clone());
+            }
+            "1001" => {
+                // JUMP: Jump to AR
+                *pc = ar.clone();
+            }
+            "1010" => {
+                // CJUMP: Conditional jump to AR if ACC is 0000
+                if !acc.contains("1") {
+                    *pc = ar.clone();
                 }
-                "1100" => {
-                    // CALL: call a subroutine
-                    let mapping_key = (utils::bin2int(ar.clone())?).to_string();
-                    let mapping = this_computer_object
-                        .get_mapping(&mapping_key)?;
-                    let subroutine = context
-                        .get_computer(&mapping)
-                        .ok_or_else(|| format!("cannot find computer with name '{}'", mapping))?;
-                    let (state, _, tape, _sub_steps, sub_computation) = subroutine.simulate(
-                        &acc,
-                        max_steps.saturating_sub(steps),
-                        context,
-                        0,
-                    )?;
-                    computation.extend(sub_computation);
-                    if state == "accept" || state == "halt" {
-                        match &subroutine.element {
-                            computer::ComputingElem::Tm(m) => {
-                                acc = tape
-                                    .into_iter()
-                                    .filter(|symb| *symb != m.blank_symbol)
-                                    .collect::<Vec<String>>()
-                                    .join("")
-                            }
-                            computer::ComputingElem::Ram(_) => {
-                                acc = tape.join("");
-                            }
-                            computer::ComputingElem::Lambda(_) => {
-                                acc = "0".to_string();
-                            }
+            }
+            "1011" => {
+                // HALT: Halt
+                return Ok(Some(computer::Outcome::Halt));
+            }
+            "1100" => {
+                // CALL: call a subroutine
+                let mapping_key = (utils::bin2int(ar.clone())?).to_string();
+                let mapping = this_computer_object
+                    .get_mapping(&mapping_key)?;
+                let subroutine = context
+                    .get_computer(&mapping)
+                    .ok_or_else(|| format!("cannot find computer with name '{}'", mapping))?;
+                let computer::SimulationResult {
+                    outcome,
+                    tape,
+                    computation: sub_computation,
+                    ..
+                } = subroutine.simulate(acc, max_steps, context, 0)?;
+                computation.extend(sub_computation.into_iter().map(|e| e.nested()));
+                if matches!(outcome, computer::Outcome::Accept | computer::Outcome::Halt) {
+                    match &subroutine.element {
+                        computer::ComputingElem::Tm(m) => {
+                            *acc = tape
+                                .into_iter()
+                                .filter(|symb| *symb != m.blank_symbol)
+                                .collect::<Vec<String>>()
+                                .join("")
                         }
-                    } else {
-                        return Ok(("reject".to_string(), 0, vec![out], steps, computation));
-                    }
-                }
-                "1101" => {
-                    // MOV: copy the value of acc to the mov register
-                    mov = acc.clone();
-                }
-                "1110" => {
-                    // LD: load the memory at address in MOV
-                    if !memory.contains_key(&mov) {
-                        memory.insert(mov.clone(), "0".to_string());
+                        computer::ComputingElem::Ram(_) => {
+                            *acc = tape.join("");
+                        }
+                        computer::ComputingElem::Lambda(_) => {
+                            *acc = "0".to_string();
+                        }
+                        computer::ComputingElem::Fsm(_)
+                        | computer::ComputingElem::Pda(_)
+                        | computer::ComputingElem::Cfg(_) => {}
                     }
-                    acc = memory
-                        .get(&mov)
-                        .ok_or(format!("key not found: {}", mov))?
-                        .clone();
-                }
-                "1111" => {
-                    // STD: store the memory at address in MOV
-                    memory.insert(mov.clone(), acc.clone());
+                } else {
+                    return Ok(Some(computer::Outcome::Reject));
                 }
-                _ => {
-                    // default: Halt
-                    halted = true;
-                    break;
+            }
+            "1101" => {
+                // MOV: copy the value of acc to the mov register
+                *mov = acc.clone();
+            }
+            "1110" => {
+                // LD: load the memory at address in MOV
+                if !memory.contains_key(mov.as_str()) {
+                    memory.insert(mov.clone(), "0".to_string());
                 }
+                *acc = memory
+                    .get(mov.as_str())
+                    .ok_or(format!("key not found: {}", mov))?
+                    .clone();
+            }
+            "1111" => {
+                // STD: store the memory at address in MOV
+                memory.insert(mov.clone(), acc.clone());
+            }
+            _ => {
+                // default: Halt
+                return Ok(Some(computer::Outcome::Halt));
             }
         }
-        if halted {
-            Ok(("halt".to_string(), 0, vec![out], steps, computation))
-        } else {
-            Ok(("timeout".to_string(), 0, vec![out], steps, computation))
-        }
+        Ok(None)
     }
 
     /// Converts the RAM machine to its encoding representation.
@@ -469,6 +637,131 @@ impl RamMachine {
     }
 }
 
+/// A builder of RAM machines, constructing their programs in code instead of reading them from
+/// files.
+///
+/// The instructions are named as in `ram` files, and their operands are either numbers or
+/// labels. A label names the address of an instruction, or of a data cell holding 0 when the
+/// program is loaded.
+///
+/// # Fields
+///
+/// * `machine` - The RAM machine built so far
+/// * `label` - The label of the next instruction, if any
+/// * `error` - The first error found, reported by `build`
+#[derive(Clone)]
+pub struct RamMachineBuilder {
+    machine: RamMachine,
+    label: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for RamMachineBuilder {
+    fn default() -> Self {
+        RamMachineBuilder::new()
+    }
+}
+
+impl RamMachineBuilder {
+    /// Creates a builder for an empty program.
+    pub fn new() -> Self {
+        RamMachineBuilder {
+            machine: RamMachine {
+                instructions: Vec::new(),
+                labels_map: std::collections::HashMap::new(),
+                translation_map: std::collections::HashMap::new(),
+            },
+            label: None,
+            error: None,
+        }
+    }
+
+    /// Names the address of the next instruction.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Adds an instruction without operand, such as `H` or `W`.
+    pub fn instruction(self, name: &str) -> Self {
+        self.push(name, "0".to_string(), String::new())
+    }
+
+    /// Adds an instruction with a numeric operand.
+    pub fn instruction_with_operand(self, name: &str, operand: i32) -> Self {
+        self.push(name, utils::int2bin(operand, 0), String::new())
+    }
+
+    /// Adds an instruction whose operand is the address named by a label.
+    pub fn instruction_with_label(self, name: &str, label: &str) -> Self {
+        self.push(name, String::new(), label.to_string())
+    }
+
+    /// Adds a data cell named by a label, holding 0 when the program is loaded.
+    pub fn data(mut self, label: &str) -> Self {
+        self.machine.labels_map.insert(
+            label.to_string(),
+            utils::int2bin(self.machine.instructions.len() as i32, 0),
+        );
+        self.machine.instructions.push(Instruction {
+            opcode: String::new(),
+            operand: "0".to_string(),
+            label: label.to_string(),
+        });
+        self
+    }
+
+    /// Adds an instruction, naming its address with the pending label.
+    fn push(mut self, name: &str, operand: String, label: String) -> Self {
+        let opcode = match RamMachine::ram_instruction_lookup(name.to_string()) {
+            Ok(opcode) => opcode,
+            Err(error) => {
+                self.error.get_or_insert(error);
+                return self;
+            }
+        };
+        if let Some(pending) = self.label.take() {
+            self.machine.labels_map.insert(
+                pending,
+                utils::int2bin(self.machine.instructions.len() as i32, 0),
+            );
+        }
+        self.machine.instructions.push(Instruction {
+            opcode,
+            operand,
+            label,
+        });
+        self
+    }
+
+    /// Builds the RAM machine.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RamMachine)` - The machine with the program
+    /// * `Err(String)` - An error message if an instruction is not valid, or a label is used
+    ///   without naming an address
+    pub fn build(self) -> Result<RamMachine, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if let Some(label) = self.label {
+            return Err(format!("label not followed by an instruction: {}", label));
+        }
+        if let Some(instr) = self
+            .machine
+            .instructions
+            .iter()
+            .find(|instr| {
+                !instr.label.is_empty() && !self.machine.labels_map.contains_key(&instr.label)
+            })
+        {
+            return Err(format!("label key not found: {}", instr.label));
+        }
+        Ok(self.machine)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -499,6 +792,17 @@ mod tests {
         assert!(!RamMachine::is_instruction(""));
     }
 
+    #[test]
+    fn test_ram_opcode_lookup() {
+        assert_eq!(RamMachine::ram_opcode_lookup("0000").unwrap(), "R");
+        assert_eq!(RamMachine::ram_opcode_lookup("1011").unwrap(), "H");
+        assert!(RamMachine::ram_opcode_lookup("10").is_err());
+        for instruction in ["MIR", "CJUMP", "STD"] {
+            let opcode = RamMachine::ram_instruction_lookup(instruction.to_string()).unwrap();
+            assert_eq!(RamMachine::ram_opcode_lookup(&opcode).unwrap(), instruction);
+        }
+    }
+
     #[test]
     fn test_ram_instruction_lookup() {
         assert_eq!(
@@ -591,15 +895,17 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (state, _, output, steps, _) = result.unwrap();
-        assert_eq!(state, "halt");
+        let computer::SimulationResult {
+            outcome: state,
+            tape: output,
+            steps,
+            ..
+        } = result.unwrap();
+        assert_eq!(state, computer::Outcome::Halt);
         assert_eq!(output[0], "1010");
         assert_eq!(steps, 3);
     }
@@ -633,14 +939,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("1111".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "1111");
     }
 
@@ -683,14 +986,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "1010"); // 5 + 5 = 10 in binary
     }
     #[test]
@@ -742,14 +1042,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("11100111".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "10001");
     }
 
@@ -811,14 +1108,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "1100");
     }
 
@@ -870,14 +1164,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "1");
     }
 
@@ -910,14 +1201,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("11".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "0011");
     }
 
@@ -965,14 +1253,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "11111111111111111111111111111011");
     }
     #[test]
@@ -1025,10 +1310,7 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 1, &computer, &context);
         assert!(result.is_ok());
@@ -1051,17 +1333,59 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 10, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, _, steps, _) = result.unwrap();
+        let computer::SimulationResult { steps, .. } = result.unwrap();
         assert_eq!(steps, 10);
     }
 
+    #[test]
+    fn test_loop_detection() {
+        let ram = RamMachine {
+            instructions: vec![
+                Instruction {
+                    opcode: "0011".to_string(), // W
+                    operand: "".to_string(),
+                    label: "".to_string(),
+                },
+                Instruction {
+                    opcode: "1001".to_string(), // JUMP
+                    operand: "0".to_string(),   // Infinite loop, writing forever
+                    label: "".to_string(),
+                },
+            ],
+            labels_map: std::collections::HashMap::new(),
+            translation_map: std::collections::HashMap::new(),
+        };
+
+        let computer = computer::Computer {
+            element: computer::ComputingElem::Ram(Box::new(ram.clone())),
+            mapping: std::collections::HashMap::new(),
+        };
+
+        let mut context = computer::Server::new();
+        context.simulation.detect_loops = true;
+
+        let computer::SimulationResult { outcome: state, tape: output, steps, .. } = ram
+            .simulate("".to_string(), 1000, &computer, &context)
+            .unwrap();
+        assert_eq!(
+            state,
+            computer::Outcome::Diverges("cycle of period 2 from step 1".to_string())
+        );
+        assert_eq!(steps, 3);
+        assert_eq!(output, vec!["00"]);
+
+        context.simulation.detect_loops = false;
+        let computer::SimulationResult { outcome: state, steps, .. } = ram
+            .simulate("".to_string(), 1000, &computer, &context)
+            .unwrap();
+        assert_eq!(state, computer::Outcome::Timeout);
+        assert_eq!(steps, 1000);
+    }
+
     #[test]
     fn test_memory_boundary_conditions() {
         let ram = RamMachine {
@@ -1091,14 +1415,11 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         let result = ram.simulate("".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "0");
     }
 
@@ -1136,16 +1457,72 @@ mod tests {
             mapping: std::collections::HashMap::new(),
         };
 
-        let context = computer::Server {
-            map_computers: std::collections::HashMap::new(),
-            computation_order: Vec::new(),
-        };
+        let context = computer::Server::new();
 
         // Input is short ("11"). Move right by 10 puts head way past end.
         // It should read "00" instead of panicking.
         let result = ram.simulate("11".to_string(), 100, &computer, &context);
         assert!(result.is_ok());
-        let (_, _, output, _, _) = result.unwrap();
+        let computer::SimulationResult { tape: output, .. } = result.unwrap();
         assert_eq!(output[0], "00");
     }
+
+    #[test]
+    fn test_builder() {
+        // adds two bytes of the input, keeping the first one in a data cell
+        let ram = RamMachine::builder()
+            .instruction_with_label("JUMP", "start")
+            .data("x")
+            .label("start")
+            .instruction_with_operand("R", 8)
+            .instruction_with_operand("MIR", 8)
+            .instruction_with_label("ST", "x")
+            .instruction_with_operand("R", 8)
+            .instruction_with_label("A", "x")
+            .instruction("W")
+            .instruction("H")
+            .build()
+            .unwrap();
+        let lines: Vec<String> = [
+            "JUMP start",
+            "x",
+            "start R 8",
+            "MIR 8",
+            "ST x",
+            "R 8",
+            "A x",
+            "W",
+            "H",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let mut computer = computer::Computer::new();
+        let from_file = match crate::file_handler::read_ram_program(lines, &mut computer)
+            .unwrap()
+            .element
+        {
+            computer::ComputingElem::Ram(m) => *m,
+            _ => panic!("expected a RAM machine"),
+        };
+        assert_eq!(
+            ram.initial_configuration("0".to_string()).unwrap(),
+            from_file.initial_configuration("0".to_string()).unwrap()
+        );
+
+        let computer = computer::Computer {
+            element: computer::ComputingElem::Ram(Box::new(ram.clone())),
+            mapping: std::collections::HashMap::new(),
+        };
+        let context = computer::Server::new();
+        let result = ram
+            .simulate("0000001100000100".to_string(), 100, &computer, &context)
+            .unwrap();
+        assert_eq!(result.outcome, computer::Outcome::Halt);
+        assert_eq!(utils::bin2int(result.output()).unwrap(), 7);
+
+        assert!(RamMachine::builder().instruction("NOP").build().is_err());
+        assert!(RamMachine::builder().instruction_with_label("JUMP", "end").build().is_err());
+        assert!(RamMachine::builder().label("end").build().is_err());
+    }
 }
@@ -7,9 +7,15 @@
 //! ## Features
 //!
 //! - **Regex Parsing**: Supports parsing of regular expressions with operations such as concatenation, alternation (`|`),
-//!   Kleene star (`*`), Kleene plus (`+`), and optional (`?`).
+//!   Kleene star (`*`), Kleene plus (`+`), optional (`?`) and bounded repetition (`{m,n}`), character classes and
+//!   multi-character symbols.
 //! - **Regex Syntax Tree**: Represents a regular expression as a tree structure using the `Regex` struct and `Operation` enum.
 //! - **Regex to FSA Conversion**: Converts a parsed regular expression into a finite state automaton represented as a Turing Machine.
+//! - **Regex to DFA Conversion**: Builds a deterministic automaton directly from a `Regex` with Brzozowski derivatives,
+//!   without going through the ε-moves of the Thompson construction.
+//! - **FSA to Regex Conversion**: Converts a finite automaton back into a regular expression with the state elimination
+//!   method, simplifying the result so that it stays readable.
+//! - **Printing**: The `Display` implementation prints a `Regex` in the syntax accepted by `build_regex_tree`.
 //!
 //! ## Supported Operations
 //!
@@ -18,19 +24,31 @@
 //! - **Kleene Star**: Matches zero or more repetitions of an expression (e.g., `a*`).
 //! - **Kleene Plus**: Matches one or more repetitions of an expression (e.g., `a+`).
 //! - **Optional**: Matches zero or one occurrence of an expression (e.g., `a?`).
-//! - **Symbols**: Matches individual characters or escaped characters.
+//! - **Bounded Repetition**: Matches between `m` and `n` repetitions of an expression (e.g., `a{2,3}`); `a{m}` matches
+//!   exactly `m` repetitions and `a{m,}` at least `m`. The bounds are at most 1000, and nested repetitions may expand
+//!   to at most 100000 nodes.
+//! - **Symbols**: Matches individual characters (e.g., `a` or `#`), escaped characters (e.g., `\.` matches a dot) or
+//!   multi-character symbols written between angle brackets (e.g., `<id>`).
+//! - **Character Classes**: Matches one symbol of a set (e.g., `[a-z0-9_]`), or any symbol of the alphabet of the
+//!   expression outside of a set (e.g., `[^ab]`). The wildcard `.` matches any symbol of the alphabet.
+//! - **Epsilon and Empty Set**: `ε` matches the empty word and `∅` matches nothing at all.
 //!
 //! ## Public API
 //!
 //! - `build_regex_tree(input: &str) -> Result<Regex, String>`: Parses a regular expression string and constructs a syntax tree.
 //! - `regex_to_fsa(regex: &Regex) -> Result<turing_machine::TuringMachine, String>`: Converts a `Regex` syntax tree into a Turing Machine representation of an FSA.
+//! - `regex_to_dfa(regex: &Regex) -> finite_automaton::FiniteAutomaton`: Converts a `Regex` syntax tree into a DFA.
+//! - `regex_to_fsa_over` and `regex_to_dfa_over`: The same conversions, with negated classes and the wildcard also
+//!   ranging over the given symbols, such as the ones of a searched text.
+//! - `fsa_to_regex(fa: &finite_automaton::FiniteAutomaton) -> Regex`: Converts a finite automaton into a simplified `Regex` syntax tree.
 //!
 //! ## Internal Parsing Functions
 //!
 //! - `parse_regex(chars: &mut Peekable<Chars>)`: Parses alternation (`|`) operations.
 //! - `parse_concat(chars: &mut Peekable<Chars>)`: Parses concatenation operations.
-//! - `parse_unary(chars: &mut Peekable<Chars>)`: Parses unary operations like `*`, `+`, and `?`.
-//! - `parse_primary(chars: &mut Peekable<Chars>)`: Parses primary expressions such as symbols and grouped expressions.
+//! - `parse_unary(chars: &mut Peekable<Chars>)`: Parses unary operations like `*`, `+`, `?` and `{m,n}`.
+//! - `parse_primary(chars: &mut Peekable<Chars>)`: Parses primary expressions such as symbols, classes and grouped expressions.
+//! - `parse_class(chars: &mut Peekable<Chars>)`: Parses the body of a character class.
 //!
 //! ## Testing
 //!
@@ -46,6 +64,7 @@
 //!
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
+use crate::finite_automaton;
 use crate::turing_machine;
 use std::iter::Peekable;
 use std::str::Chars;
@@ -63,6 +82,11 @@ use std::str::Chars;
 /// - `KleneePlus`: Represents one or more repetitions of an expression (e.g., `a+`).
 /// - `Optional`: Represents zero or one occurrence of an expression (e.g., `a?`).
 /// - `Symbol`: Represents an individual character or escaped character in the expression.
+/// - `Epsilon`: Represents the empty word (`ε`).
+/// - `Empty`: Represents the empty language (`∅`).
+/// - `Class`: Represents one symbol of a set (e.g., `[a-c]`).
+/// - `NegatedClass`: Represents one symbol of the alphabet outside of a set (e.g., `[^a-c]` or `.`).
+/// - `Repeat`: Represents a bounded number of repetitions of an expression (e.g., `a{2,3}`).
 #[derive(Clone, Debug)]
 pub enum Operation {
     Concat,
@@ -71,6 +95,11 @@ pub enum Operation {
     KleneePlus,
     Optional,
     Symbol,
+    Epsilon,
+    Empty,
+    Class,
+    NegatedClass,
+    Repeat,
 }
 
 impl PartialEq for Operation {
@@ -83,6 +112,11 @@ impl PartialEq for Operation {
                 | (Operation::KleneePlus, Operation::KleneePlus)
                 | (Operation::Optional, Operation::Optional)
                 | (Operation::Symbol, Operation::Symbol)
+                | (Operation::Epsilon, Operation::Epsilon)
+                | (Operation::Empty, Operation::Empty)
+                | (Operation::Class, Operation::Class)
+                | (Operation::NegatedClass, Operation::NegatedClass)
+                | (Operation::Repeat, Operation::Repeat)
         )
     }
 }
@@ -105,21 +139,28 @@ impl PartialEq for Operation {
 ///   This is used for binary operations like concatenation and alternation.
 /// - `symbol`: A string representing the symbol for this node. This is used
 ///   when the node represents a single character or an escaped character.
+/// - `symbols`: The set of symbols of a character class.
+/// - `min`: The minimum number of repetitions of a `Repeat` node.
+/// - `max`: The maximum number of repetitions of a `Repeat` node, `None` if unbounded.
 ///
 /// # Notes
 ///
 /// - The `symbol` field is only relevant for nodes with the `Operation::Symbol` operation.
+/// - The `symbols` field is only relevant for `Class` and `NegatedClass` nodes, and `min` and `max` for `Repeat` nodes.
 /// - For binary operations like `Concat` and `Or`, both `left` and `right` fields must be `Some`.
 /// - For unary operations like `KleeneStar`, only the `left` field is used, and the `right` field is `None`.
 ///
 /// This struct is primarily used in conjunction with the `build_regex_tree` function to parse
 /// regular expressions into a syntax tree representation.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Regex {
     pub operation: Operation,
     pub left: Option<Box<Regex>>,
     pub right: Option<Box<Regex>>,
     pub symbol: String,
+    pub symbols: Vec<String>,
+    pub min: usize,
+    pub max: Option<usize>,
 }
 
 impl Regex {
@@ -138,6 +179,9 @@ impl Regex {
             left: None,
             right: None,
             symbol: symbol.to_string(),
+            symbols: Vec::new(),
+            min: 0,
+            max: None,
         }
     }
 
@@ -158,6 +202,487 @@ impl Regex {
             left,
             right,
             symbol: String::new(),
+            symbols: Vec::new(),
+            min: 0,
+            max: None,
+        }
+    }
+
+    /// Creates a new `Regex` instance representing a character class.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - The symbols of the class.
+    /// * `negated` - Whether the class matches the symbols of the alphabet outside of `symbols`.
+    ///
+    /// # Returns
+    ///
+    /// A `Regex` variant with the `Class` or `NegatedClass` operation.
+    pub fn class(symbols: Vec<String>, negated: bool) -> Self {
+        let op = if negated { Operation::NegatedClass } else { Operation::Class };
+        Regex {
+            symbols,
+            ..Regex::operation(op, None, None)
+        }
+    }
+
+    /// Creates a new `Regex` instance representing a bounded repetition.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand` - The repeated expression.
+    /// * `min` - The minimum number of repetitions.
+    /// * `max` - The maximum number of repetitions, `None` if unbounded.
+    ///
+    /// # Returns
+    ///
+    /// A `Regex` variant with the `Repeat` operation.
+    pub fn repeat(operand: Regex, min: usize, max: Option<usize>) -> Self {
+        Regex {
+            min,
+            max,
+            ..Regex::operation(Operation::Repeat, Some(Box::new(operand)), None)
+        }
+    }
+
+    /// Collects the symbols used by the expression, in order of appearance.
+    ///
+    /// This is the alphabet negated classes and the wildcard are taken relative to.
+    ///
+    /// # Returns
+    ///
+    /// A vector with the symbols of the `Symbol`, `Class` and `NegatedClass` nodes, without duplicates.
+    pub fn alphabet(&self) -> Vec<String> {
+        let mut alphabet: Vec<String> = Vec::new();
+        let mut push = |symbol: &String| {
+            if !alphabet.contains(symbol) {
+                alphabet.push(symbol.clone());
+            }
+        };
+        match self.operation {
+            Operation::Symbol => push(&self.symbol),
+            Operation::Class | Operation::NegatedClass => self.symbols.iter().for_each(push),
+            _ => {
+                for child in self.left.iter().chain(self.right.iter()) {
+                    child.alphabet().iter().for_each(&mut push);
+                }
+            }
+        }
+        alphabet
+    }
+
+    /// Rewrites a `Repeat` node with the other operations, e.g. `a{2,3}` becomes `aaa?` and `a{2,}` becomes `aaa*`.
+    ///
+    /// # Returns
+    ///
+    /// The equivalent `Regex` without the top-level repetition, or a clone of the node if it is not a `Repeat`.
+    pub fn expand_repeat(&self) -> Regex {
+        let operand = match (&self.operation, &self.left) {
+            (Operation::Repeat, Some(operand)) => operand.as_ref().clone(),
+            _ => return self.clone(),
+        };
+        let mut factors = vec![operand.clone(); self.min];
+        match self.max {
+            None => factors.push(Regex::operation(
+                Operation::KleeneStar,
+                Some(Box::new(operand)),
+                None,
+            )),
+            Some(max) => {
+                for _ in self.min..max {
+                    factors.push(Regex::operation(
+                        Operation::Optional,
+                        Some(Box::new(operand.clone())),
+                        None,
+                    ));
+                }
+            }
+        }
+        factors
+            .into_iter()
+            .reduce(|l, r| Regex::operation(Operation::Concat, Some(Box::new(l)), Some(Box::new(r))))
+            .unwrap_or_else(Regex::epsilon)
+    }
+
+    /// Bounds the number of nodes of the expression once all of its repetitions are expanded by `expand_repeat`.
+    ///
+    /// # Returns
+    ///
+    /// An upper bound of the size of the expanded expression, saturating at `usize::MAX`.
+    pub fn expanded_size(&self) -> usize {
+        let left = self.left.as_ref().map_or(0, |l| l.expanded_size());
+        let right = self.right.as_ref().map_or(0, |r| r.expanded_size());
+        match self.operation {
+            // `max` copies of the operand, or `min` copies followed by a starred one, each with its operator
+            Operation::Repeat => self
+                .max
+                .unwrap_or(self.min.saturating_add(1))
+                .saturating_mul(left.saturating_add(2)),
+            _ => left.saturating_add(right).saturating_add(1),
+        }
+    }
+
+    /// Creates a new `Regex` instance matching only the empty word.
+    ///
+    /// # Returns
+    ///
+    /// A `Regex` variant with the `Epsilon` operation.
+    pub fn epsilon() -> Self {
+        Regex::operation(Operation::Epsilon, None, None)
+    }
+
+    /// Creates a new `Regex` instance matching no word at all.
+    ///
+    /// # Returns
+    ///
+    /// A `Regex` variant with the `Empty` operation.
+    pub fn empty() -> Self {
+        Regex::operation(Operation::Empty, None, None)
+    }
+
+    /// Checks whether the expression matches the empty word.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the empty word belongs to the language of the expression, `false` otherwise.
+    pub fn is_nullable(&self) -> bool {
+        let left = || self.left.as_ref().is_some_and(|l| l.is_nullable());
+        let right = || self.right.as_ref().is_some_and(|r| r.is_nullable());
+        match self.operation {
+            Operation::Concat => left() && right(),
+            Operation::Or => left() || right(),
+            Operation::KleeneStar | Operation::Optional | Operation::Epsilon => true,
+            Operation::KleneePlus => left(),
+            Operation::Repeat => self.min == 0 || left(),
+            Operation::Symbol | Operation::Empty | Operation::Class | Operation::NegatedClass => false,
+        }
+    }
+
+    /// Builds an equivalent and simpler expression.
+    ///
+    /// The children are simplified first, then the following rules are applied to the node:
+    /// - concatenations are flattened into a left-associated chain of factors
+    /// - `∅` absorbs concatenations and disappears from alternations, `ε` disappears from concatenations
+    /// - `R|R` becomes `R`, and `ε|R` becomes `R?` (or `R` when `R` already matches the empty word)
+    /// - `RR*` and `R*R` become `R+`
+    /// - nested and redundant unary operators are collapsed, e.g. `(R*)*` and `(R?)*` become `R*`
+    ///
+    /// # Returns
+    ///
+    /// The simplified `Regex`, matching the same language.
+    pub fn simplify(&self) -> Regex {
+        let left = self.left.as_ref().map(|l| l.simplify());
+        let right = self.right.as_ref().map(|r| r.simplify());
+        let unary =
+            |op: Operation, operand: Regex| Regex::operation(op, Some(Box::new(operand)), None);
+        let inner = |r: &Regex| r.left.as_deref().cloned();
+        match (self.operation.clone(), left, right) {
+            (Operation::Concat, Some(l), Some(r)) => {
+                let mut factors: Vec<Regex> = Vec::new();
+                for factor in l.factors().into_iter().chain(r.factors()) {
+                    let last = factors.last().cloned();
+                    match (factor.operation.clone(), last) {
+                        (Operation::Empty, _) => return Regex::empty(),
+                        (Operation::Epsilon, _) => {}
+                        (Operation::KleeneStar, Some(last))
+                            if inner(&factor).as_ref() == Some(&last) =>
+                        {
+                            factors.pop();
+                            factors.push(unary(Operation::KleneePlus, last));
+                        }
+                        (_, Some(last))
+                            if last.operation == Operation::KleeneStar
+                                && inner(&last).as_ref() == Some(&factor) =>
+                        {
+                            factors.pop();
+                            factors.push(unary(Operation::KleneePlus, factor));
+                        }
+                        _ => factors.push(factor),
+                    }
+                }
+                factors
+                    .into_iter()
+                    .reduce(|l, r| {
+                        Regex::operation(Operation::Concat, Some(Box::new(l)), Some(Box::new(r)))
+                    })
+                    .unwrap_or_else(Regex::epsilon)
+            }
+            (Operation::Or, Some(l), Some(r)) => match (&l.operation, &r.operation) {
+                (Operation::Empty, _) => r,
+                (_, Operation::Empty) => l,
+                _ if l == r => l,
+                (Operation::Epsilon, _) => unary(Operation::Optional, r).simplify(),
+                (_, Operation::Epsilon) => unary(Operation::Optional, l).simplify(),
+                _ => Regex::operation(Operation::Or, Some(Box::new(l)), Some(Box::new(r))),
+            },
+            (Operation::KleeneStar, Some(operand), _) => match operand.operation {
+                Operation::Empty | Operation::Epsilon => Regex::epsilon(),
+                Operation::KleeneStar => operand,
+                Operation::KleneePlus | Operation::Optional => unary(
+                    Operation::KleeneStar,
+                    inner(&operand).unwrap_or_else(Regex::epsilon),
+                )
+                .simplify(),
+                _ => unary(Operation::KleeneStar, operand),
+            },
+            (Operation::KleneePlus, Some(operand), _) => match operand.operation {
+                Operation::Empty
+                | Operation::Epsilon
+                | Operation::KleeneStar
+                | Operation::KleneePlus => operand,
+                Operation::Optional => unary(
+                    Operation::KleeneStar,
+                    inner(&operand).unwrap_or_else(Regex::epsilon),
+                )
+                .simplify(),
+                _ => unary(Operation::KleneePlus, operand),
+            },
+            (Operation::Optional, Some(operand), _) => match operand.operation {
+                Operation::Empty | Operation::Epsilon => Regex::epsilon(),
+                Operation::KleneePlus => unary(
+                    Operation::KleeneStar,
+                    inner(&operand).unwrap_or_else(Regex::epsilon),
+                ),
+                _ if operand.is_nullable() => operand,
+                _ => unary(Operation::Optional, operand),
+            },
+            (Operation::Repeat, Some(operand), _) => match operand.operation {
+                Operation::Empty if self.min > 0 => Regex::empty(),
+                Operation::Empty | Operation::Epsilon => Regex::epsilon(),
+                _ if self.max == Some(0) => Regex::epsilon(),
+                _ if (self.min, self.max) == (1, Some(1)) => operand,
+                _ => Regex::repeat(operand, self.min, self.max),
+            },
+            (Operation::Class, _, _) if self.symbols.is_empty() => Regex::empty(),
+            (Operation::Class, _, _) if self.symbols.len() == 1 => Regex::symbol(&self.symbols[0]),
+            _ => self.clone(),
+        }
+    }
+
+    /// Splits a chain of concatenations into its factors, from left to right.
+    fn factors(&self) -> Vec<Regex> {
+        match (&self.operation, &self.left, &self.right) {
+            (Operation::Concat, Some(l), Some(r)) => {
+                let mut factors = l.factors();
+                factors.extend(r.factors());
+                factors
+            }
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Computes the Brzozowski derivative of the expression with respect to a symbol.
+    ///
+    /// The derivative matches the words `w` such that the symbol followed by `w` is matched by the expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol to derive by, which is assumed to belong to the alphabet of the expression.
+    ///
+    /// # Returns
+    ///
+    /// The derivative, in the canonical form computed by `canonical`.
+    pub fn derivative(&self, symbol: &str) -> Regex {
+        let concat = |l: Regex, r: Regex| {
+            Regex::operation(Operation::Concat, Some(Box::new(l)), Some(Box::new(r)))
+        };
+        let or = |l: Regex, r: Regex| Regex::operation(Operation::Or, Some(Box::new(l)), Some(Box::new(r)));
+        let derive = |child: &Option<Box<Regex>>| match child {
+            Some(c) => c.derivative(symbol),
+            None => Regex::empty(),
+        };
+        let operand = || self.left.as_deref().cloned().unwrap_or_else(Regex::empty);
+        let derivative = match self.operation {
+            Operation::Symbol if self.symbol == symbol => Regex::epsilon(),
+            Operation::Class if self.symbols.iter().any(|s| s == symbol) => Regex::epsilon(),
+            Operation::NegatedClass if !self.symbols.iter().any(|s| s == symbol) => Regex::epsilon(),
+            Operation::Symbol | Operation::Class | Operation::NegatedClass => Regex::empty(),
+            Operation::Epsilon | Operation::Empty => Regex::empty(),
+            Operation::Concat => {
+                let right = self.right.as_deref().cloned().unwrap_or_else(Regex::empty);
+                let first = concat(derive(&self.left), right);
+                if self.left.as_ref().is_some_and(|l| l.is_nullable()) {
+                    or(first, derive(&self.right))
+                } else {
+                    first
+                }
+            }
+            Operation::Or => or(derive(&self.left), derive(&self.right)),
+            Operation::KleeneStar | Operation::KleneePlus => concat(
+                derive(&self.left),
+                Regex::operation(Operation::KleeneStar, Some(Box::new(operand())), None),
+            ),
+            Operation::Optional => derive(&self.left),
+            Operation::Repeat => match self.max {
+                Some(0) => Regex::empty(),
+                max => concat(
+                    derive(&self.left),
+                    Regex::repeat(operand(), self.min.saturating_sub(1), max.map(|m| m - 1)),
+                ),
+            },
+        };
+        derivative.canonical()
+    }
+
+    /// Builds the canonical form of the expression, used to recognize equal derivatives.
+    ///
+    /// The expression is simplified, then the alternatives of every alternation are sorted and deduplicated, so that
+    /// expressions differing only in the order of their alternatives have the same canonical form.
+    ///
+    /// # Returns
+    ///
+    /// The canonical `Regex`, matching the same language.
+    pub fn canonical(&self) -> Regex {
+        fn sort_alternatives(r: &Regex) -> Regex {
+            let mut r = r.clone();
+            r.left = r.left.map(|l| Box::new(sort_alternatives(&l)));
+            r.right = r.right.map(|c| Box::new(sort_alternatives(&c)));
+            if r.operation != Operation::Or {
+                return r;
+            }
+            let mut alternatives = r.alternatives();
+            alternatives.sort_by_key(|a| a.to_string());
+            alternatives.dedup();
+            alternatives
+                .into_iter()
+                .reduce(|l, r| Regex::operation(Operation::Or, Some(Box::new(l)), Some(Box::new(r))))
+                .unwrap_or_else(Regex::empty)
+        }
+        sort_alternatives(&self.simplify())
+    }
+
+    /// Splits a chain of alternations into its alternatives, from left to right.
+    fn alternatives(&self) -> Vec<Regex> {
+        match (&self.operation, &self.left, &self.right) {
+            (Operation::Or, Some(l), Some(r)) => {
+                let mut alternatives = l.alternatives();
+                alternatives.extend(r.alternatives());
+                alternatives
+            }
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Returns the binding strength of the node, used to decide where parentheses are needed
+    /// when printing: alternation binds the least, unary operators and atoms the most.
+    fn precedence(&self) -> u8 {
+        match self.operation {
+            Operation::Or => 0,
+            Operation::Concat => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Regex {
+    /// Formats the expression in the syntax accepted by `build_regex_tree`, adding only the
+    /// parentheses required by the precedence of the operators.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let child =
+            |f: &mut std::fmt::Formatter<'_>, child: &Option<Box<Regex>>, min: u8| match child {
+                Some(c) if c.precedence() < min => write!(f, "({})", c),
+                Some(c) => write!(f, "{}", c),
+                None => Ok(()),
+            };
+        match self.operation {
+            Operation::Symbol => write_symbol(f, &self.symbol, SPECIAL_CHARACTERS),
+            Operation::NegatedClass if self.symbols.is_empty() => write!(f, "."),
+            Operation::Class | Operation::NegatedClass => {
+                write!(f, "[")?;
+                if self.operation == Operation::NegatedClass {
+                    write!(f, "^")?;
+                }
+                // runs of at least three consecutive characters are written as ranges
+                let mut i = 0;
+                while i < self.symbols.len() {
+                    let single = |j: usize| {
+                        let mut chars = self.symbols.get(j)?.chars();
+                        chars.next().filter(|_| chars.next().is_none())
+                    };
+                    let mut j = i;
+                    while let (Some(a), Some(b)) = (single(j), single(j + 1)) {
+                        if b as u32 != a as u32 + 1 {
+                            break;
+                        }
+                        j += 1;
+                    }
+                    write_symbol(f, &self.symbols[i], CLASS_SPECIAL_CHARACTERS)?;
+                    if j >= i + 2 {
+                        write!(f, "-")?;
+                        write_symbol(f, &self.symbols[j], CLASS_SPECIAL_CHARACTERS)?;
+                        i = j;
+                    }
+                    i += 1;
+                }
+                write!(f, "]")
+            }
+            Operation::Repeat => {
+                child(f, &self.left, 2)?;
+                match self.max {
+                    Some(max) if max == self.min => write!(f, "{{{}}}", max),
+                    Some(max) => write!(f, "{{{},{}}}", self.min, max),
+                    None => write!(f, "{{{},}}", self.min),
+                }
+            }
+            Operation::Epsilon => write!(f, "ε"),
+            Operation::Empty => write!(f, "∅"),
+            Operation::Or => {
+                child(f, &self.left, 0)?;
+                write!(f, "|")?;
+                child(f, &self.right, 0)
+            }
+            Operation::Concat => {
+                child(f, &self.left, 1)?;
+                child(f, &self.right, 1)
+            }
+            Operation::KleeneStar | Operation::KleneePlus | Operation::Optional => {
+                child(f, &self.left, 2)?;
+                let op = match self.operation {
+                    Operation::KleeneStar => "*",
+                    Operation::KleneePlus => "+",
+                    _ => "?",
+                };
+                write!(f, "{}", op)
+            }
+        }
+    }
+}
+
+/// The characters with a special meaning outside of character classes, which must be escaped to be used as symbols.
+const SPECIAL_CHARACTERS: &str = "()|*+?{}[].<>\\ε∅";
+
+/// The characters with a special meaning inside character classes.
+const CLASS_SPECIAL_CHARACTERS: &str = "^-[]<>\\";
+
+/// The largest bound of a repetition, whose operand is copied once per repetition by the conversions to automata.
+const MAX_REPETITION: usize = 1000;
+
+/// The largest size of an expression once its repetitions are expanded, which bounds the automata of nested
+/// repetitions, whose bounds multiply.
+const MAX_EXPANDED_SIZE: usize = 100_000;
+
+/// Writes a symbol as it is read by the parser: multi-character symbols are wrapped in angle brackets and special
+/// characters are escaped.
+///
+/// # Arguments
+///
+/// * `f` - The formatter to write to.
+/// * `symbol` - The symbol to write.
+/// * `special` - The characters which must be escaped.
+fn write_symbol(f: &mut std::fmt::Formatter<'_>, symbol: &str, special: &str) -> std::fmt::Result {
+    let mut chars = symbol.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if special.contains(ch) => write!(f, "\\{}", ch),
+        (Some(_), None) => write!(f, "{}", symbol),
+        _ => {
+            write!(f, "<")?;
+            for ch in symbol.chars() {
+                if ch == '>' || ch == '\\' {
+                    write!(f, "\\")?;
+                }
+                write!(f, "{}", ch)?;
+            }
+            write!(f, ">")
         }
     }
 }
@@ -265,7 +790,7 @@ fn parse_concat(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 }
 
 /// Parses a unary expression from a stream of characters, handling operations like Kleene star (`*`),
-/// Kleene plus (`+`), and optional (`?`).
+/// Kleene plus (`+`), optional (`?`) and bounded repetition (`{m}`, `{m,}` and `{m,n}`).
 ///
 /// # Arguments
 ///
@@ -276,6 +801,11 @@ fn parse_concat(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 /// * `Ok(Regex)` - The parsed unary expression as a `Regex` node if successful.
 /// * `Err(String)` - An error message if the input is invalid or a syntax error is encountered.
 ///
+/// # Errors
+///
+/// Returns an error if a repetition is not closed, its bounds are not numbers or exceed `MAX_REPETITION`, the maximum
+/// is lower than the minimum, or the expanded expression, with the repetitions nested in it, exceeds `MAX_EXPANDED_SIZE`.
+///
 fn parse_unary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
     let mut expr = parse_primary(chars)?;
 
@@ -293,6 +823,40 @@ fn parse_unary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
                 chars.next();
                 expr = Regex::operation(Operation::Optional, Some(Box::new(expr)), None);
             }
+            '{' => {
+                chars.next();
+                let mut bounds = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => bounds.push(ch),
+                        None => return Err("Expected closing brace".to_string()),
+                    }
+                }
+                let parse_bound = |bound: &str| match bound.trim().parse::<usize>() {
+                    Ok(value) if value > MAX_REPETITION => Err(format!(
+                        "Repetition bound too large: {} (at most {})",
+                        value, MAX_REPETITION
+                    )),
+                    Ok(value) => Ok(value),
+                    Err(_) => Err(format!("Invalid repetition bound: {}", bound)),
+                };
+                let (min, max) = match bounds.split_once(',') {
+                    None => (parse_bound(&bounds)?, Some(parse_bound(&bounds)?)),
+                    Some((min, max)) if max.trim().is_empty() => (parse_bound(min)?, None),
+                    Some((min, max)) => (parse_bound(min)?, Some(parse_bound(max)?)),
+                };
+                if max.is_some_and(|max| max < min) {
+                    return Err(format!("Invalid repetition: {{{}}}", bounds));
+                }
+                expr = Regex::repeat(expr, min, max);
+                if expr.expanded_size() > MAX_EXPANDED_SIZE {
+                    return Err(format!(
+                        "Repetition too large: {{{}}} expands to more than {} nodes",
+                        bounds, MAX_EXPANDED_SIZE
+                    ));
+                }
+            }
             _ => break,
         }
     }
@@ -301,11 +865,14 @@ fn parse_unary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 }
 
 /// Parses a primary expression from a stream of characters, handling symbols, escaped characters,
-/// and grouped sub-expressions (parentheses).
+/// character classes and grouped sub-expressions (parentheses).
 ///
 /// This function is responsible for parsing the most basic units of a regular expression:
 /// - Single symbols (alphanumeric or other allowed characters)
-/// - Escaped characters (e.g., `\*`, `\+`)
+/// - Escaped characters (e.g., `\*`, `\.`), which stand for the character itself
+/// - Multi-character symbols between angle brackets (e.g., `<id>`)
+/// - Character classes (e.g., `[a-z]`, `[^ab]`) and the wildcard `.`
+/// - The empty word `ε` and the empty set `∅`
 /// - Grouped expressions within parentheses (e.g., `(a|b)`)
 ///
 /// # Arguments
@@ -320,7 +887,7 @@ fn parse_unary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 /// # Errors
 ///
 /// Returns an error if:
-/// - There is an unmatched parenthesis.
+/// - There is an unmatched parenthesis, bracket or angle bracket.
 /// - An escape character is not followed by a valid character.
 /// - An unexpected character is encountered.
 ///
@@ -336,15 +903,37 @@ fn parse_primary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 
             Ok(expr)
         }
+        Some('[') => {
+            chars.next();
+            parse_class(chars)
+        }
+        Some('<') => {
+            chars.next();
+            let symbol = parse_long_symbol(chars)?;
+            Ok(Regex::symbol(&symbol))
+        }
         Some('\\') => {
             chars.next();
             if let Some(ch) = chars.next() {
-                Ok(Regex::symbol(&format!("\\{}", ch)))
+                Ok(Regex::symbol(&ch.to_string()))
             } else {
                 Err("Unexpected end of pattern after escape character".to_string())
             }
         }
-        Some(&ch) if ch != '*' && ch != '+' && ch != '?' && ch != '|' && ch != ')' => {
+        Some('.') => {
+            chars.next();
+            // any symbol of the alphabet of the whole expression, as for negated classes
+            Ok(Regex::class(Vec::new(), true))
+        }
+        Some('ε') => {
+            chars.next();
+            Ok(Regex::epsilon())
+        }
+        Some('∅') => {
+            chars.next();
+            Ok(Regex::empty())
+        }
+        Some(&ch) if !SPECIAL_CHARACTERS.contains(ch) => {
             chars.next();
             Ok(Regex::symbol(&ch.to_string()))
         }
@@ -353,6 +942,110 @@ fn parse_primary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
     }
 }
 
+/// Parses the body of a character class, after the opening bracket.
+///
+/// A class is a sequence of symbols (plain, escaped or between angle brackets) and of ranges of single characters
+/// such as `a-z`, closed by `]`. A leading `^` negates the class: since the parser does not know the input of the
+/// expression, a negated class, like the wildcard `.`, matches the symbols outside of the class among those of
+/// `Regex::alphabet`, i.e. the ones written somewhere in the expression, and those given to `regex_to_fsa_over` or
+/// `regex_to_dfa_over`.
+///
+/// # Arguments
+///
+/// * `chars` - A mutable reference to a `Peekable<Chars>` iterator over the input regular expression string.
+///
+/// # Returns
+///
+/// * `Ok(Regex)` - A `Class` or `NegatedClass` node with the symbols of the class, in order and without duplicates.
+/// * `Err(String)` - An error message if the class is not closed, is empty, or contains an invalid range.
+///
+fn parse_class(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut symbols: Vec<String> = Vec::new();
+    let mut push = |symbol: String| {
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    };
+    loop {
+        let start = match chars.next() {
+            Some(']') => break,
+            Some('\\') => chars
+                .next()
+                .ok_or("Unexpected end of pattern after escape character")?,
+            Some('<') => {
+                push(parse_long_symbol(chars)?);
+                continue;
+            }
+            Some(ch) => ch,
+            None => return Err("Expected closing bracket".to_string()),
+        };
+        let mut lookahead = chars.clone();
+        if lookahead.next() == Some('-') && !matches!(lookahead.peek(), Some(']') | None) {
+            chars.next();
+            let end = match chars.next() {
+                Some('\\') => chars
+                    .next()
+                    .ok_or("Unexpected end of pattern after escape character")?,
+                Some(ch) => ch,
+                None => return Err("Expected closing bracket".to_string()),
+            };
+            if end < start {
+                return Err(format!("Invalid range: {}-{}", start, end));
+            }
+            (start..=end).for_each(|ch| push(ch.to_string()));
+        } else {
+            push(start.to_string());
+        }
+    }
+    if symbols.is_empty() && !negated {
+        return Err("Empty character class".to_string());
+    }
+    Ok(Regex::class(symbols, negated))
+}
+
+/// Parses a multi-character symbol, after the opening angle bracket, up to the closing one.
+///
+/// # Arguments
+///
+/// * `chars` - A mutable reference to a `Peekable<Chars>` iterator over the input regular expression string.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The symbol, where `\>` and `\\` stand for `>` and `\`.
+/// * `Err(String)` - An error message if the symbol is empty or not closed.
+///
+fn parse_long_symbol(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut symbol = String::new();
+    loop {
+        match chars.next() {
+            Some('>') => break,
+            Some('\\') => symbol.push(
+                chars
+                    .next()
+                    .ok_or("Unexpected end of pattern after escape character")?,
+            ),
+            Some(ch) => symbol.push(ch),
+            None => return Err("Expected closing angle bracket".to_string()),
+        }
+    }
+    if symbol.is_empty() {
+        return Err("Empty symbol".to_string());
+    }
+    Ok(symbol)
+}
+
+/// Collects the alphabet of an expression followed by the given symbols it does not use.
+fn extended_alphabet(regex: &Regex, symbols: &[String]) -> Vec<String> {
+    let mut alphabet = regex.alphabet();
+    for symbol in symbols {
+        if !alphabet.contains(symbol) {
+            alphabet.push(symbol.clone());
+        }
+    }
+    alphabet
+}
+
 /// Converts a parsed regular expression syntax tree into a finite state automaton (FSA)
 /// represented as a Turing Machine.
 ///
@@ -367,7 +1060,10 @@ fn parse_primary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 /// - Kleene star (`*`)
 /// - Kleene plus (`+`)
 /// - Optional (`?`)
-/// - Symbols (including escaped characters)
+/// - Bounded repetition (`{m,n}`)
+/// - Symbols (including escaped characters and multi-character symbols)
+/// - Character classes, negated classes and the wildcard, the latter two relative to the alphabet of the expression
+/// - Epsilon (`ε`) and the empty set (`∅`)
 ///
 /// # Arguments
 ///
@@ -380,14 +1076,48 @@ fn parse_primary(chars: &mut Peekable<Chars>) -> Result<Regex, String> {
 ///
 /// # Errors
 ///
-/// Returns an error if the regex tree is invalid or if required operands for operations are missing.
+/// Returns an error if the regex tree is invalid, if required operands for operations are missing, or if its repetitions
+/// expand to more than `MAX_EXPANDED_SIZE` nodes.
+///
+/// # Notes
+///
+/// The ε-moves leaving each state are added in order of priority: alternatives from left to right, and repetitions
+/// before exits. Leftmost-first searches rely on this order to follow the usual backtracking semantics.
+///
 /// # See Also
 /// - [`build_regex_tree`] for parsing a regex string into a syntax tree.
 /// - [`turing_machine::TuringMachine`] for the FSA representation.
 ///
 pub fn regex_to_fsa(regex: &Regex) -> Result<turing_machine::TuringMachine, String> {
+    regex_to_fsa_over(regex, &[])
+}
+
+/// Converts a parsed regular expression into a finite state automaton like `regex_to_fsa`, over the alphabet of the
+/// expression extended with the given symbols.
+///
+/// # Arguments
+///
+/// * `regex` - A reference to a `Regex` syntax tree representing the regular expression to convert.
+/// * `symbols` - The symbols, besides the ones of the expression, over which negated classes and the wildcard range.
+///   The space is left out, since the automaton uses it as its blank symbol.
+///
+/// # Returns
+///
+/// The same as `regex_to_fsa`.
+pub fn regex_to_fsa_over(
+    regex: &Regex,
+    symbols: &[String],
+) -> Result<turing_machine::TuringMachine, String> {
+    if regex.expanded_size() > MAX_EXPANDED_SIZE {
+        return Err(format!(
+            "Regex too large: its repetitions expand to more than {} nodes",
+            MAX_EXPANDED_SIZE
+        ));
+    }
     let mut fsa = turing_machine::TuringMachine::new();
     fsa.blank_symbol = " ".to_string();
+    fsa.input_alphabet = extended_alphabet(regex, symbols);
+    fsa.input_alphabet.retain(|symbol| symbol != " ");
 
     let (start, end) = build_fsa(&mut fsa, regex)?;
 
@@ -436,6 +1166,172 @@ pub fn regex_to_fsa(regex: &Regex) -> Result<turing_machine::TuringMachine, Stri
     Ok(fsa)
 }
 
+/// Converts a parsed regular expression syntax tree into a deterministic finite automaton with Brzozowski derivatives.
+///
+/// Each state of the automaton is a derivative of the expression in canonical form, starting from the expression
+/// itself: the move of a state on a symbol leads to its derivative with respect to that symbol, and a state is final
+/// when it matches the empty word. Since an expression has finitely many derivatives up to the reordering of the
+/// alternatives, the construction always ends. Moves leading to `∅` are left out, so the automaton may be partial.
+///
+/// Unlike `regex_to_fsa`, the result has no ε-moves and can be simulated in linear time in the length of the input.
+///
+/// # Arguments
+///
+/// * `regex` - A reference to a `Regex` syntax tree representing the regular expression to convert.
+///
+/// # Returns
+///
+/// A deterministic `FiniteAutomaton` over the alphabet of the expression, with states named `q0`, `q1`, ... in
+/// breadth-first order.
+///
+/// # See Also
+///
+/// - [`Regex::derivative`] for the derivative of an expression.
+/// - [`regex_to_fsa`] for the Thompson construction.
+pub fn regex_to_dfa(regex: &Regex) -> finite_automaton::FiniteAutomaton {
+    regex_to_dfa_over(regex, &[])
+}
+
+/// Converts a parsed regular expression into a deterministic finite automaton like `regex_to_dfa`, over the alphabet
+/// of the expression extended with the given symbols.
+///
+/// # Arguments
+///
+/// * `regex` - A reference to a `Regex` syntax tree representing the regular expression to convert.
+/// * `symbols` - The symbols, besides the ones of the expression, over which negated classes and the wildcard range.
+///
+/// # Returns
+///
+/// The same as `regex_to_dfa`.
+pub fn regex_to_dfa_over(regex: &Regex, symbols: &[String]) -> finite_automaton::FiniteAutomaton {
+    let mut fa = finite_automaton::FiniteAutomaton::new();
+    fa.input_alphabet = extended_alphabet(regex, symbols);
+    fa.initial_state = "q0".to_string();
+
+    let mut derivatives = vec![regex.canonical()];
+    let mut i = 0;
+    while i < derivatives.len() {
+        let state = format!("q{}", i);
+        fa.states.push(state.clone());
+        if derivatives[i].is_nullable() {
+            fa.final_states.push(state.clone());
+        }
+        for symbol in fa.input_alphabet.clone() {
+            let derivative = derivatives[i].derivative(&symbol);
+            if derivative.operation == Operation::Empty {
+                continue;
+            }
+            let j = match derivatives.iter().position(|d| *d == derivative) {
+                Some(j) => j,
+                None => {
+                    derivatives.push(derivative);
+                    derivatives.len() - 1
+                }
+            };
+            fa.add_transition(state.clone(), symbol, format!("q{}", j));
+        }
+        i += 1;
+    }
+    fa
+}
+
+/// Converts a finite automaton into an equivalent regular expression with the state elimination method.
+///
+/// The automaton is turned into a generalized NFA, whose edges are labelled by regular expressions, with a new
+/// initial state and a new single final state. The original states are then removed one at a time, starting from
+/// the ones with the fewest incoming and outgoing edges: removing `q` replaces every path `p -> q -> r` with the
+/// edge `p -> r` labelled `R(p,r)|R(p,q)R(q,q)*R(q,r)`. Every label is simplified as soon as it is built, so that
+/// the resulting expression stays readable.
+///
+/// # Arguments
+///
+/// * `fa` - A reference to the finite automaton to convert.
+///
+/// # Returns
+///
+/// A simplified `Regex` matching the language of the automaton. It is `ε` or `∅` only when the language is
+/// respectively the empty word alone or the empty set. Its `Display` form, `ε` and `∅` included, is read back by
+/// `build_regex_tree`, so it can be saved as a regex file.
+///
+/// # See Also
+///
+/// - [`Regex::simplify`] for the simplification rules.
+/// - [`regex_to_fsa`] for the inverse conversion.
+pub fn fsa_to_regex(fa: &finite_automaton::FiniteAutomaton) -> Regex {
+    let n = fa.states.len();
+    let (start, accept) = (n, n + 1);
+    let mut edges = vec![vec![Regex::empty(); n + 2]; n + 2];
+    let union = |a: &Regex, b: Regex| {
+        Regex::operation(Operation::Or, Some(Box::new(a.clone())), Some(Box::new(b))).simplify()
+    };
+
+    let index = |state: &String| fa.states.iter().position(|s| s == state);
+    if let Some(i) = index(&fa.initial_state) {
+        edges[start][i] = Regex::epsilon();
+    }
+    for state in fa.final_states.iter() {
+        if let Some(i) = index(state) {
+            edges[i][accept] = Regex::epsilon();
+        }
+    }
+    for t in fa.transitions.iter() {
+        if let (Some(p), Some(q)) = (index(&t.state), index(&t.new_state)) {
+            let label = if t.symbol.is_empty() {
+                Regex::epsilon()
+            } else {
+                Regex::symbol(&t.symbol)
+            };
+            edges[p][q] = union(&edges[p][q], label);
+        }
+    }
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    while !remaining.is_empty() {
+        let degree = |q: usize| {
+            let is_edge = |r: &Regex| r.operation != Operation::Empty;
+            let incoming = (0..n + 2)
+                .filter(|&p| p != q && is_edge(&edges[p][q]))
+                .count();
+            let outgoing = (0..n + 2)
+                .filter(|&r| r != q && is_edge(&edges[q][r]))
+                .count();
+            incoming * outgoing
+        };
+        let position = (0..remaining.len())
+            .min_by_key(|&i| degree(remaining[i]))
+            .unwrap_or(0);
+        let q = remaining.remove(position);
+
+        let loop_label = Regex::operation(
+            Operation::KleeneStar,
+            Some(Box::new(edges[q][q].clone())),
+            None,
+        );
+        let others: Vec<usize> = remaining.iter().cloned().chain([start, accept]).collect();
+        for &p in others.iter() {
+            if edges[p][q].operation == Operation::Empty {
+                continue;
+            }
+            for &r in others.iter() {
+                if edges[q][r].operation == Operation::Empty {
+                    continue;
+                }
+                let path = Regex::operation(
+                    Operation::Concat,
+                    Some(Box::new(Regex::operation(
+                        Operation::Concat,
+                        Some(Box::new(edges[p][q].clone())),
+                        Some(Box::new(loop_label.clone())),
+                    ))),
+                    Some(Box::new(edges[q][r].clone())),
+                );
+                edges[p][r] = union(&edges[p][r], path);
+            }
+        }
+    }
+    edges[start][accept].clone()
+}
+
 /// Recursively builds a finite state automaton (FSA) from a regular expression syntax tree.
 ///
 /// This function takes a mutable reference to a `TuringMachine` and a `Regex` syntax tree,
@@ -482,6 +1378,53 @@ fn build_fsa(
             Ok((start, end))
         }
 
+        Operation::Epsilon => {
+            let start = fsa.add_state();
+            let end = fsa.add_state();
+            fsa.add_transition(
+                start.clone(),
+                vec![" ".to_string()],
+                end.clone(),
+                vec![" ".to_string()],
+                vec![turing_machine::Direction::Stay],
+            );
+            Ok((start, end))
+        }
+
+        Operation::Empty => Ok((fsa.add_state(), fsa.add_state())),
+
+        Operation::Class | Operation::NegatedClass => {
+            let symbols: Vec<String> = if regex.operation == Operation::Class {
+                regex.symbols.clone()
+            } else {
+                fsa.input_alphabet
+                    .iter()
+                    .filter(|s| !regex.symbols.contains(s))
+                    .cloned()
+                    .collect()
+            };
+            let start = fsa.add_state();
+            let end = fsa.add_state();
+            for symbol in symbols {
+                fsa.add_transition(
+                    start.clone(),
+                    vec![symbol.clone()],
+                    end.clone(),
+                    vec![" ".to_string()],
+                    vec![turing_machine::Direction::Right],
+                );
+                if !fsa.input_alphabet.contains(&symbol) {
+                    fsa.input_alphabet.push(symbol);
+                }
+            }
+            Ok((start, end))
+        }
+
+        Operation::Repeat => {
+            regex.left.as_ref().ok_or("Repeat must have an operand")?;
+            build_fsa(fsa, &regex.expand_repeat())
+        }
+
         Operation::Concat => {
             let left = regex.left.as_ref().ok_or("Concat must have left operand")?;
             let right = regex
@@ -557,10 +1500,11 @@ fn build_fsa(
 
             let (start_operand, end_operand) = build_fsa(fsa, operand)?;
 
+            // entering the operand comes first, so that the moves are ordered by priority
             fsa.add_transition(
                 start.clone(),
                 vec![" ".to_string()],
-                end.clone(),
+                start_operand.clone(),
                 vec![" ".to_string()],
                 vec![turing_machine::Direction::Stay],
             );
@@ -568,7 +1512,7 @@ fn build_fsa(
             fsa.add_transition(
                 start.clone(),
                 vec![" ".to_string()],
-                start_operand.clone(),
+                end.clone(),
                 vec![" ".to_string()],
                 vec![turing_machine::Direction::Stay],
             );
@@ -641,7 +1585,7 @@ fn build_fsa(
             fsa.add_transition(
                 start.clone(),
                 vec![" ".to_string()],
-                end.clone(),
+                start_operand.clone(),
                 vec![" ".to_string()],
                 vec![turing_machine::Direction::Stay],
             );
@@ -649,7 +1593,7 @@ fn build_fsa(
             fsa.add_transition(
                 start.clone(),
                 vec![" ".to_string()],
-                start_operand.clone(),
+                end.clone(),
                 vec![" ".to_string()],
                 vec![turing_machine::Direction::Stay],
             );
@@ -718,7 +1662,77 @@ mod tests {
     fn test_escaped_characters() {
         let result = build_regex_tree("\\*").unwrap();
         assert_eq!(result.operation, Operation::Symbol);
-        assert_eq!(result.symbol, "\\*");
+        assert_eq!(result.symbol, "*");
+        assert_eq!(build_regex_tree("\\.").unwrap().symbol, ".");
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let result = build_regex_tree("[a-c_\\]]").unwrap();
+        assert_eq!(result.operation, Operation::Class);
+        assert_eq!(result.symbols, vec!["a", "b", "c", "_", "]"]);
+        let result = build_regex_tree("[^<if>0-1-]").unwrap();
+        assert_eq!(result.operation, Operation::NegatedClass);
+        assert_eq!(result.symbols, vec!["if", "0", "1", "-"]);
+        assert_eq!(build_regex_tree(".").unwrap(), Regex::class(Vec::new(), true));
+        assert!(build_regex_tree("[a-").is_err());
+        assert!(build_regex_tree("[z-a]").is_err());
+        assert!(build_regex_tree("[]").is_err());
+    }
+
+    #[test]
+    fn test_repetition() {
+        let result = build_regex_tree("a{2,3}").unwrap();
+        assert_eq!(result.operation, Operation::Repeat);
+        assert_eq!((result.min, result.max), (2, Some(3)));
+        assert_eq!(build_regex_tree("a{2}").unwrap().max, Some(2));
+        assert_eq!(build_regex_tree("a{2,}").unwrap().max, None);
+        assert_eq!(result.expand_repeat().to_string(), "aaa?");
+        assert!(build_regex_tree("a{3,2}").is_err());
+        assert!(build_regex_tree("a{x}").is_err());
+        assert!(build_regex_tree("a{2").is_err());
+        assert!(build_regex_tree("a{1000}").is_ok());
+        assert!(build_regex_tree("a{1000000}").is_err());
+        assert!(build_regex_tree("a{2,1001}").is_err());
+        assert!(build_regex_tree("(a{10}){100}").is_ok());
+        assert!(build_regex_tree("((a{1000}){1000}){1000}").is_err());
+        let nested = Regex::repeat(Regex::repeat(Regex::symbol("a"), 1000, None), 1000, None);
+        assert!(regex_to_fsa(&nested).is_err());
+    }
+
+    #[test]
+    fn test_special_symbols() {
+        assert_eq!(build_regex_tree("ε").unwrap(), Regex::epsilon());
+        assert_eq!(build_regex_tree("∅").unwrap(), Regex::empty());
+        assert_eq!(build_regex_tree("<id>").unwrap().symbol, "id");
+        assert!(build_regex_tree("<id").is_err());
+        assert!(build_regex_tree("<>").is_err());
+        assert!(build_regex_tree("a}").is_err());
+        assert_eq!(build_regex_tree("#|1").unwrap().alphabet(), vec!["#", "1"]);
+    }
+
+    #[test]
+    fn test_regex_to_fsa_extended_syntax() {
+        let accepts = |r: &str, w: &[&str]| {
+            let fa = finite_automaton::FiniteAutomaton::from_tm(
+                &regex_to_fsa(&build_regex_tree(r).unwrap()).unwrap(),
+            )
+            .unwrap();
+            fa.simulate(w.iter().map(|s| s.to_string()).collect(), 100).unwrap().outcome
+                == crate::computer::Outcome::Accept
+        };
+        assert!(accepts("[a-c]{2,3}", &["a", "c"]));
+        assert!(accepts("[a-c]{2,3}", &["a", "c", "b"]));
+        assert!(!accepts("[a-c]{2,3}", &["a"]));
+        assert!(!accepts("[a-c]{2,3}", &["a", "b", "c", "a"]));
+        assert!(accepts("a{2,}", &["a", "a", "a", "a"]));
+        assert!(accepts("(0|1)[^0]#", &["1", "1", "#"]));
+        assert!(!accepts("(0|1)[^0]#", &["1", "0", "#"]));
+        assert!(accepts("<if>.<fi>", &["if", "fi", "fi"]));
+        assert!(accepts("\\.\\*", &[".", "*"]));
+        assert!(accepts("aε", &["a"]));
+        assert!(!accepts("a|∅", &["b"]));
+        assert!(accepts("a|∅", &["a"]));
     }
     #[test]
     fn test_complex_regex() {
@@ -760,4 +1774,101 @@ mod tests {
         let fsa = regex_to_fsa(&regex).unwrap();
         assert!(fsa.input_alphabet.contains(&"a".to_string()));
     }
+
+    #[test]
+    fn test_derivative() {
+        let regex = build_regex_tree("ab*|b").unwrap();
+        assert_eq!(regex.derivative("a").to_string(), "b*");
+        assert_eq!(regex.derivative("b").to_string(), "ε");
+        assert_eq!(regex.derivative("c"), Regex::empty());
+        let regex = build_regex_tree("(a|b){2,3}").unwrap();
+        assert_eq!(regex.derivative("a").to_string(), "(a|b){1,2}");
+        assert_eq!(
+            build_regex_tree("b|a").unwrap().canonical(),
+            build_regex_tree("a|b|a").unwrap().canonical()
+        );
+    }
+
+    #[test]
+    fn test_regex_to_dfa() {
+        for r in ["a(b*)(c|d)+e?", "(a|b)*abb", "[a-c]{2,3}.", "(0|1)[^0]#", "a|∅", "ε", "∅"] {
+            let regex = build_regex_tree(r).unwrap();
+            let dfa = regex_to_dfa(&regex);
+            assert!(dfa.is_deterministic());
+            let thompson = finite_automaton::FiniteAutomaton::from_tm(&regex_to_fsa(&regex).unwrap()).unwrap();
+            assert_eq!(dfa.distinguishing_word(&thompson), None);
+        }
+        let dfa = regex_to_dfa(&build_regex_tree("(a|b)*abb").unwrap());
+        assert_eq!(dfa.states.len(), 4);
+        assert_eq!(dfa.final_states, vec!["q3"]);
+    }
+
+    #[test]
+    fn test_display() {
+        for r in [
+            "(a|b)*c",
+            "a(b|c)+d?",
+            "a|bc*",
+            "(ab)*|\\*",
+            "[a-c\\-]{2,3}.",
+            "(<if>|<a\\>b>)[^x]{2,}ε|∅",
+            "(ab){3}\\.",
+        ] {
+            assert_eq!(build_regex_tree(r).unwrap().to_string(), r);
+        }
+        assert_eq!(build_regex_tree("((a)(b))").unwrap().to_string(), "ab");
+    }
+
+    #[test]
+    fn test_simplify() {
+        let simplified = |r: &Regex| r.simplify().to_string();
+        let a = Regex::symbol("a");
+        let concat = |l: Regex, r: Regex| {
+            Regex::operation(Operation::Concat, Some(Box::new(l)), Some(Box::new(r)))
+        };
+        let or = |l: Regex, r: Regex| {
+            Regex::operation(Operation::Or, Some(Box::new(l)), Some(Box::new(r)))
+        };
+        assert_eq!(simplified(&concat(Regex::epsilon(), a.clone())), "a");
+        assert_eq!(simplified(&concat(Regex::empty(), a.clone())), "∅");
+        assert_eq!(simplified(&or(Regex::empty(), a.clone())), "a");
+        assert_eq!(simplified(&or(Regex::epsilon(), a.clone())), "a?");
+        assert_eq!(simplified(&or(a.clone(), a.clone())), "a");
+        assert_eq!(simplified(&build_regex_tree("aa*").unwrap()), "a+");
+        assert_eq!(simplified(&build_regex_tree("ab*b").unwrap()), "ab+");
+        assert_eq!(simplified(&build_regex_tree("(a*)*").unwrap()), "a*");
+        assert_eq!(simplified(&build_regex_tree("(a?)+").unwrap()), "a*");
+        assert_eq!(simplified(&build_regex_tree("(a*|b)?").unwrap()), "a*|b");
+    }
+
+    #[test]
+    fn test_fsa_to_regex() {
+        let to_fa = |r: &str| {
+            finite_automaton::FiniteAutomaton::from_tm(
+                &regex_to_fsa(&build_regex_tree(r).unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+        for r in ["a(b*)(c|d)+e?", "(a|b)*abb", "((a|b)(a|b))*", "a*b*"] {
+            let fa = to_fa(r);
+            let converted = fsa_to_regex(&fa);
+            assert!(to_fa(&converted.to_string())
+                .distinguishing_word(&fa)
+                .is_none());
+        }
+
+        let mut fa = finite_automaton::FiniteAutomaton::new();
+        fa.initial_state = "q0".to_string();
+        fa.states = vec!["q0".to_string(), "q1".to_string()];
+        fa.input_alphabet = vec!["a".to_string()];
+        fa.add_transition("q0".to_string(), "a".to_string(), "q1".to_string());
+        assert_eq!(fsa_to_regex(&fa), Regex::empty());
+        assert_eq!(build_regex_tree(&fsa_to_regex(&fa).to_string()), Ok(Regex::empty()));
+        fa.final_states = vec!["q0".to_string()];
+        assert_eq!(fsa_to_regex(&fa), Regex::epsilon());
+        assert_eq!(build_regex_tree(&fsa_to_regex(&fa).to_string()), Ok(Regex::epsilon()));
+        fa.final_states = vec!["q1".to_string()];
+        fa.add_transition("q1".to_string(), "a".to_string(), "q1".to_string());
+        assert_eq!(fsa_to_regex(&fa).to_string(), "a+");
+    }
 }
@@ -0,0 +1,593 @@
+//! # Trace Module
+//!
+//! This module provides the events recorded by the simulations at every step, and their
+//! renderers.
+//!
+//! ## Main Structures
+//!
+//! - **TraceEvent**: A configuration reached by a computation, for every kind of computer.
+//! - **TraceFormat**: The formats the events can be rendered in.
+//!
+//! ## Formats
+//!
+//! - **Text**: One line per event, in the format printed since the first versions of the
+//!   simulator, such as `tm;state;first tape` or `ram;opcode;operand;accumulator`.
+//! - **JSON Lines**: One JSON object per event, with every field of the event.
+//! - **CSV**: A header followed by one row per event, with the columns `kind`, `depth`, `state`,
+//!   `heads`, `tapes`, `action`, `pc`, `acc`, `mov`, `memory`, `output`, `input_head`, `input`,
+//!   `stack`, `symbol`, `level` and `expression`, each holding the field of the same name, the
+//!   instruction executed by RAM machines in `action` and the states of finite automata in
+//!   `state`. The columns not relevant to an event are left empty.
+//!
+//! The events of the subroutines called by a computation follow the event of the step calling
+//! them, one level deeper.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::finite_automaton;
+use crate::ram_machine;
+use crate::turing_machine;
+
+/// A configuration reached by a computation.
+///
+/// # Variants
+/// * `Tm` - The state and the tapes of a Turing machine, with the transition fired to reach them
+/// * `Ram` - The registers, the memory by decimal address and the output written so far of a RAM
+///   machine, with the instruction about to be executed, read at the address `pc`
+/// * `Lambda` - A lambda expression reduced
+/// * `Fsm` - The states of a finite automaton, with the input left to read
+/// * `Pda` - The state of a pushdown automaton, with the input left to read and its stack, from
+///   the bottom to the top
+/// * `Cfg` - A node of a parse tree, at a level below its root
+///
+/// Every variant records its `depth`, the number of subroutine calls it is nested in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    Tm {
+        depth: usize,
+        state: String,
+        tapes: Vec<turing_machine::Tape>,
+        transition: Option<turing_machine::Transition>,
+    },
+    Ram {
+        depth: usize,
+        pc: usize,
+        ir: String,
+        ar: String,
+        acc: String,
+        mov: String,
+        input_head: usize,
+        memory: std::collections::BTreeMap<usize, String>,
+        output: String,
+    },
+    Lambda {
+        depth: usize,
+        expression: String,
+    },
+    Fsm {
+        depth: usize,
+        states: Vec<String>,
+        input: String,
+    },
+    Pda {
+        depth: usize,
+        state: String,
+        input: String,
+        stack: Vec<String>,
+    },
+    Cfg {
+        depth: usize,
+        level: usize,
+        symbol: String,
+    },
+}
+
+/// The formats the events can be rendered in.
+///
+/// # Variants
+/// * `Text` - The historical semicolon-separated lines
+/// * `Jsonl` - One JSON object per line
+/// * `Csv` - Comma-separated values, after a header
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceFormat {
+    Text,
+    Jsonl,
+    Csv,
+}
+
+impl turing_machine::FromString for TraceFormat {
+    fn from_string(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(TraceFormat::Text),
+            "jsonl" => Ok(TraceFormat::Jsonl),
+            "csv" => Ok(TraceFormat::Csv),
+            _ => Err(format!(
+                "Invalid trace format: '{}'. Expected 'text', 'jsonl' or 'csv'",
+                s
+            )),
+        }
+    }
+}
+
+/// The header of the CSV rendering.
+pub const CSV_HEADER: &str = "kind,depth,state,heads,tapes,action,pc,acc,mov,memory,output,input_head,input,stack,symbol,level,expression";
+
+/// Quotes a string as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = "\"".to_string();
+    for c in s.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted + "\""
+}
+
+/// Formats the memory of a RAM machine as a JSON object, from the addresses to their values.
+fn json_memory(memory: &std::collections::BTreeMap<usize, String>) -> String {
+    let cells: Vec<String> = memory
+        .iter()
+        .map(|(address, value)| format!("\"{}\":{}", address, json_string(value)))
+        .collect();
+    format!("{{{}}}", cells.join(","))
+}
+
+/// Formats strings as a JSON array of strings.
+fn json_array(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| json_string(item)).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Returns the name of the instruction of a RAM machine with its operand in decimal, or the
+/// opcode itself if it is unknown.
+fn ram_instruction(ir: &str, ar: &str) -> String {
+    let name = ram_machine::RamMachine::ram_opcode_lookup(ir).unwrap_or(ir.to_string());
+    match crate::utils::bin2int(ar.to_string()) {
+        Ok(operand) if !ar.is_empty() => format!("{} {}", name, operand),
+        _ => name,
+    }
+}
+
+impl TraceEvent {
+    /// Returns the kind of computer which recorded the event, as written in the `kind` field.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TraceEvent::Tm { .. } => "tm",
+            TraceEvent::Ram { .. } => "ram",
+            TraceEvent::Lambda { .. } => "lambda",
+            TraceEvent::Fsm { .. } => "fsm",
+            TraceEvent::Pda { .. } => "pda",
+            TraceEvent::Cfg { .. } => "cfg",
+        }
+    }
+
+    /// Returns the number of subroutine calls the event is nested in.
+    pub fn depth(&self) -> usize {
+        match self {
+            TraceEvent::Tm { depth, .. }
+            | TraceEvent::Ram { depth, .. }
+            | TraceEvent::Lambda { depth, .. }
+            | TraceEvent::Fsm { depth, .. }
+            | TraceEvent::Pda { depth, .. }
+            | TraceEvent::Cfg { depth, .. } => *depth,
+        }
+    }
+
+    /// Returns the event one subroutine call deeper, for the events of a subroutine.
+    pub fn nested(mut self) -> TraceEvent {
+        match &mut self {
+            TraceEvent::Tm { depth, .. }
+            | TraceEvent::Ram { depth, .. }
+            | TraceEvent::Lambda { depth, .. }
+            | TraceEvent::Fsm { depth, .. }
+            | TraceEvent::Pda { depth, .. }
+            | TraceEvent::Cfg { depth, .. } => *depth += 1,
+        }
+        self
+    }
+
+    /// Renders the event as a JSON object, on a single line.
+    pub fn to_json(&self) -> String {
+        let fields = match self {
+            TraceEvent::Tm {
+                depth,
+                state,
+                tapes,
+                transition,
+            } => {
+                let tapes: Vec<String> = tapes
+                    .iter()
+                    .map(|tape| {
+                        format!(
+                            "{{\"cells\":{},\"head\":{}}}",
+                            json_array(&tape.tape),
+                            tape.head
+                        )
+                    })
+                    .collect();
+                let transition = match transition {
+                    Some(transition) => json_string(&transition.to_string()),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "\"kind\":\"tm\",\"depth\":{},\"state\":{},\"tapes\":[{}],\"transition\":{}",
+                    depth,
+                    json_string(state),
+                    tapes.join(","),
+                    transition
+                )
+            }
+            TraceEvent::Ram {
+                depth,
+                pc,
+                ir,
+                ar,
+                acc,
+                mov,
+                input_head,
+                memory,
+                output,
+            } => format!(
+                "\"kind\":\"ram\",\"depth\":{},\"pc\":{},\"instruction\":{},\"ir\":{},\"ar\":{},\"acc\":{},\"mov\":{},\"input_head\":{},\"memory\":{},\"output\":{}",
+                depth,
+                pc,
+                json_string(&ram_instruction(ir, ar)),
+                json_string(ir),
+                json_string(ar),
+                json_string(acc),
+                json_string(mov),
+                input_head,
+                json_memory(memory),
+                json_string(output)
+            ),
+            TraceEvent::Lambda { depth, expression } => format!(
+                "\"kind\":\"lambda\",\"depth\":{},\"expression\":{}",
+                depth,
+                json_string(expression)
+            ),
+            TraceEvent::Fsm {
+                depth,
+                states,
+                input,
+            } => format!(
+                "\"kind\":\"fsm\",\"depth\":{},\"states\":{},\"input\":{}",
+                depth,
+                json_array(states),
+                json_string(input)
+            ),
+            TraceEvent::Pda {
+                depth,
+                state,
+                input,
+                stack,
+            } => format!(
+                "\"kind\":\"pda\",\"depth\":{},\"state\":{},\"input\":{},\"stack\":{}",
+                depth,
+                json_string(state),
+                json_string(input),
+                json_array(stack)
+            ),
+            TraceEvent::Cfg {
+                depth,
+                level,
+                symbol,
+            } => format!(
+                "\"kind\":\"cfg\",\"depth\":{},\"level\":{},\"symbol\":{}",
+                depth,
+                level,
+                json_string(symbol)
+            ),
+        };
+        format!("{{{}}}", fields)
+    }
+
+    /// Renders the event as a CSV row, with the columns of `CSV_HEADER`.
+    pub fn to_csv(&self) -> String {
+        // the columns filled by the event, the other ones being left empty
+        let fields: Vec<(&str, String)> = match self {
+            TraceEvent::Tm {
+                state,
+                tapes,
+                transition,
+                ..
+            } => vec![
+                ("state", state.clone()),
+                (
+                    "heads",
+                    tapes
+                        .iter()
+                        .map(|tape| tape.head.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                ),
+                (
+                    "tapes",
+                    tapes
+                        .iter()
+                        .map(|tape| tape.tape.concat())
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                ),
+                (
+                    "action",
+                    transition
+                        .as_ref()
+                        .map(|transition| transition.to_string())
+                        .unwrap_or_default(),
+                ),
+            ],
+            TraceEvent::Ram {
+                pc,
+                ir,
+                ar,
+                acc,
+                mov,
+                input_head,
+                memory,
+                output,
+                ..
+            } => vec![
+                ("action", ram_instruction(ir, ar)),
+                ("pc", pc.to_string()),
+                ("acc", acc.clone()),
+                ("mov", mov.clone()),
+                (
+                    "memory",
+                    memory
+                        .iter()
+                        .map(|(address, value)| format!("{}={}", address, value))
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                ),
+                ("output", output.clone()),
+                ("input_head", input_head.to_string()),
+            ],
+            TraceEvent::Lambda { expression, .. } => vec![("expression", expression.clone())],
+            TraceEvent::Fsm { states, input, .. } => vec![
+                ("state", finite_automaton::state_set_name(states)),
+                ("input", input.clone()),
+            ],
+            TraceEvent::Pda {
+                state,
+                input,
+                stack,
+                ..
+            } => vec![
+                ("state", state.clone()),
+                ("input", input.clone()),
+                ("stack", stack.join(" ")),
+            ],
+            TraceEvent::Cfg { level, symbol, .. } => vec![
+                ("symbol", symbol.clone()),
+                ("level", level.to_string()),
+            ],
+        };
+        let mut row = self.kind().to_string() + "," + &self.depth().to_string();
+        for column in CSV_HEADER.split(',').skip(2) {
+            row += ",";
+            if let Some((_, value)) = fields.iter().find(|(name, _)| *name == column) {
+                row += &csv_field(value);
+            }
+        }
+        row
+    }
+}
+
+impl std::fmt::Display for TraceEvent {
+    /// Formats the event as a line of the text rendering.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceEvent::Tm { state, tapes, .. } => {
+                write!(f, "tm;{};{}", state, tapes[0].tape.concat())
+            }
+            TraceEvent::Ram { ir, ar, acc, .. } => write!(f, "ram;{};{};{}", ir, ar, acc),
+            TraceEvent::Lambda { expression, .. } => write!(f, "{}", expression),
+            TraceEvent::Fsm { states, input, .. } => write!(
+                f,
+                "fsm;{};{}",
+                finite_automaton::state_set_name(states),
+                input
+            ),
+            TraceEvent::Pda {
+                state,
+                input,
+                stack,
+                ..
+            } => write!(f, "pda;{};{};{}", state, input, stack.join(" ")),
+            TraceEvent::Cfg { level, symbol, .. } => {
+                write!(f, "cfg;{}{}", " ".repeat(4 * level), symbol)
+            }
+        }
+    }
+}
+
+/// Renders events in a format.
+///
+/// # Arguments
+///
+/// * `events` - The events to render
+/// * `format` - The format of the rendering
+///
+/// # Returns
+///
+/// The lines of the rendering, starting with the header for the CSV format.
+pub fn render(events: &[TraceEvent], format: &TraceFormat) -> Vec<String> {
+    match format {
+        TraceFormat::Text => events.iter().map(|event| event.to_string()).collect(),
+        TraceFormat::Jsonl => events.iter().map(|event| event.to_json()).collect(),
+        TraceFormat::Csv => std::iter::once(CSV_HEADER.to_string())
+            .chain(events.iter().map(|event| event.to_csv()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing_machine::FromString;
+
+    fn tm_event() -> TraceEvent {
+        TraceEvent::Tm {
+            depth: 1,
+            state: "q1".to_string(),
+            tapes: vec![
+                turing_machine::Tape {
+                    tape: vec!["B".to_string(), "1".to_string()],
+                    head: 1,
+                },
+                turing_machine::Tape {
+                    tape: vec!["\"".to_string()],
+                    head: 0,
+                },
+            ],
+            transition: Some(turing_machine::Transition {
+                state: "q0".to_string(),
+                symbols: vec!["0".to_string(), "B".to_string()],
+                new_state: "q1".to_string(),
+                new_symbols: vec!["1".to_string(), "\"".to_string()],
+                directions: vec![
+                    turing_machine::Direction::Right,
+                    turing_machine::Direction::Stay,
+                ],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_render_text() {
+        let events = vec![
+            tm_event(),
+            TraceEvent::Ram {
+                depth: 0,
+                pc: 2,
+                ir: "1000".to_string(),
+                ar: "11".to_string(),
+                acc: "101".to_string(),
+                mov: "0".to_string(),
+                input_head: 0,
+                memory: std::collections::BTreeMap::from([(3, "101".to_string())]),
+                output: String::new(),
+            },
+            TraceEvent::Fsm {
+                depth: 0,
+                states: vec!["0".to_string(), "1".to_string()],
+                input: "ab".to_string(),
+            },
+            TraceEvent::Cfg {
+                depth: 0,
+                level: 2,
+                symbol: "a".to_string(),
+            },
+        ];
+        assert_eq!(
+            render(&events, &TraceFormat::Text),
+            vec![
+                "tm;q1;B1",
+                "ram;1000;11;101",
+                "fsm;{0,1};ab",
+                "cfg;        a"
+            ]
+        );
+        assert_eq!(events[0].clone().nested().depth(), 2);
+    }
+
+    #[test]
+    fn test_render_jsonl() {
+        assert_eq!(
+            tm_event().to_json(),
+            "{\"kind\":\"tm\",\"depth\":1,\"state\":\"q1\",\"tapes\":[{\"cells\":[\"B\",\"1\"],\"head\":1},{\"cells\":[\"\\\"\"],\"head\":0}],\"transition\":\"q0 q1 0 B 1 \\\" R S\"}"
+        );
+        let ram = TraceEvent::Ram {
+            depth: 0,
+            pc: 2,
+            ir: "1000".to_string(),
+            ar: "11".to_string(),
+            acc: "101".to_string(),
+            mov: "0".to_string(),
+            input_head: 4,
+            memory: std::collections::BTreeMap::from([
+                (0, "1000".to_string()),
+                (3, "101".to_string()),
+            ]),
+            output: "1".to_string(),
+        };
+        assert_eq!(
+            ram.to_json(),
+            "{\"kind\":\"ram\",\"depth\":0,\"pc\":2,\"instruction\":\"ST 3\",\"ir\":\"1000\",\"ar\":\"11\",\"acc\":\"101\",\"mov\":\"0\",\"input_head\":4,\"memory\":{\"0\":\"1000\",\"3\":\"101\"},\"output\":\"1\"}"
+        );
+        assert_eq!(json_string("a\\b\n\u{1}"), "\"a\\\\b\\n\\u0001\"");
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let events = vec![
+            tm_event(),
+            TraceEvent::Pda {
+                depth: 0,
+                state: "1".to_string(),
+                input: "a,b".to_string(),
+                stack: vec!["B".to_string(), "<x>".to_string()],
+            },
+            TraceEvent::Ram {
+                depth: 0,
+                pc: 2,
+                ir: "1000".to_string(),
+                ar: "11".to_string(),
+                acc: "101".to_string(),
+                mov: "0".to_string(),
+                input_head: 4,
+                memory: std::collections::BTreeMap::from([
+                    (0, "1000".to_string()),
+                    (3, "101".to_string()),
+                ]),
+                output: "1".to_string(),
+            },
+            TraceEvent::Cfg {
+                depth: 0,
+                level: 2,
+                symbol: "a".to_string(),
+            },
+        ];
+        assert_eq!(
+            render(&events, &TraceFormat::Csv),
+            vec![
+                CSV_HEADER,
+                "tm,1,q1,1 0,\"B1 \"\"\",\"q0 q1 0 B 1 \"\" R S\",,,,,,,,,,,",
+                "pda,0,1,,,,,,,,,,\"a,b\",B <x>,,,",
+                "ram,0,,,,ST 3,2,101,0,0=1000 3=101,1,4,,,,,",
+                "cfg,0,,,,,,,,,,,,,a,2,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_format_from_string() {
+        assert_eq!(
+            TraceFormat::from_string("jsonl").unwrap(),
+            TraceFormat::Jsonl
+        );
+        assert_eq!(TraceFormat::from_string("csv").unwrap(), TraceFormat::Csv);
+        assert!(TraceFormat::from_string("xml").is_err());
+    }
+}
@@ -8,6 +8,7 @@
 //! - **Tape**: Represents a single tape of a Turing machine, including its contents and head position.
 //! - **Transition**: Represents a transition rule in the Turing machine, including the current state, read symbols, next state, symbols to write, and head movement directions.
 //! - **Direction**: Enum for head movement directions (`Left`, `Right`, `Stay`).
+//! - **TuringMachineBuilder**: Constructs a Turing machine in code, completing its states and tape alphabet from the transitions.
 //!
 //! ## Traits
 //!
@@ -26,6 +27,7 @@
 //!
 //! - Create a new Turing machine with `TuringMachine::new()`.
 //! - Add states and transitions using `add_state` and `add_transition`.
+//! - Or construct a complete machine with `TuringMachine::builder()`.
 //! - Simulate the machine with `simulate`.
 //! - Convert multi-tape machines to single-tape with `convert_multitape_to_singletape_tm`.
 //! - Encode/decode machines with `to_encoding` and `encoding_to_tm`.
@@ -49,8 +51,12 @@
 //!
 //! This project is licensed under the MIT License. See the LICENSE file for details.
 
+use crate::compiled_machine;
 use crate::computer;
+use crate::macro_machine;
+use crate::trace;
 use crate::utils;
+use std::rc::Rc;
 
 /// A complete representation of a deterministic or non-deterministic Turing machine, supporting multiple tapes.
 ///
@@ -118,7 +124,7 @@ pub struct TuringMachine {
 /// - Symbols on the tape must be from the Turing machine's tape alphabet
 /// - The head position is zero-based and must always point to a valid position on the tape
 ///
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Tape {
     pub tape: Vec<String>,
     pub head: usize,
@@ -210,6 +216,29 @@ pub enum Direction {
     Stay,
 }
 
+/// Represents the order in which the configurations of a non-deterministic Turing machine are
+/// explored during a simulation.
+///
+/// # Variants
+///
+/// * `Bfs` - Breadth-first search, one level of the computation tree for each step
+/// * `Dfs` - Depth-first search, expanding at most `max_steps` configurations in total
+/// * `Iddfs` - Iterative deepening, depth-first searches with limits 1, 2, ... sharing a budget
+///   of `max_steps` configurations expanded
+/// * `Random` - A single branch, choosing each move at random with the given seed
+///
+/// Every strategy stops at the first accepting configuration found, reporting its branch.
+/// Deterministic machines have a single branch, so they are simulated in the same way
+/// whatever the strategy.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Strategy {
+    Bfs,
+    Dfs,
+    Iddfs,
+    Random(u64),
+}
+
 pub trait FromString {
     fn from_string(s: &str) -> Result<Self, String>
     where
@@ -227,6 +256,50 @@ impl FromString for Direction {
     }
 }
 
+impl FromString for Strategy {
+    fn from_string(s: &str) -> Result<Self, String> {
+        match s.split_once(':') {
+            None if s == "bfs" => Ok(Strategy::Bfs),
+            None if s == "dfs" => Ok(Strategy::Dfs),
+            None if s == "iddfs" => Ok(Strategy::Iddfs),
+            None if s == "random" => Ok(Strategy::Random(0)),
+            Some(("random", seed)) => seed
+                .parse()
+                .map(Strategy::Random)
+                .map_err(|_| format!("Invalid seed: '{}'", seed)),
+            _ => Err(format!(
+                "Invalid strategy: '{}'. Expected 'bfs', 'dfs', 'iddfs', 'random' or 'random:<seed>'",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Transition {
+    /// Formats the transition as a line of a `tm` file: its state, its new state, the symbols
+    /// read, the symbols written and the moves.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let moves: Vec<&str> = self
+            .directions
+            .iter()
+            .map(|direction| match direction {
+                Direction::Left => "L",
+                Direction::Right => "R",
+                Direction::Stay => "S",
+            })
+            .collect();
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.state,
+            self.new_state,
+            self.symbols.join(" "),
+            self.new_symbols.join(" "),
+            moves.join(" ")
+        )
+    }
+}
+
 impl PartialEq for Direction {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -246,46 +319,69 @@ impl PartialEq for Direction {
 /// Represents a node in the computation tree of a Turing machine simulation.
 ///
 /// Each `TreeElement` represents a configuration of the Turing machine at a particular
-/// step in its computation, storing the current state, tape contents, and computation history.
+/// step in its computation. The nodes are stored in an arena, each one pointing to the node
+/// it was reached from, so the computation history is rebuilt only for the reported branch.
 ///
 /// # Fields
 ///
 /// * `state` - The current state of the Turing machine
-/// * `tapes` - Vector of tapes representing the current configuration of all tapes
-/// * `computation` - Vector of strings recording the computation history in the format "tm;state;tape_content"
+/// * `tapes` - Vector of tapes representing the current configuration of all tapes, shared with
+///   the set of the visited configurations and released once the node can no longer be reported
+/// * `parent` - Index in the arena of the node this configuration was reached from
+/// * `computation` - The computation history events added by the step reaching this node,
+///   followed by the history of any subroutine called, one level deeper
 ///
 /// # Notes
 ///
 /// - Used internally by the simulation algorithm to track non-deterministic computations
-/// - Implements `PartialEq` based on tape contents and state for cycle detection
-/// - The computation history can be used to reconstruct the machine's execution path
 /// - Supports both single-tape and multi-tape configurations
 ///
 #[derive(Clone)]
 struct TreeElement {
     state: String,
-    tapes: Vec<Tape>,
-    computation: Vec<String>,
+    tapes: Rc<Vec<Tape>>,
+    parent: Option<usize>,
+    computation: Vec<trace::TraceEvent>,
 }
-impl PartialEq for TreeElement {
-    fn eq(&self, other: &Self) -> bool {
-        for (ind, tape) in self.tapes.iter().enumerate() {
-            if tape.tape != other.tapes[ind].tape || tape.head != other.tapes[ind].head {
-                return false;
-            }
-        }
-        self.state == other.state
-    }
+
+/// The data shared by the whole simulation of a Turing machine, whatever the order in which its
+/// configurations are explored.
+///
+/// # Fields
+///
+/// * `transitions_map` - The transitions of the machine, indexed by state and symbols read
+/// * `computer` - The Computer object that contains mappings for subroutine calls
+/// * `context` - The Server object that provides access to other computers for subroutine execution
+/// * `max_steps` - Maximum number of steps the simulation should run before stopping
+///
+struct Search<'a> {
+    transitions_map: std::collections::HashMap<String, Vec<Transition>>,
+    computer: &'a computer::Computer,
+    context: &'a computer::Server,
+    max_steps: usize,
 }
 
-/* fn print_tree(tree: &Vec<Vec<TreeElement>>) {
-    for (ind, level) in tree.iter().enumerate() {
-        println!("Level {}", ind);
-        for (ind2, element) in level.iter().enumerate() {
-            println!("{} {} {}", ind2, element.state, element.tapes[0].tape[element.tapes[0].head]);
-        }
+/// Builds the key of a configuration, used to detect the configurations already visited.
+///
+/// # Arguments
+///
+/// * `state` - The current state of the Turing machine
+/// * `tapes` - The tapes of the configuration, with their head positions
+///
+/// # Returns
+///
+/// The state and the tapes, shared with the configuration rather than copied. The keys are
+/// looked up by their hashes and compared in full only when the hashes are equal, so that two
+/// distinct configurations are never mistaken for each other.
+fn configuration_key(state: &str, tapes: &Rc<Vec<Tape>>) -> (String, Rc<Vec<Tape>>) {
+    (state.to_string(), Rc::clone(tapes))
+}
+
+impl Default for TuringMachine {
+    fn default() -> Self {
+        TuringMachine::new()
     }
-} */
+}
 
 impl TuringMachine {
     /// Creates a new empty `TuringMachine` instance with default values.
@@ -315,6 +411,16 @@ impl TuringMachine {
             next_state_id: 0,
         }
     }
+
+    /// Returns a builder constructing a Turing machine in code.
+    ///
+    /// # Returns
+    ///
+    /// A `TuringMachineBuilder` for a single-tape machine with `_` as blank symbol.
+    pub fn builder() -> TuringMachineBuilder {
+        TuringMachineBuilder::new()
+    }
+
     /// Adds a new state to the Turing machine and returns its name.
     ///
     /// The state name is generated using a counter, resulting in names like "state 0", "state 1", etc.
@@ -415,8 +521,10 @@ impl TuringMachine {
     /// # Returns
     ///
     /// Returns a `Result` containing either:
-    /// - Ok with a `SimulationResult` tuple containing:
-    ///   - Final state type ("accept", "reject", "halt", or current state)
+    /// - Ok with a `SimulationResult` containing:
+    ///   - The outcome: `Accept`, `Reject` or `Halt` in a final state, `Timeout` if a
+    ///     transition could still be followed, `Diverges` if it could but the search explored
+    ///     every reachable configuration, `Stuck` otherwise
     ///   - Final head position
     ///   - Final tape contents
     ///   - Number of steps executed
@@ -428,7 +536,18 @@ impl TuringMachine {
     /// - The simulation supports both deterministic and non-deterministic Turing machines
     /// - Supports multi-tape configurations through the internal tape_count property
     /// - Can execute subroutines by mapping states to other computers in the context
-    /// - Maintains computation history for each step of execution
+    /// - Maintains computation history for each step of execution, rebuilt at the end for the
+    ///   reported branch only, unless the context disables the trace
+    /// - Deterministic machines which do not call subroutines run on a `CompiledMachine`, which
+    ///   ends with `Outcome::Diverges`, holding the proof that the computation never halts, if
+    ///   the context detects loops
+    /// - If the context sets a block size, deterministic single-tape machines which do not call
+    ///   subroutines run on a `MacroMachine` instead, without computation history
+    /// - Loops can only be detected by the `CompiledMachine`, so detecting them fails with a
+    ///   block size, or on machines which are non-deterministic or call subroutines
+    /// - Non-deterministic machines explore each configuration once, recognizing the visited
+    ///   ones by their state and tapes, which the set of visited configurations shares with the
+    ///   nodes of the computation tree
     /// - Stops when reaching max_steps, a final state, or when no valid transitions exist
     pub fn simulate(
         &self,
@@ -441,220 +560,579 @@ impl TuringMachine {
         if max_steps == 0 {
             return Err("max steps should be greater than 0".to_string());
         }
-        let transitions_map = self.make_transition_map();
-        /* if transitions_map.is_empty(){
-            return Err("empty transition function".to_string());
-        } */
-        let mut tree = Vec::new();
-        tree.push(Vec::new());
-        let det = self.is_deterministic();
-        let mut tape = Vec::new();
-        if input.is_empty() || input[0] != self.blank_symbol {
-            tape.push(self.blank_symbol.clone());
+        let calls_subroutines = self
+            .transitions
+            .iter()
+            .any(|t| this_computer_object.mapping.contains_key(&t.new_state));
+        if !calls_subroutines && self.is_deterministic() {
+            let tape = self.initial_tapes(input.clone(), prev_head).swap_remove(0).tape;
+            if context.simulation.block_size > 0 {
+                if context.simulation.detect_loops {
+                    return Err("loops cannot be detected over blocks of cells".to_string());
+                }
+                if let Some(mut machine) =
+                    macro_machine::MacroMachine::new(self, context.simulation.block_size, &tape)
+                {
+                    return Ok(machine.simulate(&tape, prev_head, max_steps));
+                }
+            }
+            if let Some(machine) = compiled_machine::CompiledMachine::new(self, &tape) {
+                return Ok(machine.simulate(
+                    &tape,
+                    prev_head,
+                    max_steps,
+                    context.simulation.trace,
+                    context.simulation.detect_loops,
+                ));
+            }
         }
-        for symbol in input {
-            tape.push(symbol);
+        if context.simulation.detect_loops {
+            return Err(
+                "loops can only be detected on deterministic Turing machines which do not call \
+                 subroutines"
+                    .to_string(),
+            );
         }
-        let mut tapes = Vec::new();
-        tapes.push(Tape {
-            tape: tape.clone(),
-            head: prev_head,
-        });
-        for _ in 1..self.tape_count {
-            tapes.push(Tape {
-                tape: vec![self.blank_symbol.clone()],
-                head: 0,
+        self.simulate_tree(input, max_steps, this_computer_object, context, prev_head)
+    }
+
+    /// Simulates the execution of the Turing machine on a given input, exploring its computation
+    /// tree with the strategy of the context.
+    ///
+    /// # Arguments
+    ///
+    /// The same as `simulate`, whose results are also the same.
+    pub fn simulate_tree(
+        &self,
+        input: Vec<String>,
+        max_steps: usize,
+        this_computer_object: &computer::Computer,
+        context: &computer::Server,
+        prev_head: usize,
+    ) -> Result<computer::SimulationResult, String> {
+        let search = Search {
+            transitions_map: self.make_transition_map(),
+            computer: this_computer_object,
+            context,
+            max_steps,
+        };
+        let det = self.is_deterministic();
+        let tapes = Rc::new(self.initial_tapes(input, prev_head));
+        let mut arena = vec![TreeElement {
+            state: self.initial_state.clone(),
+            tapes: Rc::clone(&tapes),
+            parent: None,
+            computation: Vec::new(),
+        }];
+        if context.simulation.trace {
+            arena[0].computation.push(trace::TraceEvent::Tm {
+                depth: 0,
+                state: self.initial_state.clone(),
+                tapes: tapes.to_vec(),
+                transition: None,
             });
         }
-        tree[0].push(TreeElement {
-            state: self.initial_state.clone(),
-            tapes: tapes.clone(),
-            computation: vec![
-                "tm;".to_string()
-                    + &self.initial_state
-                    + ";"
-                    + &tapes[0].tape.clone().join(""),
-            ],
-        });
+        if !det {
+            match context.simulation.strategy {
+                Strategy::Bfs => {}
+                Strategy::Dfs => {
+                    let mut steps = 0;
+                    let (branch, cut) =
+                        self.depth_first(&search, arena.swap_remove(0), max_steps, &mut steps)?;
+                    let node = branch.len() - 1;
+                    return self.branch_result(branch, node, steps, !cut);
+                }
+                Strategy::Iddfs => return self.iterative_deepening(&search, arena.swap_remove(0)),
+                Strategy::Random(seed) => return self.random_walk(&search, arena, seed),
+            }
+        }
+        // deterministic machines follow a single path, so only non-deterministic ones need to
+        // remember the configurations they visited
+        let mut visited = std::collections::HashSet::new();
+        if !det {
+            visited.insert(configuration_key(&self.initial_state, &tapes));
+        }
+        let mut level = vec![0];
         let mut steps = 0;
         let mut halts = false;
+        let mut exhausted = false;
         while steps < max_steps && !halts {
             steps += 1;
             let mut new_level = Vec::new();
-            for (ind, element) in tree[tree.len() - 1].iter().enumerate() {
-                let state = element.state.clone();
-                if self.is_final(&state) && (self.is_deterministic() || state == self.accept_state)
-                {
+            for (ind, &node) in level.iter().enumerate() {
+                let state = arena[node].state.clone();
+                if self.is_final(&state) && (det || state == self.accept_state) {
                     halts = true;
                     break;
                 }
-                let mut key = state.clone();
-                for tapenum in 0..self.tape_count {
-                    key += &element.tapes[tapenum].tape[element.tapes[tapenum].head];
-                }
-                let mut found = false;
-                if transitions_map.contains_key(&key) {
-                    found = true;
-                    let possible_transitions =
-                        transitions_map.get(&key).unwrap_or(&Vec::new()).clone();
-                    for transition in possible_transitions.iter() {
-                        let mut this_computation = element.computation.clone();
-                        let mut new_tapes = Vec::new();
-                        for tapenum in 0..self.tape_count {
-                            let mut new_tape = element.tapes[tapenum].clone();
-                            new_tape.tape[new_tape.head] = transition.new_symbols[tapenum].clone();
-                            let new_head = match transition.directions[tapenum] {
-                                Direction::Left => {
-                                    if new_tape.head == 0 {
-                                        new_tape.tape.insert(0, self.blank_symbol.clone());
-                                        0
-                                    } else {
-                                        new_tape.head - 1
-                                    }
-                                }
-                                Direction::Right => {
-                                    if new_tape.head == new_tape.tape.len() - 1 {
-                                        new_tape.tape.push(self.blank_symbol.clone());
-                                    }
-                                    new_tape.head + 1
-                                }
-                                Direction::Stay => new_tape.head,
-                            };
-                            new_tape.head = new_head;
-                            new_tapes.push(new_tape);
-                        }
-                        let new_state = transition.new_state.clone();
-                        this_computation.push(
-                            "tm;".to_string()
-                                + &new_state
-                                + ";"
-                                + &new_tapes[0].tape.clone().join(""),
-                        );
-                        let subroutine_name: String = this_computer_object
-                            .get_mapping(&new_state)?;
-                        if subroutine_name != *"" {
-                            let remaining_steps = max_steps.saturating_sub(steps);
-                            let subroutine = context
-                                .get_computer(&subroutine_name)
-                                .ok_or_else(|| {
-                                    format!("cannot get computer with name '{}'", subroutine_name)
-                                })?;
-                            let new_tape_input = if subroutine.is_ram() {
-                                new_tapes[0]
-                                    .tape
-                                    .iter()
-                                    .filter(|symb| *symb != &self.blank_symbol)
-                                    .map(|symb| symb.as_str())
-                                    .collect::<Vec<&str>>()
-                                    .join("")
-                            } else {
-                                new_tapes[0].tape.clone().join("")
-                            };
-                            let (_, head_result, tape_result, steps_result, sub_computation) =
-                                subroutine.simulate(
-                                    &new_tape_input,
-                                    remaining_steps,
-                                    context,
-                                    new_tapes[0].head,
-                                )?;
-                            this_computation.extend(sub_computation);
-                            if subroutine.is_ram() {
-                                new_tapes[0].tape = [
-                                    vec![self.blank_symbol.clone()],
-                                    utils::input_string_to_vec(
-                                        self.input_alphabet.clone(),
-                                        tape_result[0].clone(),
-                                    ),
-                                ]
-                                .concat();
-                            } else {
-                                new_tapes[0].tape = tape_result;
-                            }
-                            new_tapes[0].head = head_result;
-                            steps += steps_result;
-                        }
-                        let el = TreeElement {
-                            state: new_state,
-                            tapes: new_tapes,
-                            computation: this_computation,
-                        };
-                        if !new_level.contains(&el) {
-                            new_level.push(el);
-                        }
+                let successors = self.successors(&search, &arena[node], steps)?;
+                let found = successors.is_some();
+                for (mut el, steps_result) in successors.unwrap_or_default() {
+                    steps += steps_result;
+                    if det || visited.insert(configuration_key(&el.state, &el.tapes)) {
+                        el.parent = Some(node);
+                        arena.push(el);
+                        new_level.push(arena.len() - 1);
                     }
                 }
-                if !found && new_level.is_empty() && ind == tree[tree.len() - 1].len() - 1 {
+                if !found && new_level.is_empty() && ind == level.len() - 1 {
                     halts = true;
                     break;
                 }
             }
-            if det && !halts && steps < max_steps {
-                tree.pop();
+            // every branch leads back to a configuration already visited
+            if new_level.is_empty() {
+                halts = true;
+                exhausted = true;
             }
-            tree.push(new_level);
-        }
-        tree.pop();
-        let mut last_level = tree.pop().ok_or_else(|| "empty tree".to_string())?;
-        if last_level.is_empty() {
-            return Err("Simulation ended with empty level".to_string());
+            if halts || steps >= max_steps {
+                break;
+            }
+            for node in level {
+                arena[node].tapes = Rc::default();
+            }
+            level = new_level;
         }
-        let mut previous = 0;
+        let mut previous = level[0];
         if !det {
             let mut max_priority = -1;
-            for (ind, element) in last_level.iter().enumerate() {
-                let priority = if element.state == self.accept_state && !self.accept_state.is_empty() {
-                    3
-                } else if element.state == self.halt_state && !self.halt_state.is_empty() {
-                    2
-                } else if element.state == self.reject_state && !self.reject_state.is_empty() {
-                    1
-                } else if self.is_final(&element.state) {
-                    2
-                } else {
-                    0
-                };
+            for &node in level.iter() {
+                let priority = self.priority(&arena[node].state);
                 if priority > max_priority {
                     max_priority = priority;
-                    previous = ind;
+                    previous = node;
                 }
             }
         }
-        let last_element = last_level.remove(previous);
-        let mut tapes = last_element.tapes;
-        let first_tape = tapes.remove(0);
-        if self.accept_state == last_element.state {
-            Ok((
-                "accept".to_string(),
-                first_tape.head,
-                first_tape.tape,
-                steps,
-                last_element.computation,
-            ))
+        self.branch_result(arena, previous, steps, exhausted)
+    }
+
+    /// Computes the configurations reached from a configuration with a single move.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The data shared by the whole simulation
+    /// * `element` - The configuration to move from
+    /// * `steps` - The number of steps already executed, limiting the steps left to subroutines
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing either:
+    /// - Ok with `None` if no transition can be taken, otherwise with the new configurations,
+    ///   without a parent, each with the number of steps executed by the subroutine it called
+    /// - Err with an error message if a subroutine fails
+    fn successors(
+        &self,
+        search: &Search,
+        element: &TreeElement,
+        steps: usize,
+    ) -> Result<Option<Vec<(TreeElement, usize)>>, String> {
+        let mut key = element.state.clone();
+        for tape in element.tapes.iter() {
+            key += &tape.tape[tape.head];
+        }
+        let Some(possible_transitions) = search.transitions_map.get(&key) else {
+            return Ok(None);
+        };
+        let mut successors = Vec::new();
+        for transition in possible_transitions.iter() {
+            let mut new_tapes = self.apply_transition(transition, &element.tapes);
+            let new_state = transition.new_state.clone();
+            let mut this_computation = Vec::new();
+            if search.context.simulation.trace {
+                this_computation.push(trace::TraceEvent::Tm {
+                    depth: 0,
+                    state: new_state.clone(),
+                    tapes: new_tapes.clone(),
+                    transition: Some(transition.clone()),
+                });
+            }
+            let mut steps_result = 0;
+            let subroutine_name: String = search.computer.get_mapping(&new_state)?;
+            if subroutine_name != *"" {
+                let remaining_steps = search.max_steps.saturating_sub(steps);
+                let subroutine = search
+                    .context
+                    .get_computer(&subroutine_name)
+                    .ok_or_else(|| format!("cannot get computer with name '{}'", subroutine_name))?;
+                let new_tape_input = if subroutine.is_ram() {
+                    new_tapes[0]
+                        .tape
+                        .iter()
+                        .filter(|symb| *symb != &self.blank_symbol)
+                        .map(|symb| symb.as_str())
+                        .collect::<Vec<&str>>()
+                        .join("")
+                } else {
+                    new_tapes[0].tape.clone().join("")
+                };
+                let computer::SimulationResult {
+                    head: head_result,
+                    tape: tape_result,
+                    steps: sub_steps,
+                    computation: sub_computation,
+                    ..
+                } = subroutine.simulate(
+                    &new_tape_input,
+                    remaining_steps,
+                    search.context,
+                    new_tapes[0].head,
+                )?;
+                if search.context.simulation.trace {
+                    this_computation.extend(sub_computation.into_iter().map(|e| e.nested()));
+                }
+                if subroutine.is_ram() {
+                    new_tapes[0].tape = [
+                        vec![self.blank_symbol.clone()],
+                        utils::input_string_to_vec(
+                            self.input_alphabet.clone(),
+                            tape_result[0].clone(),
+                        ),
+                    ]
+                    .concat();
+                } else {
+                    new_tapes[0].tape = tape_result;
+                }
+                new_tapes[0].head = head_result;
+                steps_result = sub_steps;
+            }
+            successors.push((
+                TreeElement {
+                    state: new_state,
+                    tapes: Rc::new(new_tapes),
+                    parent: None,
+                    computation: this_computation,
+                },
+                steps_result,
+            ));
+        }
+        Ok(Some(successors))
+    }
+
+    /// Builds the tapes of the initial configuration on an input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input symbols, written on the first tape after a blank symbol unless they
+    ///   start with one
+    /// * `head` - The initial position of the head of the first tape
+    ///
+    /// # Returns
+    ///
+    /// The first tape with the input, followed by the other tapes, holding a single blank symbol.
+    pub fn initial_tapes(&self, input: Vec<String>, head: usize) -> Vec<Tape> {
+        let mut tape = Vec::new();
+        if input.is_empty() || input[0] != self.blank_symbol {
+            tape.push(self.blank_symbol.clone());
+        }
+        tape.extend(input);
+        let mut tapes = vec![Tape { tape, head }];
+        for _ in 1..self.tape_count {
+            tapes.push(Tape {
+                tape: vec![self.blank_symbol.clone()],
+                head: 0,
+            });
+        }
+        tapes
+    }
+
+    /// Applies a transition to the tapes of a configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `transition` - The transition to apply, whose symbols are the ones under the heads
+    /// * `tapes` - The tapes of the configuration
+    ///
+    /// # Returns
+    ///
+    /// The tapes with the new symbols written and the heads moved, extended with a blank symbol
+    /// whenever a head moves past one of their ends.
+    pub fn apply_transition(&self, transition: &Transition, tapes: &[Tape]) -> Vec<Tape> {
+        let mut new_tapes = Vec::new();
+        for (tapenum, tape) in tapes.iter().enumerate().take(self.tape_count) {
+            let mut new_tape = tape.clone();
+            new_tape.tape[new_tape.head] = transition.new_symbols[tapenum].clone();
+            let new_head = match transition.directions[tapenum] {
+                Direction::Left => {
+                    if new_tape.head == 0 {
+                        new_tape.tape.insert(0, self.blank_symbol.clone());
+                        0
+                    } else {
+                        new_tape.head - 1
+                    }
+                }
+                Direction::Right => {
+                    if new_tape.head == new_tape.tape.len() - 1 {
+                        new_tape.tape.push(self.blank_symbol.clone());
+                    }
+                    new_tape.head + 1
+                }
+                Direction::Stay => new_tape.head,
+            };
+            new_tape.head = new_head;
+            new_tapes.push(new_tape);
+        }
+        new_tapes
+    }
+
+    /// Ranks the state of a configuration, to choose the branch to report when the input is
+    /// not accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to rank
+    ///
+    /// # Returns
+    ///
+    /// 3 for the accept state, 2 for the halt state (and any other final state), 1 for the
+    /// reject state and 0 for the other states.
+    fn priority(&self, state: &String) -> i32 {
+        if *state == self.accept_state && !self.accept_state.is_empty() {
+            3
+        } else if *state == self.halt_state && !self.halt_state.is_empty() {
+            2
+        } else if *state == self.reject_state && !self.reject_state.is_empty() {
+            1
+        } else if self.is_final(state) {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Explores the configurations depth-first, up to a maximum depth, stopping at the first
+    /// accepting configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The data shared by the whole simulation
+    /// * `root` - The initial configuration
+    /// * `limit` - The maximum number of moves along each branch
+    /// * `steps` - The number of steps executed, incremented for each configuration expanded;
+    ///   the search stops when it reaches the `max_steps` of the simulation
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing either:
+    /// - Ok with the branch to report, from the root to the reported node, and whether the
+    ///   limit or the steps running out cut some branch. The node is the accepting one, or the first one in the best
+    ///   final state, or the one expanded when the steps ran out, or the first leaf left.
+    /// - Err with an error message if a subroutine fails
+    ///
+    /// # Notes
+    ///
+    /// - Only the current branch is kept, with the configurations reached from its nodes which
+    ///   are still to be explored: a subtree is dropped as soon as it has been explored
+    /// - A configuration is explored again only when it is reached with fewer moves
+    /// - The transitions are tried in the order they are defined
+    fn depth_first(
+        &self,
+        search: &Search,
+        root: TreeElement,
+        limit: usize,
+        steps: &mut usize,
+    ) -> Result<(Vec<TreeElement>, bool), String> {
+        let mut depths = std::collections::HashMap::new();
+        depths.insert(configuration_key(&root.state, &root.tapes), 0);
+        // branch[i] is the node at depth i, pending[i] the configurations still to explore
+        // from it, the next one last
+        let mut branch = vec![root];
+        let mut pending: Vec<Vec<TreeElement>> = Vec::new();
+        let mut best: Option<Vec<TreeElement>> = None;
+        let mut best_priority = 0;
+        let mut first_leaf: Option<Vec<TreeElement>> = None;
+        let mut cut = false;
+        loop {
+            let depth = branch.len() - 1;
+            let state = branch[depth].state.clone();
+            let priority = self.priority(&state);
+            if priority == 3 {
+                return Ok((branch, cut));
+            }
+            if priority > best_priority {
+                best_priority = priority;
+                best = Some(branch.clone());
+            }
+            let mut children = Vec::new();
+            if !self.is_final(&state) {
+                if depth >= limit {
+                    cut = true;
+                } else if *steps >= search.max_steps {
+                    return Ok((best.unwrap_or(branch), true));
+                } else {
+                    *steps += 1;
+                    let successors = self.successors(search, &branch[depth], *steps)?;
+                    for (mut el, steps_result) in successors.unwrap_or_default() {
+                        *steps += steps_result;
+                        let key = configuration_key(&el.state, &el.tapes);
+                        if depths.get(&key).is_some_and(|d| *d <= depth + 1) {
+                            continue;
+                        }
+                        depths.insert(key, depth + 1);
+                        el.parent = Some(depth);
+                        children.push(el);
+                    }
+                }
+            }
+            children.reverse();
+            pending.push(children);
+            // moves to the next configuration to explore, dropping the explored subtrees
+            loop {
+                if let Some(next) = pending.last_mut().and_then(|children| children.pop()) {
+                    let parent = branch.len() - 1;
+                    branch[parent].tapes = Rc::default();
+                    branch.push(next);
+                    break;
+                }
+                pending.pop();
+                if first_leaf.is_none() && best.is_none() {
+                    first_leaf = Some(branch.clone());
+                }
+                branch.pop();
+                if branch.is_empty() {
+                    return Ok((best.or(first_leaf).unwrap_or_default(), cut));
+                }
+            }
+        }
+    }
+
+    /// Explores the configurations with depth-first searches of increasing depth, so that the
+    /// shortest accepting branch is found, like with a breadth-first search, keeping only the
+    /// current branch of each search.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The data shared by the whole simulation
+    /// * `root` - The initial configuration
+    ///
+    /// # Returns
+    ///
+    /// The `SimulationResult` of the accepting branch, or of the branch reported by the last
+    /// depth-first search, with the steps executed by all the searches. The searches share the
+    /// `max_steps` of the simulation, counting the configurations expanded by each of them.
+    fn iterative_deepening(
+        &self,
+        search: &Search,
+        root: TreeElement,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut steps = 0;
+        let mut limit = 1;
+        loop {
+            let (branch, cut) = self.depth_first(search, root.clone(), limit, &mut steps)?;
+            let node = branch.len() - 1;
+            if !cut || steps >= search.max_steps || branch[node].state == self.accept_state {
+                return self.branch_result(branch, node, steps, !cut);
+            }
+            limit += 1;
+        }
+    }
+
+    /// Follows a single branch, choosing each move at random among the possible ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The data shared by the whole simulation
+    /// * `arena` - The arena of the configurations, holding the initial configuration only
+    /// * `seed` - The seed of the pseudo-random generator, so that runs can be repeated
+    ///
+    /// # Returns
+    ///
+    /// The `SimulationResult` of the branch, ending in a final state, in a configuration
+    /// without moves or after `max_steps` moves.
+    fn random_walk(
+        &self,
+        search: &Search,
+        mut arena: Vec<TreeElement>,
+        seed: u64,
+    ) -> Result<computer::SimulationResult, String> {
+        // xorshift generator, whose state must not be zero
+        let mut random = seed ^ 0x9e37_79b9_7f4a_7c15;
+        let mut node = 0;
+        let mut steps = 0;
+        while steps < search.max_steps && !self.is_final(&arena[node].state) {
+            steps += 1;
+            let mut successors = match self.successors(search, &arena[node], steps)? {
+                Some(successors) => successors,
+                None => break,
+            };
+            random ^= random << 13;
+            random ^= random >> 7;
+            random ^= random << 17;
+            let (mut el, steps_result) =
+                successors.swap_remove((random % successors.len() as u64) as usize);
+            steps += steps_result;
+            el.parent = Some(node);
+            arena[node].tapes = Rc::default();
+            arena.push(el);
+            node = arena.len() - 1;
+        }
+        self.branch_result(arena, node, steps, false)
+    }
+
+    /// Builds the result of a simulation from the node reached by the reported branch.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - The arena of the configurations
+    /// * `node` - The index of the reported node, whose tapes must be kept
+    /// * `steps` - The number of steps executed
+    /// * `exhausted` - Whether every reachable configuration was explored, so that a node which
+    ///   could still move diverges instead of timing out
+    ///
+    /// # Returns
+    ///
+    /// The `SimulationResult` of the node, with the computation history of its branch.
+    fn branch_result(
+        &self,
+        mut arena: Vec<TreeElement>,
+        node: usize,
+        steps: usize,
+        exhausted: bool,
+    ) -> Result<computer::SimulationResult, String> {
+        let mut computation = Vec::new();
+        let mut branch = Some(node);
+        while let Some(node) = branch {
+            computation.push(std::mem::take(&mut arena[node].computation));
+            branch = arena[node].parent;
+        }
+        let computation: Vec<trace::TraceEvent> =
+            computation.into_iter().rev().flatten().collect();
+        let last_element = arena.swap_remove(node);
+        let read: Vec<String> = last_element
+            .tapes
+            .iter()
+            .map(|tape| tape.tape[tape.head].clone())
+            .collect();
+        let outcome = if self.accept_state == last_element.state {
+            computer::Outcome::Accept
         } else if self.reject_state == last_element.state {
-            Ok((
-                "reject".to_string(),
-                first_tape.head,
-                first_tape.tape,
-                steps,
-                last_element.computation,
-            ))
+            computer::Outcome::Reject
         } else if self.is_final(&last_element.state) {
-            Ok((
-                "halt".to_string(),
-                first_tape.head,
-                first_tape.tape,
-                steps,
-                last_element.computation,
-            ))
+            computer::Outcome::Halt
+        } else if self
+            .transitions
+            .iter()
+            .any(|t| t.state == last_element.state && t.symbols == read)
+        {
+            if exhausted {
+                computer::Outcome::Diverges(
+                    "every reachable configuration explored without accepting".to_string(),
+                )
+            } else {
+                computer::Outcome::Timeout
+            }
         } else {
-            Ok((
-                last_element.state,
-                first_tape.head,
-                first_tape.tape,
-                steps,
-                last_element.computation,
-            ))
-        }
+            computer::Outcome::Stuck(last_element.state)
+        };
+        let mut tapes = Rc::unwrap_or_clone(last_element.tapes);
+        let first_tape = tapes.remove(0);
+        Ok(computer::SimulationResult {
+            outcome,
+            head: first_tape.head,
+            tape: first_tape.tape,
+            steps,
+            computation,
+        })
     }
 
     /// Converts the Turing machine into an encoded format for standardized representation.
@@ -2233,6 +2711,170 @@ impl TuringMachine {
         Ok(true)
     }
 }
+
+/// A builder of Turing machines, constructing them in code instead of reading them from files.
+///
+/// The states are collected from the initial and final states and from the transitions, in
+/// the order in which they appear. The tape alphabet always contains the blank symbol, the
+/// input alphabet and the symbols read or written by the transitions.
+///
+/// # Fields
+///
+/// * `machine` - The Turing machine built so far
+#[derive(Clone)]
+pub struct TuringMachineBuilder {
+    machine: TuringMachine,
+}
+
+impl Default for TuringMachineBuilder {
+    fn default() -> Self {
+        TuringMachineBuilder::new()
+    }
+}
+
+impl TuringMachineBuilder {
+    /// Creates a builder for a single-tape machine with `_` as blank symbol.
+    pub fn new() -> Self {
+        TuringMachineBuilder {
+            machine: TuringMachine {
+                blank_symbol: "_".to_string(),
+                ..TuringMachine::new()
+            },
+        }
+    }
+
+    /// Sets the initial state.
+    pub fn initial_state(mut self, state: &str) -> Self {
+        self.machine.initial_state = state.to_string();
+        self
+    }
+
+    /// Sets the accepting state.
+    pub fn accept_state(mut self, state: &str) -> Self {
+        self.machine.accept_state = state.to_string();
+        self
+    }
+
+    /// Sets the rejecting state.
+    pub fn reject_state(mut self, state: &str) -> Self {
+        self.machine.reject_state = state.to_string();
+        self
+    }
+
+    /// Sets the halting state.
+    pub fn halt_state(mut self, state: &str) -> Self {
+        self.machine.halt_state = state.to_string();
+        self
+    }
+
+    /// Sets the blank symbol.
+    pub fn blank_symbol(mut self, symbol: &str) -> Self {
+        self.machine.blank_symbol = symbol.to_string();
+        self
+    }
+
+    /// Sets the number of tapes.
+    pub fn tapes(mut self, tape_count: usize) -> Self {
+        self.machine.tape_count = tape_count;
+        self
+    }
+
+    /// Adds symbols to the input alphabet.
+    pub fn input_alphabet(mut self, symbols: &[&str]) -> Self {
+        for symbol in symbols {
+            if !self.machine.input_alphabet.iter().any(|s| s == symbol) {
+                self.machine.input_alphabet.push(symbol.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds symbols to the tape alphabet, for the ones never read nor written by a transition.
+    pub fn tape_alphabet(mut self, symbols: &[&str]) -> Self {
+        for symbol in symbols {
+            if !self.machine.tape_alphabet.iter().any(|s| s == symbol) {
+                self.machine.tape_alphabet.push(symbol.to_string());
+            }
+        }
+        self
+    }
+
+    /// Adds a transition.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state from which the transition starts
+    /// * `symbols` - The symbols to read, one for each tape
+    /// * `new_state` - The state to transition to
+    /// * `new_symbols` - The symbols to write, one for each tape
+    /// * `directions` - The directions of the heads, one for each tape
+    pub fn transition(
+        mut self,
+        state: &str,
+        symbols: &[&str],
+        new_state: &str,
+        new_symbols: &[&str],
+        directions: &[Direction],
+    ) -> Self {
+        self.machine.add_transition(
+            state.to_string(),
+            symbols.iter().map(|s| s.to_string()).collect(),
+            new_state.to_string(),
+            new_symbols.iter().map(|s| s.to_string()).collect(),
+            directions.to_vec(),
+        );
+        self
+    }
+
+    /// Builds the Turing machine.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TuringMachine)` - The machine, with its states and tape alphabet completed
+    /// * `Err(String)` - An error message if the initial state is missing, a transition does
+    ///   not have one symbol and direction for each tape, or the machine is not valid
+    pub fn build(self) -> Result<TuringMachine, String> {
+        let mut tm = self.machine;
+        if tm.initial_state.is_empty() {
+            return Err("the initial state is not set".to_string());
+        }
+        if let Some(t) = tm.transitions.iter().find(|t| {
+            t.symbols.len() != tm.tape_count
+                || t.new_symbols.len() != tm.tape_count
+                || t.directions.len() != tm.tape_count
+        }) {
+            return Err(format!(
+                "transition '{}' does not work on {} tapes",
+                t, tm.tape_count
+            ));
+        }
+        let mut states = vec![tm.initial_state.clone()];
+        let mut symbols = vec![tm.blank_symbol.clone()];
+        symbols.extend(tm.input_alphabet.iter().cloned());
+        for t in tm.transitions.iter() {
+            states.push(t.state.clone());
+            states.push(t.new_state.clone());
+            symbols.extend(t.symbols.iter().cloned());
+            symbols.extend(t.new_symbols.iter().cloned());
+        }
+        states.extend(tm.final_states());
+        for state in states {
+            if !tm.states.contains(&state) {
+                tm.states.push(state);
+            }
+        }
+        for symbol in symbols {
+            if !tm.tape_alphabet.contains(&symbol) {
+                tm.tape_alphabet.push(symbol);
+            }
+        }
+        if !tm.is_ok() {
+            return Err("the Turing machine is not valid".to_string());
+        }
+        Ok(tm)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2460,7 +3102,7 @@ mod tests {
         let context = computer::Server::new();
 
         // Should accept "1"
-        let result: (String, usize, Vec<String>, usize, Vec<String>) = tm
+        let result: computer::SimulationResult = tm
             
             .simulate(
                 vec!["1".to_string()],
@@ -2470,7 +3112,7 @@ mod tests {
                 0,
             )
             .unwrap();
-        assert_eq!(result.0, "accept");
+        assert_eq!(result.outcome, computer::Outcome::Accept);
 
         // Should accept "01"
         let result = tm
@@ -2483,7 +3125,7 @@ mod tests {
                 0,
             )
             .unwrap();
-        assert_eq!(result.0, "accept");
+        assert_eq!(result.outcome, computer::Outcome::Accept);
 
         // Should reject "0"
         let result = tm
@@ -2496,11 +3138,270 @@ mod tests {
                 0,
             )
             .unwrap();
-        assert_eq!(result.0, "reject");
+        assert_eq!(result.outcome, computer::Outcome::Reject);
 
         // Should reject empty input
         let result = tm.simulate(vec![], 100, &computer, &context, 0).unwrap();
-        assert_eq!(result.0, "reject");
+        assert_eq!(result.outcome, computer::Outcome::Reject);
+    }
+
+    fn guessing_machine() -> TuringMachine {
+        let mut tm = TuringMachine::new();
+        tm.blank_symbol = "B".to_string();
+        tm.initial_state = "qs".to_string();
+        tm.accept_state = "qa".to_string();
+        tm.states = vec![
+            "qs".to_string(),
+            "q0".to_string(),
+            "q1".to_string(),
+            "qa".to_string(),
+        ];
+        tm.input_alphabet = vec!["0".to_string(), "1".to_string()];
+        tm.tape_alphabet = vec!["0".to_string(), "1".to_string(), "B".to_string()];
+        let mut add = |state: &str, symbol: &str, new_state: &str, direction: Direction| {
+            tm.add_transition(
+                state.to_string(),
+                vec![symbol.to_string()],
+                new_state.to_string(),
+                vec![symbol.to_string()],
+                vec![direction],
+            )
+        };
+        // guesses which 1 to stop on
+        add("qs", "B", "q0", Direction::Right);
+        add("q0", "0", "q0", Direction::Right);
+        add("q0", "1", "q0", Direction::Right);
+        add("q0", "1", "qa", Direction::Stay);
+        // loops forever without reading the input
+        add("q0", "B", "q1", Direction::Stay);
+        add("q0", "B", "q0", Direction::Stay);
+        add("q1", "B", "q0", Direction::Stay);
+        assert!(!tm.is_deterministic());
+
+        tm
+    }
+
+    #[test]
+    fn test_nondeterministic_simulation() {
+        let tm = guessing_machine();
+        assert!(!tm.is_deterministic());
+
+        let computer = computer::Computer::new();
+        let context = computer::Server::new();
+        let input: Vec<String> = "0010".chars().map(|c| c.to_string()).collect();
+        let computer::SimulationResult { outcome: state, head, computation, .. } =
+            tm.simulate(input, 100, &computer, &context, 0).unwrap();
+        assert_eq!(state, computer::Outcome::Accept);
+        assert_eq!(head, 3);
+        assert_eq!(computation.len(), 5);
+        assert_eq!(computation[0].to_string(), "tm;qs;B0010");
+        assert_eq!(computation[4].to_string(), "tm;qa;B0010");
+
+        // every branch goes back to a configuration already visited
+        let input: Vec<String> = "00".chars().map(|c| c.to_string()).collect();
+        let computer::SimulationResult { outcome: state, steps, .. } =
+            tm.simulate(input, 1_000_000, &computer, &context, 0).unwrap();
+        assert_ne!(state, computer::Outcome::Accept);
+        assert!(steps < 10);
+    }
+
+    #[test]
+    fn test_exhausted_search() {
+        // moves back and forth between two states without ever halting
+        let tm = TuringMachine::builder()
+            .initial_state("1")
+            .halt_state("h")
+            .blank_symbol("_")
+            .input_alphabet(&["0"])
+            .transition("1", &["_"], "1", &["_"], &[Direction::Stay])
+            .transition("1", &["_"], "2", &["_"], &[Direction::Stay])
+            .transition("2", &["_"], "1", &["_"], &[Direction::Stay])
+            .build()
+            .unwrap();
+        let computer = computer::Computer::new();
+        let mut context = computer::Server::new();
+        for strategy in [Strategy::Bfs, Strategy::Dfs, Strategy::Iddfs] {
+            context.simulation.strategy = strategy.clone();
+            let result = tm.simulate(Vec::new(), 1000, &computer, &context, 0).unwrap();
+            assert!(
+                matches!(result.outcome, computer::Outcome::Diverges(_)),
+                "{:?}",
+                strategy
+            );
+            assert!(result.steps < 10);
+        }
+
+        context.simulation.detect_loops = true;
+        assert!(tm.simulate(Vec::new(), 1000, &computer, &context, 0).is_err());
+    }
+
+    #[test]
+    fn test_configuration_key() {
+        let tapes = Rc::new(vec![Tape {
+            tape: vec!["_".to_string(), "0".to_string()],
+            head: 1,
+        }]);
+        let key = configuration_key("1", &tapes);
+        // the key shares the tapes of the configuration instead of copying them
+        assert!(Rc::ptr_eq(&key.1, &tapes));
+        let mut visited = std::collections::HashSet::new();
+        assert!(visited.insert(key));
+        assert!(!visited.insert(configuration_key("1", &Rc::new(tapes.to_vec()))));
+        assert!(visited.insert(configuration_key("2", &tapes)));
+    }
+
+    #[test]
+    fn test_strategies() {
+        assert_eq!(Strategy::from_string("iddfs").unwrap(), Strategy::Iddfs);
+        assert_eq!(Strategy::from_string("random").unwrap(), Strategy::Random(0));
+        assert_eq!(Strategy::from_string("random:42").unwrap(), Strategy::Random(42));
+        assert!(Strategy::from_string("random:x").is_err());
+        assert!(Strategy::from_string("best").is_err());
+
+        let tm = guessing_machine();
+        let computer = computer::Computer::new();
+        let mut context = computer::Server::new();
+        let input: Vec<String> = "0010".chars().map(|c| c.to_string()).collect();
+        for strategy in [Strategy::Bfs, Strategy::Dfs, Strategy::Iddfs] {
+            context.simulation.strategy = strategy.clone();
+            let computer::SimulationResult { outcome: state, head, tape, computation, .. } = tm
+                .simulate(input.clone(), 100, &computer, &context, 0)
+                .unwrap();
+            assert_eq!(state, computer::Outcome::Accept, "{:?}", strategy);
+            assert_eq!(head, 3);
+            assert_eq!(tape.join(""), "B0010");
+            assert_eq!(computation.last().unwrap().to_string(), "tm;qa;B0010");
+            if strategy != Strategy::Dfs {
+                assert_eq!(computation.len(), 5);
+            }
+        }
+
+        let input: Vec<String> = "000".chars().map(|c| c.to_string()).collect();
+        context.simulation.strategy = Strategy::Dfs;
+        let result = tm.simulate(input.clone(), 100, &computer, &context, 0).unwrap();
+        assert_ne!(result.outcome, computer::Outcome::Accept);
+
+        let input: Vec<String> = "0101".chars().map(|c| c.to_string()).collect();
+        context.simulation.strategy = Strategy::Random(7);
+        let first = tm.simulate(input.clone(), 50, &computer, &context, 0).unwrap();
+        let second = tm.simulate(input.clone(), 50, &computer, &context, 0).unwrap();
+        assert_eq!(first.computation, second.computation);
+        assert!(["accept", "timeout", "q0", "q1"].contains(&first.outcome.to_string().as_str()));
+        assert!(first.computation.len() <= 51);
+        let accepted = (0..20).any(|seed| {
+            context.simulation.strategy = Strategy::Random(seed);
+            tm.simulate(input.clone(), 50, &computer, &context, 0).unwrap().outcome
+                == computer::Outcome::Accept
+        });
+        assert!(accepted);
+    }
+
+    #[test]
+    fn test_depth_first_work() {
+        // writes a 0 or a 1 on every cell, forever
+        let mut builder = TuringMachine::builder()
+            .initial_state("q")
+            .accept_state("qa")
+            .blank_symbol("B")
+            .input_alphabet(&["0", "1"]);
+        for symbol in ["0", "1", "B"] {
+            for written in ["0", "1"] {
+                builder = builder.transition("q", &[symbol], "q", &[written], &[Direction::Right]);
+            }
+        }
+        let tm = builder.build().unwrap();
+        let computer = computer::Computer::new();
+        let mut context = computer::Server::new();
+        for strategy in [Strategy::Dfs, Strategy::Iddfs] {
+            context.simulation.strategy = strategy.clone();
+            let result = tm
+                .simulate(vec!["0".to_string()], 40, &computer, &context, 0)
+                .unwrap();
+            assert_eq!(result.outcome, computer::Outcome::Timeout, "{:?}", strategy);
+            assert_eq!(result.steps, 40, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_compiled_simulation() {
+        let mut tm = TuringMachine::new();
+        tm.blank_symbol = "B".to_string();
+        tm.initial_state = "qs".to_string();
+        tm.accept_state = "qa".to_string();
+        tm.tape_count = 2;
+        tm.states = vec![
+            "qs".to_string(),
+            "q0".to_string(),
+            "q1".to_string(),
+            "qa".to_string(),
+        ];
+        tm.input_alphabet = vec!["0".to_string(), "1".to_string()];
+        tm.tape_alphabet = vec!["0".to_string(), "1".to_string(), "B".to_string()];
+        let mut add = |state: &str, symbol: &str, new_state: &str, directions: [Direction; 2]| {
+            tm.add_transition(
+                state.to_string(),
+                vec![symbol.to_string(), if state == "q0" { "B" } else { symbol }.to_string()],
+                new_state.to_string(),
+                vec![symbol.to_string(), symbol.to_string()],
+                directions.to_vec(),
+            )
+        };
+        // copies the input on the second tape, then goes back past its start
+        add("qs", "B", "q0", [Direction::Right, Direction::Stay]);
+        add("q0", "0", "q0", [Direction::Right, Direction::Right]);
+        add("q0", "1", "q0", [Direction::Right, Direction::Right]);
+        add("q0", "B", "q1", [Direction::Left, Direction::Left]);
+        add("q1", "0", "q1", [Direction::Left, Direction::Left]);
+        add("q1", "1", "q1", [Direction::Left, Direction::Left]);
+        add("q1", "B", "qa", [Direction::Stay, Direction::Stay]);
+        assert!(tm.is_deterministic());
+        let single_tape = tm.clone().convert_multitape_to_singletape_tm().unwrap();
+
+        let computer = computer::Computer::new();
+        let context = computer::Server::new();
+        for machine in [&tm, &single_tape] {
+            for input in ["", "0", "0110", "B1"] {
+                let input: Vec<String> = input.chars().map(|c| c.to_string()).collect();
+                for max_steps in [1, 2, 5, 1000] {
+                    let compiled = machine
+                        .simulate(input.clone(), max_steps, &computer, &context, 0)
+                        .unwrap();
+                    let generic = machine
+                        .simulate_tree(input.clone(), max_steps, &computer, &context, 0)
+                        .unwrap();
+                    assert_eq!(compiled, generic, "{:?} {}", input, max_steps);
+                }
+            }
+        }
+
+        let mut context = computer::Server::new();
+        context.simulation.trace = false;
+        let input: Vec<String> = "0110".chars().map(|c| c.to_string()).collect();
+        let computer::SimulationResult { outcome: state, steps, computation, .. } =
+            tm.simulate(input, 1000, &computer, &context, 0).unwrap();
+        assert_eq!(state, computer::Outcome::Accept);
+        assert_eq!(steps, 12);
+        assert!(computation.is_empty());
+
+        context.simulation.block_size = 3;
+        let input: Vec<String> = "0110".chars().map(|c| c.to_string()).collect();
+        let accelerated = single_tape
+            .simulate(input.clone(), 1000, &computer, &context, 0)
+            .unwrap();
+        let generic = single_tape
+            .simulate_tree(input, 1000, &computer, &context, 0)
+            .unwrap();
+        assert_eq!(accelerated.outcome, generic.outcome);
+        assert_eq!(accelerated.head, generic.head);
+        assert_eq!(accelerated.tape, generic.tape);
+        assert_eq!(accelerated.steps, generic.steps);
+        assert!(accelerated.computation.is_empty());
+
+        context.simulation.detect_loops = true;
+        let input: Vec<String> = "0110".chars().map(|c| c.to_string()).collect();
+        assert!(single_tape
+            .simulate(input, 1000, &computer, &context, 0)
+            .is_err());
     }
 
     #[test]
@@ -2646,7 +3547,7 @@ mod tests {
             .simulate(vec![], 1000, &computer, &context, 0)
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
+        assert_eq!(multi_result.outcome, single_result.outcome);
 
         // Test input "0"
         let multi_result = tm
@@ -2671,7 +3572,7 @@ mod tests {
             )
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
+        assert_eq!(multi_result.outcome, single_result.outcome);
 
         // Test input "01"
         let multi_result = tm
@@ -2695,7 +3596,7 @@ mod tests {
             )
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
+        assert_eq!(multi_result.outcome, single_result.outcome);
     }
 
     #[test]
@@ -2756,8 +3657,8 @@ mod tests {
             .simulate(vec!["0".to_string()], 100, &computer, &context, 0)
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
-        assert_eq!(multi_result.0, "accept");
+        assert_eq!(multi_result.outcome, single_result.outcome);
+        assert_eq!(multi_result.outcome, computer::Outcome::Accept);
     }
 
     #[test]
@@ -2801,8 +3702,8 @@ mod tests {
             .simulate(vec!["1".to_string()], 100, &computer, &context, 1)
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
-        assert_eq!(multi_result.0, "accept");
+        assert_eq!(multi_result.outcome, single_result.outcome);
+        assert_eq!(multi_result.outcome, computer::Outcome::Accept);
     }
 
     #[test]
@@ -2853,8 +3754,8 @@ mod tests {
             .simulate(vec!["1".to_string()], 100, &computer, &context, 0)
             .unwrap();
 
-        assert_eq!(multi_result.0, single_result.0);
-        assert_eq!(multi_result.0, "accept");
+        assert_eq!(multi_result.outcome, single_result.outcome);
+        assert_eq!(multi_result.outcome, computer::Outcome::Accept);
     }
     #[test]
     fn test_is_ok() {
@@ -2914,4 +3815,162 @@ mod tests {
         tm7.initial_state = "qx".to_string();
         assert!(!tm7.is_ok());
     }
+
+    #[test]
+    fn test_subroutine_trace() {
+        let machine = |initial: &str, transitions: &[(&str, &str, &str, &str)]| {
+            let mut tm = TuringMachine::new();
+            tm.blank_symbol = "_".to_string();
+            tm.initial_state = initial.to_string();
+            tm.halt_state = "halt".to_string();
+            tm.input_alphabet = vec!["1".to_string()];
+            tm.tape_alphabet = vec!["_".to_string(), "1".to_string()];
+            tm.states = vec![initial.to_string(), "halt".to_string()];
+            for (state, read, new_state, write) in transitions {
+                if !tm.states.contains(&new_state.to_string()) {
+                    tm.states.push(new_state.to_string());
+                }
+                tm.add_transition(
+                    state.to_string(),
+                    vec![read.to_string()],
+                    new_state.to_string(),
+                    vec![write.to_string()],
+                    vec![Direction::Stay],
+                );
+            }
+            tm
+        };
+        // the state call runs the helper, which writes a 1 under the head
+        let main = machine("q0", &[("q0", "_", "call", "_"), ("call", "1", "halt", "1")]);
+        let helper = machine("h0", &[("h0", "_", "halt", "1")]);
+        let mut computer = computer::Computer {
+            element: computer::ComputingElem::Tm(Box::new(main)),
+            mapping: std::collections::HashMap::new(),
+        };
+        computer.add_mapping("call".to_string(), "helper".to_string());
+        let mut context = computer::Server::new();
+        context.add_computer(
+            "helper".to_string(),
+            computer::Computer {
+                element: computer::ComputingElem::Tm(Box::new(helper)),
+                mapping: std::collections::HashMap::new(),
+            },
+        );
+        let computer::SimulationResult {
+            outcome: state,
+            computation,
+            ..
+        } = computer.simulate("", 100, &context, 0).unwrap();
+        assert_eq!(state, computer::Outcome::Halt);
+        let lines: Vec<String> = computation
+            .iter()
+            .map(|event| format!("{} {}", event.depth(), event))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0 tm;q0;_",
+                "0 tm;call;_",
+                "1 tm;h0;_",
+                "1 tm;halt;1",
+                "0 tm;halt;1"
+            ]
+        );
+        match &computation[4] {
+            trace::TraceEvent::Tm { transition, .. } => {
+                assert_eq!(transition.as_ref().unwrap().to_string(), "call halt 1 1 S");
+            }
+            _ => panic!("expected a Turing machine event"),
+        }
+    }
+
+    #[test]
+    fn test_outcome() {
+        // moves right over the 1s and has no transition on the 0
+        let mut tm = TuringMachine::new();
+        tm.blank_symbol = "_".to_string();
+        tm.initial_state = "q0".to_string();
+        tm.input_alphabet = vec!["0".to_string(), "1".to_string()];
+        tm.tape_alphabet = vec!["_".to_string(), "0".to_string(), "1".to_string()];
+        tm.states = vec!["q0".to_string(), "q1".to_string()];
+        tm.add_transition(
+            "q0".to_string(),
+            vec!["_".to_string()],
+            "q1".to_string(),
+            vec!["_".to_string()],
+            vec![Direction::Right],
+        );
+        tm.add_transition(
+            "q1".to_string(),
+            vec!["1".to_string()],
+            "q1".to_string(),
+            vec!["1".to_string()],
+            vec![Direction::Right],
+        );
+        let computer = computer::Computer {
+            element: computer::ComputingElem::Tm(Box::new(tm.clone())),
+            mapping: std::collections::HashMap::new(),
+        };
+        let context = computer::Server::new();
+        let input: Vec<String> = "110".chars().map(|c| c.to_string()).collect();
+
+        let result = tm.simulate(input.clone(), 100, &computer, &context, 0).unwrap();
+        assert_eq!(result.outcome, computer::Outcome::Stuck("q1".to_string()));
+        assert_eq!(result.outcome.to_string(), "q1");
+        assert_eq!(result.head, 3);
+        assert_eq!(result.output(), "_110");
+
+        let result = tm.simulate(input, 2, &computer, &context, 0).unwrap();
+        assert_eq!(result.outcome, computer::Outcome::Timeout);
+        assert_eq!(result.outcome.to_string(), "timeout");
+        assert_eq!(result.steps, 2);
+    }
+
+    #[test]
+    fn test_builder() {
+        let tm = TuringMachine::builder()
+            .initial_state("q0")
+            .accept_state("qa")
+            .input_alphabet(&["a", "b"])
+            .transition("q0", &["_"], "q1", &["_"], &[Direction::Right])
+            .transition("q1", &["a"], "q1", &["x"], &[Direction::Right])
+            .transition("q1", &["_"], "qa", &["_"], &[Direction::Stay])
+            .build()
+            .unwrap();
+        assert_eq!(tm.states, vec!["q0", "q1", "qa"]);
+        assert_eq!(tm.tape_alphabet, vec!["_", "a", "b", "x"]);
+        assert_eq!(tm.transitions.len(), 3);
+        assert!(tm.is_ok());
+
+        let computer = computer::Computer {
+            element: computer::ComputingElem::Tm(Box::new(tm.clone())),
+            mapping: std::collections::HashMap::new(),
+        };
+        let context = computer::Server::new();
+        let input: Vec<String> = "aa".chars().map(|c| c.to_string()).collect();
+        let result = tm.simulate(input, 100, &computer, &context, 0).unwrap();
+        assert_eq!(result.outcome, computer::Outcome::Accept);
+        assert_eq!(result.output(), "_xx_");
+
+        let two_tapes = TuringMachine::builder()
+            .initial_state("q0")
+            .tapes(2)
+            .transition("q0", &["_", "_"], "q0", &["1", "1"], &[Direction::Right, Direction::Stay])
+            .build()
+            .unwrap();
+        assert_eq!(two_tapes.tape_count, 2);
+
+        assert!(TuringMachine::builder().build().is_err());
+        assert!(TuringMachine::builder()
+            .initial_state("q0")
+            .tapes(2)
+            .transition("q0", &["_"], "q0", &["1"], &[Direction::Right])
+            .build()
+            .is_err());
+        assert!(TuringMachine::builder()
+            .initial_state("q0")
+            .input_alphabet(&["_"])
+            .build()
+            .is_err());
+    }
 }
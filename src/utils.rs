@@ -129,7 +129,7 @@ pub fn uint2str(n: usize, alphabet: Vec<String>) -> Result<String, String> {
         let y = x[1..m].to_string();
         p += 1;
         let bitnum = std::cmp::max(1, (alphabet.len() as f64).log2().ceil() as usize);
-        let padding = if y.len() % bitnum != 0 {
+        let padding = if !y.len().is_multiple_of(bitnum) {
             bitnum - (y.len() % bitnum)
         } else {
             0
@@ -156,7 +156,7 @@ pub fn uint2str(n: usize, alphabet: Vec<String>) -> Result<String, String> {
 /// A `Result` containing the string representation if successful, or an error message if the input is invalid.
 pub fn bin2alphabet(s: String, alphabet: Vec<String>) -> Result<String, String> {
     let bitnum: usize = std::cmp::max(1, (alphabet.len() as f64).log2().ceil() as usize);
-    if s.is_empty() || (s.len() % bitnum != 0) {
+    if s.is_empty() || !s.len().is_multiple_of(bitnum) {
         return Err(format!("wrong input string length: {}", s.len()));
     }
     let mut result = String::new();
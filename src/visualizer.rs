@@ -0,0 +1,513 @@
+//! # Visualizer Module
+//!
+//! This module provides a full-screen terminal view of a running computation, drawn with ANSI
+//! escape sequences.
+//!
+//! ## Main Structures
+//!
+//! - **Visualizer**: The computation shown, driven by a debugger, with the state of its controls.
+//!
+//! ## Key Features
+//!
+//! - **Turing Machines**: Every tape, with the cell under its head highlighted, the current state
+//!   and the transition just fired, for single-tape and multi-tape machines.
+//! - **RAM Machines**: The registers, the input with its head, the output and the memory, with
+//!   the instruction about to be executed highlighted.
+//! - **Lambda Expressions**: The expression reduced so far.
+//! - **Controls**: `space` runs or pauses the computation, `n` makes a step, `b` goes back one
+//!   step, `+` and `-` change the speed and `q` (or `Ctrl-C`) quits.
+//!
+//! The keys are read without waiting for a new line by setting the terminal with `stty`, which
+//! is restored when the visualizer quits, fails or panics. `Ctrl-C` is read as a key instead of
+//! interrupting the process, so that the terminal is never left in that setting.
+//!
+//! ## Author
+//!
+//! - dp
+//!
+//! # License
+//!
+//! This project is licensed under the MIT License. See the LICENSE file for details.
+
+use crate::computer;
+use crate::debugger;
+use crate::utils;
+use std::io::{Read, Write};
+
+/// The delays between two steps, in milliseconds, from the slowest speed to the fastest one.
+const DELAYS: [u64; 8] = [1000, 500, 250, 100, 50, 20, 5, 0];
+
+/// The key sent by `Ctrl-C`, which quits the visualizer.
+const CTRL_C: u8 = 3;
+
+/// The escape sequence highlighting the following characters.
+const HIGHLIGHT: &str = "\x1b[7m";
+
+/// The escape sequence writing the following characters in bold.
+const BOLD: &str = "\x1b[1m";
+
+/// The escape sequence resetting the style of the following characters.
+const RESET: &str = "\x1b[0m";
+
+/// A full-screen view of a computation.
+///
+/// # Fields
+/// * `debugger` - The debugger making the steps of the computation
+/// * `running` - Whether the computation makes steps on its own
+/// * `speed` - The index of the delay between two steps in `DELAYS`
+/// * `status` - The reason why the computation stopped, empty while it can continue
+///
+pub struct Visualizer<'a> {
+    pub debugger: debugger::Debugger<'a>,
+    pub running: bool,
+    pub speed: usize,
+    pub status: String,
+}
+
+/// Returns the cells of a tape around its head fitting in a width, with the head highlighted.
+fn tape_window(cells: &[String], head: usize, width: usize) -> String {
+    let mut start = head;
+    let mut end = head + 1;
+    let mut used = cells[head].chars().count();
+    loop {
+        let mut grown = false;
+        if end < cells.len() && used + cells[end].chars().count() <= width {
+            used += cells[end].chars().count();
+            end += 1;
+            grown = true;
+        }
+        if start > 0 && used + cells[start - 1].chars().count() <= width {
+            start -= 1;
+            used += cells[start].chars().count();
+            grown = true;
+        }
+        if !grown {
+            break;
+        }
+    }
+    format!(
+        "{}{}{}{}{}",
+        cells[start..head].concat(),
+        HIGHLIGHT,
+        cells[head],
+        RESET,
+        cells[head + 1..end].concat()
+    )
+}
+
+impl<'a> Visualizer<'a> {
+    /// Creates a paused view of the computation of a debugger.
+    pub fn new(debugger: debugger::Debugger<'a>) -> Visualizer<'a> {
+        Visualizer {
+            debugger,
+            running: false,
+            speed: 3,
+            status: String::new(),
+        }
+    }
+
+    /// Makes a step, recording why the computation stopped if it cannot continue.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the step was made or the computation stopped
+    /// * `Err(String)` - An error message if the step fails
+    fn step(&mut self) -> Result<(), String> {
+        if !self.debugger.step()? {
+            self.running = false;
+            self.status = self.debugger.stop_reason();
+        }
+        Ok(())
+    }
+
+    /// Handles a key pressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key pressed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - If the visualizer continues
+    /// * `Ok(false)` - If the key quits the visualizer
+    /// * `Err(String)` - An error message if a step fails
+    pub fn key(&mut self, key: u8) -> Result<bool, String> {
+        match key {
+            b'q' | CTRL_C => return Ok(false),
+            b' ' => self.running = !self.running && self.status.is_empty(),
+            b'n' => {
+                self.running = false;
+                self.step()?;
+            }
+            b'b' => {
+                self.running = false;
+                if self.debugger.back(1) > 0 {
+                    self.status.clear();
+                }
+            }
+            b'+' => self.speed = (self.speed + 1).min(DELAYS.len() - 1),
+            b'-' => self.speed = self.speed.saturating_sub(1),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Makes a step if the computation is running.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the step was made, or none was due
+    /// * `Err(String)` - An error message if the step fails
+    pub fn tick(&mut self) -> Result<(), String> {
+        if self.running {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the delay before the next step.
+    pub fn delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(DELAYS[self.speed])
+    }
+
+    /// Draws the current configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of columns of the terminal
+    /// * `height` - The number of lines of the terminal
+    ///
+    /// # Returns
+    ///
+    /// The escape sequences and the text redrawing the whole screen.
+    pub fn render(&self, width: usize, height: usize) -> String {
+        let status = if !self.status.is_empty() {
+            self.status.clone()
+        } else if self.running {
+            "running".to_string()
+        } else {
+            "paused".to_string()
+        };
+        let mut lines = vec![format!(
+            "{}step {}{} | {} | speed {}/{}",
+            BOLD,
+            self.debugger.steps(),
+            RESET,
+            status,
+            self.speed + 1,
+            DELAYS.len()
+        )];
+        match self.debugger.current() {
+            debugger::Configuration::Tm { state, tapes, .. } => {
+                lines.push(format!("state: {}{}{}", BOLD, state, RESET));
+                lines.push(format!(
+                    "fired: {}",
+                    self.debugger.fired().unwrap_or_default()
+                ));
+                lines.push(String::new());
+                for (index, tape) in tapes.iter().enumerate() {
+                    let label = format!("tape {}: ", index + 1);
+                    let cells = width.saturating_sub(label.len()).max(1);
+                    lines.push(label + &tape_window(&tape.tape, tape.head, cells));
+                }
+            }
+            debugger::Configuration::Ram(configuration, _) => {
+                let pc = utils::bin2int(configuration.pc.clone()).unwrap_or(0);
+                lines.push(format!(
+                    "pc: {}{}{}  acc: {}  mov: {}",
+                    BOLD, pc, RESET, configuration.acc, configuration.mov
+                ));
+                lines.push(format!(
+                    "fired: {}",
+                    self.debugger.fired().unwrap_or_default()
+                ));
+                let input: Vec<String> = configuration
+                    .input
+                    .chars()
+                    .map(|c| c.to_string())
+                    .chain(std::iter::once(" ".to_string()))
+                    .collect();
+                let head = configuration.input_head.min(input.len() - 1);
+                lines.push(
+                    "input: ".to_string()
+                        + &tape_window(&input, head, width.saturating_sub(7).max(1)),
+                );
+                lines.push(format!("output: {}", configuration.out));
+                lines.push(String::new());
+                lines.push("memory:".to_string());
+                let mut memory: Vec<(i32, &String)> = configuration
+                    .memory
+                    .iter()
+                    .filter_map(|(address, value)| {
+                        utils::bin2int(address.clone()).ok().map(|a| (a, value))
+                    })
+                    .collect();
+                memory.sort();
+                let rows = height.saturating_sub(lines.len() + 3).max(1);
+                for (address, value) in memory.iter().take(rows) {
+                    let cell = format!("  {:>6}: {}", address, value);
+                    if *address == pc {
+                        lines.push(format!("{}{}{}", HIGHLIGHT, cell, RESET));
+                    } else {
+                        lines.push(cell);
+                    }
+                }
+                if memory.len() > rows {
+                    lines.push(format!("  ... {} more cells", memory.len() - rows));
+                }
+            }
+            debugger::Configuration::Lambda(expression) => {
+                lines.push(String::new());
+                let text: Vec<char> = expression.to_string().chars().collect();
+                for chunk in text.chunks(width.max(1)) {
+                    lines.push(chunk.iter().collect());
+                }
+            }
+        }
+        lines.push(String::new());
+        lines.push("space run/pause | n step | b back | + faster | - slower | q quit".to_string());
+        // overwrites the previous screen from its top, clearing what is left of every line and
+        // the lines below, so that it does not flicker
+        let mut screen = "\x1b[H".to_string();
+        for line in lines {
+            screen += &line;
+            screen += "\x1b[K\n";
+        }
+        screen + "\x1b[J"
+    }
+}
+
+/// Reads the size of the terminal with `stty size`, once when the visualizer starts, since
+/// running a command at every frame would slow the fastest speeds down.
+///
+/// # Returns
+///
+/// The width and the height of the terminal, 80 by 24 if they cannot be read.
+fn terminal_size() -> (usize, usize) {
+    let size = stty(&["size"]).unwrap_or_default();
+    let mut dimensions = size.split_whitespace().map(|d| d.parse::<usize>());
+    match (dimensions.next(), dimensions.next()) {
+        (Some(Ok(height)), Some(Ok(width))) if height > 0 && width > 0 => (width, height),
+        _ => (80, 24),
+    }
+}
+
+/// Runs the terminal setting command `stty` on the standard input.
+fn stty(arguments: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("stty")
+        .args(arguments)
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err("the visualizer needs an interactive terminal".to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The terminal set up for the visualizer, restored when dropped, even by a panic.
+///
+/// # Fields
+///
+/// * `saved` - The setting of the terminal before the visualizer, as printed by `stty -g`
+///
+struct Terminal {
+    saved: String,
+}
+
+impl Terminal {
+    /// Reads the keys without waiting for a new line nor echoing them, with `Ctrl-C` read as a
+    /// key, and switches to the alternate screen with the cursor hidden.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Terminal)` - The guard restoring the terminal
+    /// * `Err(String)` - An error message if the standard input is not a terminal
+    fn setup() -> Result<Terminal, String> {
+        let saved = stty(&["-g"])?;
+        let terminal = Terminal { saved };
+        stty(&["-icanon", "-echo", "-isig", "min", "0", "time", "0"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Shows a computation full screen until the user quits, then prints its last configuration.
+///
+/// # Arguments
+///
+/// * `computer` - The computer to show, a Turing machine, a RAM machine or a lambda expression
+/// * `context` - The context of the computer
+/// * `input` - The input of the computation
+/// * `max_steps` - The maximum number of steps of the computation
+///
+/// # Returns
+///
+/// * `Ok(())` - If the user quit the visualizer
+/// * `Err(String)` - An error message if the terminal cannot be set or a step fails
+pub fn run(
+    computer: &computer::Computer,
+    context: &computer::Server,
+    input: &str,
+    max_steps: usize,
+) -> Result<(), String> {
+    let mut visualizer = Visualizer::new(debugger::Debugger::new(
+        computer, context, input, max_steps,
+    )?);
+    let terminal = Terminal::setup()?;
+    let (width, height) = terminal_size();
+    let result = (|| -> Result<(), String> {
+        let mut stdout = std::io::stdout();
+        let mut keys = [0u8; 16];
+        loop {
+            let screen = visualizer.render(width, height);
+            stdout
+                .write_all(screen.as_bytes())
+                .and_then(|_| stdout.flush())
+                .map_err(|e| e.to_string())?;
+            let read = std::io::stdin()
+                .read(&mut keys)
+                .map_err(|e| e.to_string())?;
+            for key in keys[..read].iter() {
+                if !visualizer.key(*key)? {
+                    return Ok(());
+                }
+            }
+            if visualizer.running {
+                std::thread::sleep(visualizer.delay());
+                visualizer.tick()?;
+            } else {
+                std::thread::sleep(std::time::Duration::from_millis(30));
+            }
+        }
+    })();
+    drop(terminal);
+    println!("{}", visualizer.debugger.describe());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ram_machine;
+    use crate::turing_machine;
+
+    #[test]
+    fn test_tape_window() {
+        let cells: Vec<String> = "abcdefgh".chars().map(|c| c.to_string()).collect();
+        assert_eq!(tape_window(&cells, 0, 3), "\x1b[7ma\x1b[0mbc");
+        assert_eq!(tape_window(&cells, 4, 3), "d\x1b[7me\x1b[0mf");
+        assert_eq!(tape_window(&cells, 7, 100), "abcdefg\x1b[7mh\x1b[0m");
+    }
+
+    #[test]
+    fn test_multitape_machine() {
+        let mut tm = turing_machine::TuringMachine::new();
+        tm.blank_symbol = "_".to_string();
+        tm.initial_state = "q0".to_string();
+        tm.halt_state = "qh".to_string();
+        tm.states = vec!["q0".to_string(), "qh".to_string()];
+        tm.input_alphabet = vec!["a".to_string()];
+        tm.tape_alphabet = vec!["_".to_string(), "a".to_string()];
+        tm.tape_count = 2;
+        // copies the input on the second tape
+        for (state, read, new_state, write) in [
+            ("q0", ["_", "_"], "q0", ["_", "_"]),
+            ("q0", ["a", "_"], "q0", ["a", "a"]),
+        ] {
+            tm.add_transition(
+                state.to_string(),
+                read.iter().map(|s| s.to_string()).collect(),
+                new_state.to_string(),
+                write.iter().map(|s| s.to_string()).collect(),
+                vec![turing_machine::Direction::Right; 2],
+            );
+        }
+        let c = computer::Computer {
+            element: computer::ComputingElem::Tm(Box::new(tm)),
+            mapping: std::collections::HashMap::new(),
+        };
+        let context = computer::Server::new();
+        let debugger = debugger::Debugger::new(&c, &context, "aa", 3).unwrap();
+        let mut visualizer = Visualizer::new(debugger);
+        let screen = visualizer.render(80, 24);
+        assert!(screen.contains("step 0\x1b[0m | paused"));
+        assert!(screen.contains("tape 1: \x1b[7m_\x1b[0maa"));
+        assert!(screen.contains("tape 2: \x1b[7m_\x1b[0m"));
+
+        assert!(visualizer.key(b'n').unwrap());
+        assert!(visualizer.key(b' ').unwrap());
+        visualizer.tick().unwrap();
+        let screen = visualizer.render(80, 24);
+        assert!(screen.contains("step 2\x1b[0m | running"));
+        assert!(screen.contains("fired: q0 q0 a _ a a R R"));
+        assert!(screen.contains("tape 1: _a\x1b[7ma\x1b[0m"));
+        assert!(screen.contains("tape 2: _a\x1b[7m_\x1b[0m"));
+        visualizer.tick().unwrap();
+        visualizer.tick().unwrap();
+        assert!(!visualizer.running);
+        assert_eq!(visualizer.status, "maximum number of steps reached");
+        assert!(visualizer.key(b' ').unwrap());
+        assert!(!visualizer.running);
+        assert!(visualizer.key(b'b').unwrap());
+        assert!(visualizer.status.is_empty());
+        assert_eq!(visualizer.debugger.steps(), 2);
+
+        assert!(visualizer.key(b'-').unwrap());
+        assert_eq!(visualizer.delay(), std::time::Duration::from_millis(250));
+        for _ in 0..10 {
+            visualizer.key(b'+').unwrap();
+        }
+        assert_eq!(visualizer.delay(), std::time::Duration::ZERO);
+        assert!(!visualizer.key(b'q').unwrap());
+        assert!(!visualizer.key(CTRL_C).unwrap());
+    }
+
+    #[test]
+    fn test_ram_machine() {
+        let instruction = |opcode: &str, operand: &str| ram_machine::Instruction {
+            opcode: opcode.to_string(),
+            operand: operand.to_string(),
+            label: "".to_string(),
+        };
+        // reads a bit of the input, writes it and halts
+        let ram = ram_machine::RamMachine {
+            instructions: vec![
+                instruction("0000", "1"),
+                instruction("0011", ""),
+                instruction("1011", ""),
+            ],
+            labels_map: std::collections::HashMap::new(),
+            translation_map: std::collections::HashMap::new(),
+        };
+        let c = computer::Computer {
+            element: computer::ComputingElem::Ram(Box::new(ram)),
+            mapping: std::collections::HashMap::new(),
+        };
+        let context = computer::Server::new();
+        let debugger = debugger::Debugger::new(&c, &context, "1", 100).unwrap();
+        let mut visualizer = Visualizer::new(debugger);
+        visualizer.key(b'n').unwrap();
+        visualizer.key(b'n').unwrap();
+        let screen = visualizer.render(80, 24);
+        assert!(screen.contains("pc: \x1b[1m2\x1b[0m  acc: 1"));
+        assert!(screen.contains("fired: W"));
+        assert!(screen.contains("input: \x1b[7m1\x1b[0m "));
+        assert!(screen.contains("output: 1"));
+        assert!(screen.contains("       0: 00001"));
+        assert!(screen.contains("\x1b[7m       2: 1011\x1b[0m"));
+
+        // the lines left for the memory
+        let screen = visualizer.render(80, 11);
+        assert!(screen.contains("       0: 00001"));
+        assert!(screen.contains("  ... 2 more cells"));
+    }
+}